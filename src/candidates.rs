@@ -0,0 +1,123 @@
+use reeds_shepp_lib::{PATH_FNS, Path, Pose, path_length, utils};
+
+use crate::path::path_end_pose;
+
+/// How far a candidate may end from the goal and still count as reaching it.
+const MAX_END_ERROR: f64 = 1e-6;
+
+/// One `PATH_FNS` entry evaluated under a reflect/timeflip transform.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub family: usize,
+    pub reflect: bool,
+    pub timeflip: bool,
+    pub path: Path,
+    pub length: f64,
+}
+
+/// The goal a path function receives for `relative`, the end pose in the
+/// start's frame, under a reflect/timeflip transform.
+pub fn transform_goal(relative: &Pose, reflect: bool, timeflip: bool) -> Pose {
+    let mut x = relative.x;
+    let mut y = relative.y;
+    let mut theta_degree = relative.theta_degree;
+    if reflect {
+        y = -y;
+        theta_degree = -theta_degree;
+    }
+    if timeflip {
+        x = -x;
+        theta_degree = if reflect { theta_degree } else { -theta_degree };
+    }
+    Pose { x, y, theta_degree }
+}
+
+/// Name of a reflect/timeflip transform, `none` for the identity.
+pub fn transform_name(reflect: bool, timeflip: bool) -> &'static str {
+    match (reflect, timeflip) {
+        (false, false) => "none",
+        (true, false) => "reflect",
+        (false, true) => "timeflip",
+        (true, true) => "reflect+timeflip",
+    }
+}
+
+/// The transform's name with a leading space, to follow a family label;
+/// empty for the identity.
+pub fn transform_suffix(reflect: bool, timeflip: bool) -> String {
+    if reflect || timeflip {
+        format!(" {}", transform_name(reflect, timeflip))
+    } else {
+        String::new()
+    }
+}
+
+/// Evaluates a single path family between two poses. Returns an empty path
+/// when the family is infeasible or `family` is out of range.
+pub fn evaluate_family(
+    start: &Pose,
+    end: &Pose,
+    family: usize,
+    reflect: bool,
+    timeflip: bool,
+) -> Path {
+    let goal = transform_goal(&utils::change_of_basis(start, end), reflect, timeflip);
+    let Some(path_fn) = PATH_FNS.get(family) else {
+        return Path::new();
+    };
+    let mut path = path_fn(goal.x, goal.y, goal.theta_degree);
+    if timeflip {
+        path = reeds_shepp_lib::timeflip(path);
+    }
+    if reflect {
+        path = reeds_shepp_lib::reflect(path);
+    }
+    path
+}
+
+/// Every family under every transform, skipping the infeasible ones.
+pub fn all_candidates(start: &Pose, end: &Pose) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    for family in 0..PATH_FNS.len() {
+        for (reflect, timeflip) in [(false, false), (false, true), (true, false), (true, true)] {
+            let path = evaluate_family(start, end, family, reflect, timeflip);
+            if path.is_empty() {
+                continue;
+            }
+            let length = path_length(&path);
+            candidates.push(Candidate {
+                family,
+                reflect,
+                timeflip,
+                path,
+                length,
+            });
+        }
+    }
+    candidates
+}
+
+pub fn shortest_candidate(candidates: &[Candidate]) -> Option<&Candidate> {
+    candidates
+        .iter()
+        .min_by(|a, b| a.length.total_cmp(&b.length))
+}
+
+/// Whether driving `path` from `start` really ends on `end`. Some transforms
+/// of some families produce paths that do not.
+pub fn reaches_goal(start: &Pose, end: &Pose, path: &Path) -> bool {
+    poses_match(&path_end_pose(start, path), end)
+}
+
+/// Whether two poses agree in position and heading up to solver round-off.
+pub fn poses_match(a: &Pose, b: &Pose) -> bool {
+    let heading_error = utils::normalize_angle_rad((a.theta_degree - b.theta_degree).to_radians());
+    (a.x - b.x).hypot(a.y - b.y) < MAX_END_ERROR && heading_error.abs() < MAX_END_ERROR
+}
+
+/// [`all_candidates`] without the ones that miss the goal.
+pub fn reaching_candidates(start: &Pose, end: &Pose) -> Vec<Candidate> {
+    let mut candidates = all_candidates(start, end);
+    candidates.retain(|c| reaches_goal(start, end, &c.path));
+    candidates
+}
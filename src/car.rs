@@ -0,0 +1,38 @@
+use reeds_shepp_lib::Pose;
+
+// --- Constants ---
+pub const CAR_LENGTH: f64 = 1.0;
+pub const CAR_WIDTH: f64 = 0.6;
+pub const HEADLIGHT_HIT_RADIUS: f64 = 0.24;
+
+/// World position of the headlight, which sits on the front edge of the car.
+pub fn headlight_world_pos(pose: &Pose) -> (f64, f64) {
+    let angle_rad = pose.theta_degree.to_radians();
+    let off = CAR_LENGTH / 2.0;
+    (
+        pose.x + off * angle_rad.cos(),
+        pose.y + off * angle_rad.sin(),
+    )
+}
+
+/// Expresses a world position in the car's local frame (x forward, y left).
+pub fn to_local(world_pos: (f64, f64), pose: &Pose) -> (f64, f64) {
+    let angle_rad = pose.theta_degree.to_radians();
+    let cos_a = angle_rad.cos();
+    let sin_a = angle_rad.sin();
+    let dx = world_pos.0 - pose.x;
+    let dy = world_pos.1 - pose.y;
+    (dx * cos_a + dy * sin_a, (-dx * sin_a) + dy * cos_a)
+}
+
+pub fn check_body_hit(world_click_pos: (f64, f64), pose: &Pose) -> bool {
+    let (local_x, local_y) = to_local(world_click_pos, pose);
+    local_x.abs() <= CAR_LENGTH / 2.0 && local_y.abs() <= CAR_WIDTH / 2.0
+}
+
+pub fn check_headlight_hit(world_click_pos: (f64, f64), pose: &Pose) -> bool {
+    let (hx, hy) = headlight_world_pos(pose);
+    let dx = world_click_pos.0 - hx;
+    let dy = world_click_pos.1 - hy;
+    dx * dx + dy * dy < HEADLIGHT_HIT_RADIUS * HEADLIGHT_HIT_RADIUS
+}
@@ -0,0 +1,326 @@
+//! Continuous-curvature (CC) variant of a Reeds-Shepp path.
+//!
+//! Every circular arc is replaced by a CC-turn of some deflection: a
+//! clothoid ramping the curvature up to `1 / TURNING_RADIUS` at the given
+//! sharpness, an optional circular arc, and a clothoid back down to zero.
+//! Curvature is therefore zero at every segment junction and cusp and
+//! continuous everywhere else.
+//!
+//! A CC-turn ends on a wider circle than the classic arc it replaces, so
+//! keeping the classic params would miss the goal. [`solve_cc_path`] keeps
+//! the word of the classic path and corrects its params with Newton's method
+//! until the CC path ends on the goal pose.
+
+use reeds_shepp_lib::{Gear, Path, Pose, Steering, utils};
+
+use crate::path::{PathSample, TURNING_RADIUS, path_arc_length, segment_length};
+
+// --- Constants ---
+pub const DEFAULT_MAX_STEERING_RATE: f64 = 2.0;
+pub const MIN_STEERING_RATE: f64 = 0.2;
+pub const MAX_STEERING_RATE: f64 = 10.0;
+/// Simpson intervals per clothoid, fixed so the end pose is a smooth
+/// function of the params.
+const CLOTHOID_INTERVALS: usize = 256;
+const MIN_PIECE_LENGTH: f64 = 1e-10;
+/// Residual of the end pose, in length units and radians, counted as on goal.
+pub const END_TOLERANCE: f64 = 1e-9;
+const MAX_ITERATIONS: usize = 50;
+const DIFFERENCE_STEP: f64 = 1e-7;
+/// Params are kept above this so no element of the word disappears.
+const MIN_PARAM: f64 = 1e-6;
+
+/// A stretch of constant sharpness: `curvature(u) = kappa0 + sharpness * u`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CcPiece {
+    pub segment: usize,
+    pub gear: Gear,
+    pub kappa0: f64,
+    pub sharpness: f64,
+    pub length: f64,
+}
+
+impl CcPiece {
+    fn curvature_at(&self, u: f64) -> f64 {
+        self.kappa0 + self.sharpness * u
+    }
+
+    fn heading_change(&self, u: f64) -> f64 {
+        let g = match self.gear {
+            Gear::Forward => 1.0,
+            Gear::Backwards => -1.0,
+        };
+        g * (self.kappa0 * u + 0.5 * self.sharpness * u * u)
+    }
+}
+
+/// Converts `path` into CC pieces. `max_steering_rate` is the largest
+/// curvature change per unit of arc length the vehicle can achieve.
+pub fn cc_pieces(path: &Path, max_steering_rate: f64) -> Vec<CcPiece> {
+    let sigma = max_steering_rate.max(MIN_STEERING_RATE);
+    let kappa_max = 1.0 / TURNING_RADIUS;
+    let mut pieces = Vec::new();
+    for (segment, element) in path.iter().enumerate() {
+        let length = segment_length(element);
+        if length < MIN_PIECE_LENGTH {
+            continue;
+        }
+        let sign = match element.steering {
+            Steering::Straight => {
+                pieces.push(CcPiece {
+                    segment,
+                    gear: element.gear,
+                    kappa0: 0.0,
+                    sharpness: 0.0,
+                    length,
+                });
+                continue;
+            }
+            Steering::Left => 1.0,
+            Steering::Right => -1.0,
+        };
+        let deflection = length * kappa_max;
+        let ramps_deflection = kappa_max * kappa_max / sigma;
+        let (kappa_peak, arc_length) = if deflection >= ramps_deflection {
+            (kappa_max, (deflection - ramps_deflection) / kappa_max)
+        } else {
+            ((sigma * deflection).sqrt(), 0.0)
+        };
+        let ramp = kappa_peak / sigma;
+        pieces.push(CcPiece {
+            segment,
+            gear: element.gear,
+            kappa0: 0.0,
+            sharpness: sign * sigma,
+            length: ramp,
+        });
+        if arc_length > 0.0 {
+            pieces.push(CcPiece {
+                segment,
+                gear: element.gear,
+                kappa0: sign * kappa_peak,
+                sharpness: 0.0,
+                length: arc_length,
+            });
+        }
+        pieces.push(CcPiece {
+            segment,
+            gear: element.gear,
+            kappa0: sign * kappa_peak,
+            sharpness: -sign * sigma,
+            length: ramp,
+        });
+    }
+    pieces
+}
+
+pub fn cc_length(pieces: &[CcPiece]) -> f64 {
+    pieces.iter().map(|p| p.length).sum()
+}
+
+/// Integrates one piece from `(x, y, theta)` over `[u0, u1]`, in closed
+/// form for lines and arcs and with Simpson's rule for clothoids.
+fn integrate(piece: &CcPiece, pose: (f64, f64, f64), u0: f64, u1: f64) -> (f64, f64, f64) {
+    let g = match piece.gear {
+        Gear::Forward => 1.0,
+        Gear::Backwards => -1.0,
+    };
+    let theta_base = pose.2 - piece.heading_change(u0);
+    let theta_end = theta_base + piece.heading_change(u1);
+    let (x, y) = if piece.sharpness != 0.0 {
+        let h = (u1 - u0) / CLOTHOID_INTERVALS as f64;
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for i in 0..=CLOTHOID_INTERVALS {
+            let weight = match i {
+                0 => 1.0,
+                _ if i == CLOTHOID_INTERVALS => 1.0,
+                _ if i % 2 == 1 => 4.0,
+                _ => 2.0,
+            };
+            let theta = theta_base + piece.heading_change(u0 + i as f64 * h);
+            sx += weight * theta.cos();
+            sy += weight * theta.sin();
+        }
+        (pose.0 + g * h / 3.0 * sx, pose.1 + g * h / 3.0 * sy)
+    } else if piece.kappa0.abs() > 0.0 {
+        let k = piece.kappa0;
+        (
+            pose.0 + (theta_end.sin() - pose.2.sin()) / k,
+            pose.1 + (pose.2.cos() - theta_end.cos()) / k,
+        )
+    } else {
+        let d = g * (u1 - u0);
+        (pose.0 + d * pose.2.cos(), pose.1 + d * pose.2.sin())
+    };
+    (x, y, utils::normalize_angle_rad(theta_end))
+}
+
+/// Pose reached by driving `pieces` from `start_pose`.
+pub fn cc_end_pose(start_pose: &Pose, pieces: &[CcPiece]) -> Pose {
+    let start = (
+        start_pose.x,
+        start_pose.y,
+        utils::normalize_angle_rad(start_pose.theta_degree.to_radians()),
+    );
+    let (x, y, theta) = pieces.iter().fold(start, |pose, piece| {
+        integrate(piece, pose, 0.0, piece.length)
+    });
+    Pose {
+        x,
+        y,
+        theta_degree: theta.to_degrees(),
+    }
+}
+
+/// Position and heading error of the CC version of `path` at `end`.
+fn end_residual(start: &Pose, end: &Pose, path: &Path, max_steering_rate: f64) -> [f64; 3] {
+    let reached = cc_end_pose(start, &cc_pieces(path, max_steering_rate));
+    [
+        reached.x - end.x,
+        reached.y - end.y,
+        utils::normalize_angle_rad((reached.theta_degree - end.theta_degree).to_radians()),
+    ]
+}
+
+/// Solves the 3x3 system `m * v = b` by Cramer's rule.
+fn solve3(m: [[f64; 3]; 3], b: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(m);
+    if !d.is_normal() {
+        return None;
+    }
+    let mut v = [0.0; 3];
+    for (col, value) in v.iter_mut().enumerate() {
+        let mut mc = m;
+        for row in 0..3 {
+            mc[row][col] = b[row];
+        }
+        *value = det(mc) / d;
+    }
+    Some(v)
+}
+
+/// The word of `path` with params corrected so that its CC turns end on
+/// `end`, or `None` when Newton's method does not get there with every
+/// param staying positive. Degenerate elements are dropped first.
+pub fn solve_cc_path(
+    start: &Pose,
+    end: &Pose,
+    path: &Path,
+    max_steering_rate: f64,
+) -> Option<Path> {
+    let mut path: Path = path
+        .iter()
+        .filter(|e| segment_length(e) >= MIN_PIECE_LENGTH)
+        .copied()
+        .collect();
+    for _ in 0..MAX_ITERATIONS {
+        let r = end_residual(start, end, &path, max_steering_rate);
+        if r.iter().all(|v| v.abs() < END_TOLERANCE) {
+            return Some(path);
+        }
+        if path.len() < 3 {
+            return None;
+        }
+        // Minimum norm Newton step `-J^T (J J^T)^-1 r` with a forward
+        // difference Jacobian, one column per param.
+        let columns: Vec<[f64; 3]> = (0..path.len())
+            .map(|i| {
+                let mut moved = path.clone();
+                moved[i].param += DIFFERENCE_STEP;
+                let rm = end_residual(start, end, &moved, max_steering_rate);
+                [0, 1, 2].map(|k| (rm[k] - r[k]) / DIFFERENCE_STEP)
+            })
+            .collect();
+        let mut jjt = [[0.0; 3]; 3];
+        for c in &columns {
+            for (a, row) in jjt.iter_mut().enumerate() {
+                for (b, value) in row.iter_mut().enumerate() {
+                    *value += c[a] * c[b];
+                }
+            }
+        }
+        let y = solve3(jjt, r)?;
+        let delta: Vec<f64> = columns
+            .iter()
+            .map(|c| -(c[0] * y[0] + c[1] * y[1] + c[2] * y[2]))
+            .collect();
+        let mut scale = 1.0;
+        while path
+            .iter()
+            .zip(&delta)
+            .any(|(e, d)| e.param + scale * d < MIN_PARAM)
+        {
+            scale *= 0.5;
+            if scale < 1e-6 {
+                return None;
+            }
+        }
+        for (e, d) in path.iter_mut().zip(&delta) {
+            e.param += scale * d;
+        }
+    }
+    None
+}
+
+/// Samples the CC path every `step` of arc length, including every piece
+/// boundary and the final pose.
+pub fn sample_cc_path(start_pose: &Pose, pieces: &[CcPiece], step: f64) -> Vec<PathSample> {
+    let mut samples = Vec::new();
+    let mut pose = (
+        start_pose.x,
+        start_pose.y,
+        utils::normalize_angle_rad(start_pose.theta_degree.to_radians()),
+    );
+    let mut s = 0.0;
+    let sample = |piece: &CcPiece, pose: (f64, f64, f64), u: f64, s: f64| PathSample {
+        x: pose.0,
+        y: pose.1,
+        theta: pose.2,
+        gear: piece.gear,
+        curvature: piece.curvature_at(u),
+        s,
+        segment: piece.segment,
+    };
+    for piece in pieces {
+        let n_steps = (piece.length / step).ceil().max(1.0) as usize;
+        let mut u = 0.0;
+        for i in 0..n_steps {
+            let next_u = piece.length * i as f64 / n_steps as f64;
+            pose = integrate(piece, pose, u, next_u);
+            u = next_u;
+            samples.push(sample(piece, pose, u, s + u));
+        }
+        pose = integrate(piece, pose, u, piece.length);
+        s += piece.length;
+    }
+    if let Some(last) = pieces.last() {
+        samples.push(sample(last, pose, last.length, s));
+    }
+    samples
+}
+
+/// Summary of a CC path compared with its classic counterpart. Only built
+/// for CC paths that end on the goal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CcComparison {
+    pub classic_length: f64,
+    pub cc_length: f64,
+}
+
+impl CcComparison {
+    pub fn length_overhead(&self) -> f64 {
+        self.cc_length - self.classic_length
+    }
+}
+
+pub fn compare(classic: &Path, pieces: &[CcPiece]) -> CcComparison {
+    CcComparison {
+        classic_length: path_arc_length(classic),
+        cc_length: cc_length(pieces),
+    }
+}
@@ -0,0 +1,187 @@
+//! Geometric construction behind the CSC and CCC families: the left and
+//! right turning circles of both poses, the common tangents between every
+//! start and end circle, and the circles touching a start and an end circle
+//! of the same direction.
+//!
+//! All circles have the turning radius. Between two such circles there are
+//! two outer tangents whenever their centers differ and two inner ones once
+//! the centers are at least two radii apart. A CSC path drives along one of
+//! the tangents; a CCC path turns on a middle circle, whose center lies two
+//! radii from both outer centers and which exists up to four radii apart.
+
+use reeds_shepp_lib::{Gear, Path, Pose, Steering};
+
+use crate::path::{TURNING_RADIUS, path_turning_circle_centers, segments};
+
+// --- Constants ---
+/// Points closer than this count as the same when matching the path.
+const MATCH_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurningCircle {
+    pub center: (f64, f64),
+    /// `Left` for a counter-clockwise circle.
+    pub steering: Steering,
+}
+
+/// The left and right turning circle of `pose`, in that order.
+pub fn turning_circles(pose: &Pose) -> [TurningCircle; 2] {
+    let (sin, cos) = pose.theta_degree.to_radians().sin_cos();
+    let r = TURNING_RADIUS;
+    [
+        TurningCircle {
+            center: (pose.x - r * sin, pose.y + r * cos),
+            steering: Steering::Left,
+        },
+        TurningCircle {
+            center: (pose.x + r * sin, pose.y - r * cos),
+            steering: Steering::Right,
+        },
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TangentKind {
+    /// Keeps both circles on the same side, as in LSL and RSR.
+    Outer,
+    /// Crosses between the circles, as in LSR and RSL.
+    Inner,
+}
+
+/// Segment between the touching points of a common tangent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tangent {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub kind: TangentKind,
+}
+
+/// Common tangents of two circles of the turning radius, from `a` to `b`.
+pub fn common_tangents(a: (f64, f64), b: (f64, f64)) -> Vec<Tangent> {
+    let r = TURNING_RADIUS;
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let d = dx.hypot(dy);
+    if d < MATCH_TOLERANCE {
+        return Vec::new();
+    }
+    let u = (dx / d, dy / d);
+    let n = (-u.1, u.0);
+    let mut tangents: Vec<Tangent> = [1.0, -1.0]
+        .into_iter()
+        .map(|side| Tangent {
+            from: (a.0 + side * r * n.0, a.1 + side * r * n.1),
+            to: (b.0 + side * r * n.0, b.1 + side * r * n.1),
+            kind: TangentKind::Outer,
+        })
+        .collect();
+    if d >= 2.0 * r {
+        // Both inner tangents pass through the midpoint; the touching point
+        // is at angle `acos(2r / d)` from the line between the centers.
+        let cos = 2.0 * r / d;
+        let sin = (1.0 - cos * cos).max(0.0).sqrt();
+        for side in [1.0, -1.0] {
+            let dir = (cos * u.0 + side * sin * n.0, cos * u.1 + side * sin * n.1);
+            tangents.push(Tangent {
+                from: (a.0 + r * dir.0, a.1 + r * dir.1),
+                to: (b.0 - r * dir.0, b.1 - r * dir.1),
+                kind: TangentKind::Inner,
+            });
+        }
+    }
+    tangents
+}
+
+/// Centers of the circles of the turning radius touching both circles at
+/// `a` and `b` from outside.
+pub fn middle_circles(a: (f64, f64), b: (f64, f64)) -> Vec<(f64, f64)> {
+    let r = TURNING_RADIUS;
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let d = dx.hypot(dy);
+    if d < MATCH_TOLERANCE || d > 4.0 * r {
+        return Vec::new();
+    }
+    let h = ((2.0 * r).powi(2) - (d / 2.0).powi(2)).max(0.0).sqrt();
+    let m = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let n = (-dy / d, dx / d);
+    vec![
+        (m.0 + h * n.0, m.1 + h * n.1),
+        (m.0 - h * n.0, m.1 - h * n.1),
+    ]
+}
+
+/// Everything drawn by the construction overlay for one pair of poses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Construction {
+    /// Left and right circle of the start, then of the end.
+    pub circles: [TurningCircle; 4],
+    pub tangents: Vec<Tangent>,
+    /// Middle circles between the start and end circles turning the same way,
+    /// which the outer arcs of a CCC path join through.
+    pub middle: Vec<TurningCircle>,
+}
+
+impl Construction {
+    pub fn new(start: &Pose, end: &Pose) -> Self {
+        let [start_left, start_right] = turning_circles(start);
+        let [end_left, end_right] = turning_circles(end);
+        let mut tangents = Vec::new();
+        for a in [start_left, start_right] {
+            for b in [end_left, end_right] {
+                tangents.extend(common_tangents(a.center, b.center));
+            }
+        }
+        let mut middle = Vec::new();
+        for (a, b, steering) in [
+            (start_left, end_left, Steering::Right),
+            (start_right, end_right, Steering::Left),
+        ] {
+            middle.extend(
+                middle_circles(a.center, b.center)
+                    .into_iter()
+                    .map(|center| TurningCircle { center, steering }),
+            );
+        }
+        Construction {
+            circles: [start_left, start_right, end_left, end_right],
+            tangents,
+            middle,
+        }
+    }
+
+    /// Tangents that a straight element of `path` from `start` drives along.
+    pub fn used_tangents(&self, start: &Pose, path: &Path) -> Vec<usize> {
+        let close = |p: (f64, f64), q: (f64, f64)| (p.0 - q.0).hypot(p.1 - q.1) < MATCH_TOLERANCE;
+        segments(start, path)
+            .iter()
+            .filter(|seg| seg.element.steering == Steering::Straight)
+            .filter_map(|seg| {
+                let (x, y, theta) = seg.start;
+                let sign = match seg.element.gear {
+                    Gear::Forward => 1.0,
+                    Gear::Backwards => -1.0,
+                };
+                let a = (x, y);
+                let b = (
+                    x + sign * seg.length * theta.cos(),
+                    y + sign * seg.length * theta.sin(),
+                );
+                self.tangents.iter().position(|t| {
+                    (close(t.from, a) && close(t.to, b)) || (close(t.from, b) && close(t.to, a))
+                })
+            })
+            .collect()
+    }
+
+    /// Middle circles that `path` from `start` turns on.
+    pub fn used_middle(&self, start: &Pose, path: &Path) -> Vec<usize> {
+        let centers = path_turning_circle_centers(path, start);
+        (0..self.middle.len())
+            .filter(|&i| {
+                let c = self.middle[i].center;
+                centers
+                    .iter()
+                    .any(|p| (p.0 - c.0).hypot(p.1 - c.1) < MATCH_TOLERANCE)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,161 @@
+//! PNG snapshots of the current view and animated GIFs of the path
+//! playback, rasterized on the CPU through the same `draw_scene` the window
+//! uses, plus a text dump of the lattice primitive set.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::lattice::{PrimitiveSet, write_primitives};
+use crate::path::{PathSample, path_arc_length, sample_path_at};
+use crate::raster::Canvas;
+use crate::render::Rgba;
+use crate::scene::{draw_pose_elements, draw_scene};
+use crate::state::State;
+use crate::view::View;
+
+// --- Constants ---
+pub const DEFAULT_GIF_FPS: u32 = 25;
+/// GIF delays are whole centiseconds, so faster rates cannot be represented.
+pub const MAX_GIF_FPS: u32 = 50;
+/// Playback speed in world units per second.
+pub const PLAYBACK_SPEED: f64 = 2.0;
+/// How long the last frame is held before the GIF loops.
+const END_HOLD_CENTISECONDS: u16 = 100;
+/// NeuQuant sampling factor, 1 (best) to 30 (fastest).
+const GIF_QUANTIZE_SPEED: i32 = 10;
+
+// --- Colors ---
+pub const PLAYBACK_CAR_COLOR: Rgba = Rgba::new(1.0, 0.6, 0.1, 0.8);
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+    /// A GIF needs a path to play back.
+    NoPath,
+    /// GIF frames are limited to 65535 pixels per side.
+    TooLarge {
+        width: u32,
+        height: u32,
+    },
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "export i/o error: {}", e),
+            ExportError::Png(e) => write!(f, "png encoding failed: {}", e),
+            ExportError::Gif(e) => write!(f, "gif encoding failed: {}", e),
+            ExportError::NoPath => write!(f, "no path to play back"),
+            ExportError::TooLarge { width, height } => {
+                write!(f, "{}x{} is too large for a gif", width, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(e: png::EncodingError) -> Self {
+        ExportError::Png(e)
+    }
+}
+
+impl From<gif::EncodingError> for ExportError {
+    fn from(e: gif::EncodingError) -> Self {
+        ExportError::Gif(e)
+    }
+}
+
+fn canvas_for(view: &View) -> Canvas {
+    Canvas::new(view.width.round() as u32, view.height.round() as u32)
+}
+
+/// The scene exactly as the window shows it, minus the UI.
+pub fn render_scene(state: &State, view: &View) -> Canvas {
+    let mut canvas = canvas_for(view);
+    draw_scene(&mut canvas, view, state);
+    canvas
+}
+
+/// Arc lengths of the playback frames: one per `1 / fps` seconds at
+/// [`PLAYBACK_SPEED`], always ending exactly on the goal.
+pub fn playback_arc_lengths(length: f64, fps: u32) -> Vec<f64> {
+    let step = PLAYBACK_SPEED / fps.clamp(1, MAX_GIF_FPS) as f64;
+    let count = (length / step).ceil() as usize;
+    (0..count)
+        .map(|i| i as f64 * step)
+        .chain(std::iter::once(length))
+        .collect()
+}
+
+/// Car poses of the playback of the displayed path, one per frame.
+pub fn playback_samples(state: &State, fps: u32) -> Result<Vec<PathSample>, ExportError> {
+    let (Some(path), Some(start)) = (&state.current_raw_path, &state.start_pose) else {
+        return Err(ExportError::NoPath);
+    };
+    let arc_lengths = playback_arc_lengths(path_arc_length(path), fps);
+    Ok(sample_path_at(start, path, &arc_lengths))
+}
+
+/// One playback frame: the static scene with the moving car on top.
+pub fn render_playback_frame(state: &State, view: &View, sample: &PathSample) -> Canvas {
+    let mut canvas = render_scene(state, view);
+    draw_pose_elements(&mut canvas, view, &sample.pose(), PLAYBACK_CAR_COLOR);
+    canvas
+}
+
+/// Writes the lattice primitive set as text and returns the primitive count.
+pub fn export_primitives(set: &PrimitiveSet, file: &Path) -> Result<usize, ExportError> {
+    let mut writer = BufWriter::new(File::create(file)?);
+    write_primitives(&mut writer, set)?;
+    writer.flush()?;
+    Ok(set.len())
+}
+
+pub fn export_png(state: &State, view: &View, file: &Path) -> Result<(), ExportError> {
+    let canvas = render_scene(state, view);
+    let writer = BufWriter::new(File::create(file)?);
+    let mut encoder = png::Encoder::new(writer, canvas.width, canvas.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()?
+        .write_image_data(&canvas.to_rgba8())?;
+    Ok(())
+}
+
+/// Writes a looping GIF of the path playback and returns the frame count.
+pub fn export_gif(state: &State, view: &View, fps: u32, file: &Path) -> Result<usize, ExportError> {
+    let fps = fps.clamp(1, MAX_GIF_FPS);
+    let samples = playback_samples(state, fps)?;
+    let (width, height) = (view.width.round() as u32, view.height.round() as u32);
+    let (Ok(w), Ok(h)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(ExportError::TooLarge { width, height });
+    };
+    let delay = (100.0 / fps as f64).round() as u16;
+    let mut encoder = gif::Encoder::new(BufWriter::new(File::create(file)?), w, h, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    // Frames are encoded as they are rendered so large exports stay small in memory.
+    for (i, sample) in samples.iter().enumerate() {
+        let mut rgba = render_playback_frame(state, view, sample).to_rgba8();
+        let mut frame = gif::Frame::from_rgba_speed(w, h, &mut rgba, GIF_QUANTIZE_SPEED);
+        frame.delay = if i + 1 == samples.len() {
+            END_HOLD_CENTISECONDS
+        } else {
+            delay
+        };
+        encoder.write_frame(&frame)?;
+    }
+    Ok(samples.len())
+}
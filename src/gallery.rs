@@ -0,0 +1,140 @@
+//! Gallery of every `PATH_FNS` entry under every transform as thumbnails.
+//!
+//! Thumbnails are tiled family by family in `TRANSFORMS` order, with a
+//! column count that is a multiple of four so that each family stays on one
+//! row. Like the transform explorer, all thumbnails share one world square,
+//! so the sizes of the paths compare directly.
+
+use reeds_shepp_lib::{PATH_FNS, Pose};
+
+use crate::render::ScreenPoint;
+use crate::transforms::{
+    Feasibility, TRANSFORMS, TransformCell, bounding_square, square_to_screen,
+};
+
+// --- Constants ---
+pub const GALLERY_GAP: f32 = 6.0;
+/// Room at the bottom of a thumbnail for its label.
+pub const THUMB_LABEL_HEIGHT: f32 = 28.0;
+const THUMB_PADDING: f32 = 6.0;
+
+/// One family under one transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GalleryThumb {
+    pub family: usize,
+    pub cell: TransformCell,
+}
+
+/// Every family and transform between two poses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gallery {
+    pub start: Pose,
+    pub end: Pose,
+    pub thumbs: Vec<GalleryThumb>,
+    pub center: (f64, f64),
+    pub half_extent: f64,
+    /// Index of the shortest thumbnail that reaches the goal.
+    pub optimal: Option<usize>,
+}
+
+impl Gallery {
+    pub fn new(start: &Pose, end: &Pose) -> Self {
+        let thumbs: Vec<GalleryThumb> = (0..PATH_FNS.len())
+            .flat_map(|family| {
+                TRANSFORMS
+                    .iter()
+                    .map(move |&(reflect, timeflip)| GalleryThumb {
+                        family,
+                        cell: TransformCell::new(start, end, family, reflect, timeflip),
+                    })
+            })
+            .collect();
+        let (center, half_extent) = bounding_square(start, end, thumbs.iter().map(|t| &t.cell));
+        let optimal = thumbs
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.cell.feasibility == Feasibility::Feasible)
+            .filter_map(|(i, t)| Some((i, t.cell.length()?)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i);
+        Gallery {
+            start: *start,
+            end: *end,
+            thumbs,
+            center,
+            half_extent,
+            optimal,
+        }
+    }
+}
+
+/// Screen layout of the thumbnail grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GalleryLayout {
+    pub left: f32,
+    pub top: f32,
+    pub columns: usize,
+    pub thumb_size: f32,
+    pub gap: f32,
+}
+
+impl GalleryLayout {
+    /// Largest square thumbnails for `count` of them inside the rectangle,
+    /// with whole families per row.
+    pub fn fit(left: f32, top: f32, width: f32, height: f32, count: usize) -> Self {
+        let per_family = TRANSFORMS.len();
+        let size_for = |columns: usize| {
+            let rows = count.div_ceil(columns).max(1);
+            let w = (width - (columns - 1) as f32 * GALLERY_GAP) / columns as f32;
+            let h = (height - (rows - 1) as f32 * GALLERY_GAP) / rows as f32;
+            w.min(h)
+        };
+        let columns = (per_family..=count.max(per_family))
+            .step_by(per_family)
+            .max_by(|&a, &b| size_for(a).total_cmp(&size_for(b)))
+            .unwrap_or(per_family);
+        GalleryLayout {
+            left,
+            top,
+            columns,
+            thumb_size: size_for(columns).max(0.0),
+            gap: GALLERY_GAP,
+        }
+    }
+
+    /// Top left corner of thumbnail `index`.
+    pub fn thumb_origin(&self, index: usize) -> ScreenPoint {
+        let (col, row) = ((index % self.columns) as f32, (index / self.columns) as f32);
+        (
+            self.left + col * (self.thumb_size + self.gap),
+            self.top + row * (self.thumb_size + self.gap),
+        )
+    }
+
+    /// Thumbnail under the screen point, if any of the `count`.
+    pub fn thumb_at(&self, p: ScreenPoint, count: usize) -> Option<usize> {
+        (0..count).find(|&i| {
+            let (x, y) = self.thumb_origin(i);
+            (x..=x + self.thumb_size).contains(&p.0) && (y..=y + self.thumb_size).contains(&p.1)
+        })
+    }
+
+    /// Screen position of a world point inside thumbnail `index`.
+    pub fn to_screen(&self, gallery: &Gallery, index: usize, point: (f64, f64)) -> ScreenPoint {
+        let (left, top) = self.thumb_origin(index);
+        let plot = (self.thumb_size - THUMB_LABEL_HEIGHT - 2.0 * THUMB_PADDING).max(0.0);
+        square_to_screen(
+            (left + (self.thumb_size - plot) / 2.0, top + THUMB_PADDING),
+            plot,
+            (gallery.center, gallery.half_extent),
+            point,
+        )
+    }
+
+    /// Baselines of the two label lines of thumbnail `index`.
+    pub fn label_origins(&self, index: usize) -> [ScreenPoint; 2] {
+        let (left, top) = self.thumb_origin(index);
+        let bottom = top + self.thumb_size - 4.0;
+        [(left + 4.0, bottom - 12.0), (left + 4.0, bottom)]
+    }
+}
@@ -0,0 +1,76 @@
+//! Metric grid settings and the snapping of placed positions and headings.
+
+// --- Constants ---
+pub const DEFAULT_GRID_SPACING: f64 = 1.0;
+pub const MIN_GRID_SPACING: f64 = 0.1;
+pub const MAX_GRID_SPACING: f64 = 2.0;
+pub const DEFAULT_ANGLE_INCREMENT_DEGREE: f64 = 15.0;
+pub const MIN_ANGLE_INCREMENT_DEGREE: f64 = 1.0;
+pub const MAX_ANGLE_INCREMENT_DEGREE: f64 = 90.0;
+
+/// What pose placement and drags snap to. Spacing and increment are kept
+/// while snapping is off so the sliders do not forget them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapSettings {
+    pub to_grid: bool,
+    pub grid_spacing: f64,
+    pub to_angles: bool,
+    pub angle_increment_degree: f64,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        SnapSettings {
+            to_grid: false,
+            grid_spacing: DEFAULT_GRID_SPACING,
+            to_angles: false,
+            angle_increment_degree: DEFAULT_ANGLE_INCREMENT_DEGREE,
+        }
+    }
+}
+
+fn snap(value: f64, increment: f64) -> f64 {
+    (value / increment).round() * increment
+}
+
+impl SnapSettings {
+    /// Nearest grid point to `world_pos` when grid snapping is on.
+    pub fn position(&self, world_pos: (f64, f64)) -> (f64, f64) {
+        if !self.to_grid || self.grid_spacing <= 0.0 {
+            return world_pos;
+        }
+        (
+            snap(world_pos.0, self.grid_spacing),
+            snap(world_pos.1, self.grid_spacing),
+        )
+    }
+
+    /// Nearest angle increment to `theta_degree` when angle snapping is on,
+    /// kept in (-180, 180].
+    pub fn heading(&self, theta_degree: f64) -> f64 {
+        if !self.to_angles || self.angle_increment_degree <= 0.0 {
+            return theta_degree;
+        }
+        let snapped = snap(theta_degree, self.angle_increment_degree);
+        if snapped <= -180.0 {
+            snapped + 360.0
+        } else if snapped > 180.0 {
+            snapped - 360.0
+        } else {
+            snapped
+        }
+    }
+}
+
+/// The 1-2-5 step (… 0.5, 1, 2, 5, 10 …) that is at least `min_step`.
+pub fn nice_step(min_step: f64) -> f64 {
+    if min_step <= 0.0 || !min_step.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= min_step * (1.0 - 1e-9))
+        .unwrap_or(10.0 * magnitude)
+}
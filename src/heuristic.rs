@@ -0,0 +1,415 @@
+//! Precomputed Reeds-Shepp distances for use as a planner heuristic.
+//!
+//! The table holds the optimal path length from the origin, headed 0°, to
+//! every pose of a regular grid over the relative `(x, y, theta)`. Lengths
+//! are invariant under moving both poses together, so looking up the goal in
+//! the start's frame gives the distance between any two poses. Lookups
+//! between grid points interpolate trilinearly, with theta wrapping around.
+//!
+//! The file is a fixed header followed by the lengths as little-endian `f32`,
+//! x outermost and theta innermost:
+//!
+//! | bytes | content                                  |
+//! |-------|------------------------------------------|
+//! | 8     | magic `RSHEUR\0\0`                       |
+//! | 4     | format version (`u32`)                   |
+//! | 20    | x axis: min, max (`f64`), count (`u32`)  |
+//! | 20    | y axis: min, max (`f64`), count (`u32`)  |
+//! | 4     | theta count (`u32`), from -180° upwards  |
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::thread;
+
+use reeds_shepp_lib::{Pose, get_optimal_path, path_length, utils};
+
+use crate::rng::Rng;
+
+// --- Constants ---
+pub const LUT_MAGIC: [u8; 8] = *b"RSHEUR\0\0";
+pub const LUT_VERSION: u32 = 1;
+pub const LUT_HEADER_SIZE: usize = 56;
+pub const DEFAULT_LUT_AXIS: LutAxis = LutAxis {
+    min: -10.0,
+    max: 10.0,
+    count: 81,
+};
+pub const DEFAULT_THETA_COUNT: usize = 72;
+
+/// `count` evenly spaced values from `min` to `max`, both included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LutAxis {
+    pub min: f64,
+    pub max: f64,
+    pub count: usize,
+}
+
+impl LutAxis {
+    pub fn step(&self) -> f64 {
+        (self.max - self.min) / (self.count - 1) as f64
+    }
+
+    pub fn value(&self, i: usize) -> f64 {
+        self.min + i as f64 * self.step()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite() && self.min < self.max && self.count >= 2
+    }
+
+    /// Lower grid index and the fraction towards the next one, or `None`
+    /// outside the axis.
+    fn locate(&self, v: f64) -> Option<(usize, f64)> {
+        if !(self.min..=self.max).contains(&v) {
+            return None;
+        }
+        let f = (v - self.min) / self.step();
+        let i = (f.floor() as usize).min(self.count - 2);
+        Some((i, f - i as f64))
+    }
+}
+
+/// Parses `MIN:MAX:COUNT`, e.g. `-10:10:81`.
+impl FromStr for LutAxis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let [min, max, count] = parts[..] else {
+            return Err(format!("expected MIN:MAX:COUNT, got `{}`", s));
+        };
+        let axis = LutAxis {
+            min: min.parse().map_err(|_| format!("bad minimum `{}`", min))?,
+            max: max.parse().map_err(|_| format!("bad maximum `{}`", max))?,
+            count: count
+                .parse()
+                .map_err(|_| format!("bad count `{}`", count))?,
+        };
+        if !axis.is_valid() {
+            return Err(format!("`{}` needs MIN < MAX and COUNT >= 2", s));
+        }
+        Ok(axis)
+    }
+}
+
+impl fmt::Display for LutAxis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.min, self.max, self.count)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LutGrid {
+    pub x: LutAxis,
+    pub y: LutAxis,
+    /// Headings `-180 + i * 360 / theta_count` degrees.
+    pub theta_count: usize,
+}
+
+impl Default for LutGrid {
+    fn default() -> Self {
+        LutGrid {
+            x: DEFAULT_LUT_AXIS,
+            y: DEFAULT_LUT_AXIS,
+            theta_count: DEFAULT_THETA_COUNT,
+        }
+    }
+}
+
+impl LutGrid {
+    pub fn len(&self) -> usize {
+        self.x.count * self.y.count * self.theta_count
+    }
+
+    /// [`len`](Self::len), or `None` if it does not fit a `usize`, as for a
+    /// corrupt header.
+    fn checked_len(&self) -> Option<usize> {
+        self.x
+            .count
+            .checked_mul(self.y.count)?
+            .checked_mul(self.theta_count)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn theta_step(&self) -> f64 {
+        360.0 / self.theta_count as f64
+    }
+
+    pub fn theta_degree(&self, i: usize) -> f64 {
+        -180.0 + i as f64 * self.theta_step()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.x.is_valid() && self.y.is_valid() && self.theta_count >= 1
+    }
+
+    fn index(&self, ix: usize, iy: usize, it: usize) -> usize {
+        (ix * self.y.count + iy) * self.theta_count + it
+    }
+
+    /// The relative goal pose at a grid point.
+    pub fn pose(&self, ix: usize, iy: usize, it: usize) -> Pose {
+        Pose {
+            x: self.x.value(ix),
+            y: self.y.value(iy),
+            theta_degree: self.theta_degree(it),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LutError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u32),
+    /// The header describes an empty, inverted or unaddressably large grid.
+    InvalidGrid,
+    /// The data after the header does not match the grid size, in bytes.
+    SizeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for LutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LutError::Io(e) => write!(f, "lookup table i/o error: {}", e),
+            LutError::BadMagic => write!(f, "not a reeds-shepp lookup table"),
+            LutError::UnsupportedVersion(v) => {
+                write!(f, "unsupported lookup table version {}", v)
+            }
+            LutError::InvalidGrid => write!(f, "lookup table header describes an invalid grid"),
+            LutError::SizeMismatch { expected, actual } => write!(
+                f,
+                "lookup table holds {} data bytes, the grid needs {}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LutError {}
+
+impl From<io::Error> for LutError {
+    fn from(e: io::Error) -> Self {
+        LutError::Io(e)
+    }
+}
+
+/// Optimal path length from the origin to `goal`. Only the origin itself
+/// has no path, and its distance is zero.
+pub fn exact_distance(goal: Pose) -> f64 {
+    let origin = Pose {
+        x: 0.0,
+        y: 0.0,
+        theta_degree: 0.0,
+    };
+    get_optimal_path(origin, goal).map_or(0.0, |path| path_length(&path))
+}
+
+/// Interpolation error against [`exact_distance`] at random poses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccuracyReport {
+    pub samples: usize,
+    pub mean_error: f64,
+    pub rms_error: f64,
+    pub max_error: f64,
+    /// Relative pose with the largest error.
+    pub max_error_at: Pose,
+    /// Largest error divided by the exact length, ignoring lengths below 1.
+    pub max_relative_error: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeuristicTable {
+    pub grid: LutGrid,
+    values: Vec<f32>,
+}
+
+impl HeuristicTable {
+    /// Evaluates every grid point, splitting the x slices over `threads`.
+    pub fn compute(grid: LutGrid, threads: usize) -> Self {
+        let mut values = vec![0.0f32; grid.len()];
+        let slice = grid.y.count * grid.theta_count;
+        let rows_per_thread = grid.x.count.div_ceil(threads.max(1));
+        thread::scope(|scope| {
+            for (chunk_index, chunk) in values.chunks_mut(rows_per_thread * slice).enumerate() {
+                scope.spawn(move || {
+                    let first_row = chunk_index * rows_per_thread;
+                    for (i, value) in chunk.iter_mut().enumerate() {
+                        let ix = first_row + i / slice;
+                        let iy = i % slice / grid.theta_count;
+                        let it = i % grid.theta_count;
+                        *value = exact_distance(grid.pose(ix, iy, it)) as f32;
+                    }
+                });
+            }
+        });
+        HeuristicTable { grid, values }
+    }
+
+    pub fn get(&self, ix: usize, iy: usize, it: usize) -> f32 {
+        self.values[self.grid.index(ix, iy, it)]
+    }
+
+    /// Interpolated length to the relative pose `goal`, or `None` if it lies
+    /// outside the x/y range.
+    pub fn lookup(&self, goal: &Pose) -> Option<f64> {
+        let (ix, fx) = self.grid.x.locate(goal.x)?;
+        let (iy, fy) = self.grid.y.locate(goal.y)?;
+        let n = self.grid.theta_count;
+        let ft = (goal.theta_degree + 180.0).rem_euclid(360.0) / self.grid.theta_step();
+        let it = (ft.floor() as usize).min(n - 1);
+        let ft = ft - it as f64;
+        let it_next = (it + 1) % n;
+
+        let mut sum = 0.0;
+        for (dx, wx) in [(0, 1.0 - fx), (1, fx)] {
+            for (dy, wy) in [(0, 1.0 - fy), (1, fy)] {
+                for (t, wt) in [(it, 1.0 - ft), (it_next, ft)] {
+                    let w = wx * wy * wt;
+                    if w > 0.0 {
+                        sum += w * self.get(ix + dx, iy + dy, t) as f64;
+                    }
+                }
+            }
+        }
+        Some(sum)
+    }
+
+    /// Length from `start` to `end`, looked up in the start's frame.
+    pub fn distance(&self, start: &Pose, end: &Pose) -> Option<f64> {
+        self.lookup(&utils::change_of_basis(start, end))
+    }
+
+    /// Compares lookups with exact evaluation at `samples` uniformly drawn
+    /// poses inside the grid.
+    pub fn accuracy(&self, samples: usize, seed: u64) -> AccuracyReport {
+        let mut rng = Rng(seed);
+        let mut report = AccuracyReport {
+            samples,
+            mean_error: 0.0,
+            rms_error: 0.0,
+            max_error: 0.0,
+            max_error_at: Pose {
+                x: 0.0,
+                y: 0.0,
+                theta_degree: 0.0,
+            },
+            max_relative_error: 0.0,
+        };
+        let mut squares = 0.0;
+        for _ in 0..samples {
+            let goal = Pose {
+                x: rng.range(self.grid.x.min, self.grid.x.max),
+                y: rng.range(self.grid.y.min, self.grid.y.max),
+                theta_degree: rng.range(-180.0, 180.0),
+            };
+            let exact = exact_distance(goal);
+            let error = (self.lookup(&goal).unwrap_or(f64::NAN) - exact).abs();
+            report.mean_error += error;
+            squares += error * error;
+            if error > report.max_error {
+                report.max_error = error;
+                report.max_error_at = goal;
+            }
+            if exact >= 1.0 {
+                report.max_relative_error = report.max_relative_error.max(error / exact);
+            }
+        }
+        if samples > 0 {
+            report.mean_error /= samples as f64;
+            report.rms_error = (squares / samples as f64).sqrt();
+        }
+        report
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        // Checked before writing, so a table that cannot be read back
+        // leaves no truncated header behind.
+        let header_count = |count: usize| {
+            u32::try_from(count).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("grid count {} does not fit the lookup table header", count),
+                )
+            })
+        };
+        let x_count = header_count(self.grid.x.count)?;
+        let y_count = header_count(self.grid.y.count)?;
+        let theta_count = header_count(self.grid.theta_count)?;
+        writer.write_all(&LUT_MAGIC)?;
+        writer.write_all(&LUT_VERSION.to_le_bytes())?;
+        for (axis, count) in [(&self.grid.x, x_count), (&self.grid.y, y_count)] {
+            writer.write_all(&axis.min.to_le_bytes())?;
+            writer.write_all(&axis.max.to_le_bytes())?;
+            writer.write_all(&count.to_le_bytes())?;
+        }
+        writer.write_all(&theta_count.to_le_bytes())?;
+        for value in &self.values {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, LutError> {
+        let mut header = [0u8; LUT_HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        if header[..8] != LUT_MAGIC {
+            return Err(LutError::BadMagic);
+        }
+        let u32_at = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().unwrap());
+        let f64_at = |at: usize| f64::from_le_bytes(header[at..at + 8].try_into().unwrap());
+        let version = u32_at(8);
+        if version != LUT_VERSION {
+            return Err(LutError::UnsupportedVersion(version));
+        }
+        let axis_at = |at: usize| LutAxis {
+            min: f64_at(at),
+            max: f64_at(at + 8),
+            count: u32_at(at + 16) as usize,
+        };
+        let grid = LutGrid {
+            x: axis_at(12),
+            y: axis_at(32),
+            theta_count: u32_at(52) as usize,
+        };
+        let expected = match grid.checked_len().and_then(|n| n.checked_mul(4)) {
+            Some(bytes) if grid.is_valid() => bytes,
+            _ => return Err(LutError::InvalidGrid),
+        };
+
+        // Read what is there before allocating, so a corrupt header cannot
+        // request an enormous buffer.
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        if data.len() != expected {
+            return Err(LutError::SizeMismatch {
+                expected,
+                actual: data.len(),
+            });
+        }
+        let values = data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        Ok(HeuristicTable { grid, values })
+    }
+
+    pub fn save(&self, file: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(file)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load(file: &Path) -> Result<Self, LutError> {
+        Self::read_from(&mut BufReader::new(File::open(file)?))
+    }
+}
@@ -0,0 +1,604 @@
+//! Input events fed into the `State` machine, and a plain-text session format
+//! so that a run can be recorded and replayed frame by frame.
+//!
+//! A session file holds one event per line; every frame starts with a `frame`
+//! line. Positions are world coordinates, so replays do not depend on the
+//! window size.
+//!
+//! ```text
+//! frame
+//! pointer 1.5 -0.25 1 1 0 0
+//! frame
+//! mode 3
+//! reflect 1
+//! ```
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::grid::SnapSettings;
+use crate::lattice::LatticeSettings;
+use crate::obstacle::Obstacle;
+use crate::reach::ReachSettings;
+use crate::sketch::SketchMetric;
+use crate::stability::StabilitySettings;
+use crate::state::PathColoring;
+use crate::trailer::TrailerModel;
+
+const FRAME_MARKER: &str = "frame";
+
+/// Left mouse button state for one frame, already mapped to world coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerInput {
+    pub world_pos: (f64, f64),
+    pub pressed: bool,
+    pub down: bool,
+    pub released: bool,
+    pub over_ui: bool,
+}
+
+/// Single-key shortcuts of the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+    Reset,
+    ExportPng,
+    ExportPrimitives,
+    ExportGif,
+}
+
+impl Shortcut {
+    pub const ALL: [Shortcut; 4] = [
+        Shortcut::Reset,
+        Shortcut::ExportPng,
+        Shortcut::ExportPrimitives,
+        Shortcut::ExportGif,
+    ];
+
+    /// The key that triggers the shortcut, lower case as in the session format.
+    pub fn key(self) -> char {
+        match self {
+            Shortcut::Reset => 'r',
+            Shortcut::ExportPng => 'p',
+            Shortcut::ExportPrimitives => 'l',
+            Shortcut::ExportGif => 'g',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    Reset,
+    /// A shortcut key went down; ignored while the word field has focus.
+    Shortcut(Shortcut),
+    /// The custom word field gained or lost keyboard focus.
+    SetWordFocus(bool),
+    Pointer(PointerInput),
+    SelectDisplayMode(usize),
+    SetReflect(bool),
+    SetTimeflip(bool),
+    SetCc {
+        enabled: bool,
+        max_steering_rate: f64,
+    },
+    SetTrailer {
+        enabled: bool,
+        model: TrailerModel,
+    },
+    SetGrid {
+        visible: bool,
+        snap: SnapSettings,
+    },
+    SetPathColoring(PathColoring),
+    /// Custom path word; the rest of the line in the session format.
+    SetCustomWord(String),
+    /// While enabled, pointer drags draw a sketch instead of placing poses.
+    SetSketch {
+        enabled: bool,
+        metric: SketchMetric,
+    },
+    /// While enabled, pointer drags draw obstacle rectangles.
+    SetObstacleEditing(bool),
+    AddObstacle(Obstacle),
+    ClearObstacles,
+    SetCspace {
+        enabled: bool,
+        heading_degree: f64,
+    },
+    /// Roadmap size; changing it rebuilds the roadmap.
+    SetPrm {
+        samples: usize,
+        neighbors: usize,
+    },
+    /// Rebuilds the roadmap from a fresh seed.
+    RebuildRoadmap,
+    /// Lattice resolution; changing it regenerates the primitive set.
+    SetLattice(LatticeSettings),
+    SetHeadingSweep(bool),
+    /// Turns the end pose to this heading, e.g. from a click on the sweep chart.
+    SetEndHeading(f64),
+    SetReach {
+        enabled: bool,
+        settings: ReachSettings,
+    },
+    SetStability(StabilitySettings),
+    SetTransformExplorer(bool),
+    /// Shows the solver's intermediate values in `SinglePath` mode.
+    SetTrace(bool),
+    SetConstruction(bool),
+}
+
+/// Everything that happened during one rendered frame, in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputFrame {
+    pub events: Vec<InputEvent>,
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::Io(e) => write!(f, "session i/o error: {}", e),
+            SessionError::Parse { line, message } => {
+                write!(f, "session line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<io::Error> for SessionError {
+    fn from(e: io::Error) -> Self {
+        SessionError::Io(e)
+    }
+}
+
+fn flag(b: bool) -> u8 {
+    b as u8
+}
+
+/// `text` in double quotes with quotes, backslashes and line breaks
+/// escaped, so that it survives a session line unchanged.
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Inverse of [`quote`].
+fn unquote(quoted: &str) -> Result<String, String> {
+    let inner = quoted
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or(format!("expected a quoted string, got `{}`", quoted))?;
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.push(match chars.next() {
+                Some('"') => '"',
+                Some('\\') => '\\',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                other => return Err(format!("unknown escape `\\{}`", other.unwrap_or(' '))),
+            }),
+            '"' => return Err(format!("unescaped quote in `{}`", quoted)),
+            c => text.push(c),
+        }
+    }
+    Ok(text)
+}
+
+impl fmt::Display for InputEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputEvent::Reset => write!(f, "reset"),
+            InputEvent::Shortcut(shortcut) => write!(f, "key {}", shortcut.key()),
+            InputEvent::SetWordFocus(b) => write!(f, "word_focus {}", flag(*b)),
+            InputEvent::Pointer(p) => write!(
+                f,
+                "pointer {} {} {} {} {} {}",
+                p.world_pos.0,
+                p.world_pos.1,
+                flag(p.pressed),
+                flag(p.down),
+                flag(p.released),
+                flag(p.over_ui)
+            ),
+            InputEvent::SelectDisplayMode(i) => write!(f, "mode {}", i),
+            InputEvent::SetReflect(b) => write!(f, "reflect {}", flag(*b)),
+            InputEvent::SetTimeflip(b) => write!(f, "timeflip {}", flag(*b)),
+            InputEvent::SetCc {
+                enabled,
+                max_steering_rate,
+            } => write!(f, "cc {} {}", flag(*enabled), max_steering_rate),
+            InputEvent::SetTrailer { enabled, model } => write!(
+                f,
+                "trailer {} {} {} {}",
+                flag(*enabled),
+                model.hitch_offset,
+                model.trailer_length,
+                model.jackknife_limit_degree
+            ),
+            InputEvent::SetGrid { visible, snap } => write!(
+                f,
+                "grid {} {} {} {} {}",
+                flag(*visible),
+                flag(snap.to_grid),
+                snap.grid_spacing,
+                flag(snap.to_angles),
+                snap.angle_increment_degree
+            ),
+            InputEvent::SetPathColoring(coloring) => match coloring {
+                PathColoring::Gear => write!(f, "coloring gear"),
+                PathColoring::Steering => write!(f, "coloring steering"),
+            },
+            InputEvent::SetCustomWord(word) => write!(f, "word {}", quote(word)),
+            InputEvent::SetSketch { enabled, metric } => {
+                let metric = match metric {
+                    SketchMetric::Frechet => "frechet",
+                    SketchMetric::Hausdorff => "hausdorff",
+                };
+                write!(f, "sketch {} {}", flag(*enabled), metric)
+            }
+            InputEvent::SetObstacleEditing(b) => write!(f, "edit_obstacles {}", flag(*b)),
+            InputEvent::AddObstacle(o) => {
+                write!(
+                    f,
+                    "obstacle {} {} {} {}",
+                    o.min.0, o.min.1, o.max.0, o.max.1
+                )
+            }
+            InputEvent::ClearObstacles => write!(f, "clear_obstacles"),
+            InputEvent::SetCspace {
+                enabled,
+                heading_degree,
+            } => write!(f, "cspace {} {}", flag(*enabled), heading_degree),
+            InputEvent::SetPrm { samples, neighbors } => write!(f, "prm {} {}", samples, neighbors),
+            InputEvent::RebuildRoadmap => write!(f, "rebuild_roadmap"),
+            InputEvent::SetLattice(settings) => write!(
+                f,
+                "lattice {} {}",
+                settings.spacing, settings.max_primitive_length
+            ),
+            InputEvent::SetHeadingSweep(b) => write!(f, "heading_sweep {}", flag(*b)),
+            InputEvent::SetTransformExplorer(b) => write!(f, "transforms {}", flag(*b)),
+            InputEvent::SetTrace(b) => write!(f, "trace {}", flag(*b)),
+            InputEvent::SetConstruction(b) => write!(f, "construction {}", flag(*b)),
+            InputEvent::SetEndHeading(theta) => write!(f, "end_heading {}", theta),
+            InputEvent::SetReach { enabled, settings } => {
+                let headings = if settings.union { "union" } else { "heading" };
+                write!(
+                    f,
+                    "reach {} {} {}",
+                    flag(*enabled),
+                    settings.budget,
+                    headings
+                )
+            }
+            InputEvent::SetStability(s) => write!(
+                f,
+                "stability {} {} {} {}",
+                flag(s.show_runner_up),
+                s.gap_threshold,
+                flag(s.hysteresis),
+                s.margin
+            ),
+        }
+    }
+}
+
+impl FromStr for InputEvent {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut tokens = line.split_whitespace();
+        let keyword = tokens.next().ok_or("empty event")?;
+        let args: Vec<&str> = tokens.collect();
+        let float = |i: usize| -> Result<f64, String> {
+            args.get(i)
+                .ok_or(format!("`{}` is missing argument {}", keyword, i + 1))?
+                .parse::<f64>()
+                .map_err(|e| format!("`{}` argument {}: {}", keyword, i + 1, e))
+        };
+        let boolean = |i: usize| -> Result<bool, String> {
+            match args.get(i).copied() {
+                Some("0") => Ok(false),
+                Some("1") => Ok(true),
+                _ => Err(format!("`{}` argument {} must be 0 or 1", keyword, i + 1)),
+            }
+        };
+        let expect_args = |n: usize| -> Result<(), String> {
+            if args.len() == n {
+                Ok(())
+            } else {
+                Err(format!(
+                    "`{}` takes {} arguments, got {}",
+                    keyword,
+                    n,
+                    args.len()
+                ))
+            }
+        };
+        match keyword {
+            "reset" => {
+                expect_args(0)?;
+                Ok(InputEvent::Reset)
+            }
+            "key" => {
+                expect_args(1)?;
+                Shortcut::ALL
+                    .into_iter()
+                    .find(|s| args[0] == s.key().to_string())
+                    .map(InputEvent::Shortcut)
+                    .ok_or(format!("unknown shortcut key `{}`", args[0]))
+            }
+            "word_focus" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetWordFocus(boolean(0)?))
+            }
+            "pointer" => {
+                expect_args(6)?;
+                Ok(InputEvent::Pointer(PointerInput {
+                    world_pos: (float(0)?, float(1)?),
+                    pressed: boolean(2)?,
+                    down: boolean(3)?,
+                    released: boolean(4)?,
+                    over_ui: boolean(5)?,
+                }))
+            }
+            "mode" => {
+                expect_args(1)?;
+                args[0]
+                    .parse()
+                    .map(InputEvent::SelectDisplayMode)
+                    .map_err(|e| format!("`mode` argument: {}", e))
+            }
+            "reflect" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetReflect(boolean(0)?))
+            }
+            "timeflip" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetTimeflip(boolean(0)?))
+            }
+            "cc" => {
+                expect_args(2)?;
+                Ok(InputEvent::SetCc {
+                    enabled: boolean(0)?,
+                    max_steering_rate: float(1)?,
+                })
+            }
+            "trailer" => {
+                expect_args(4)?;
+                Ok(InputEvent::SetTrailer {
+                    enabled: boolean(0)?,
+                    model: TrailerModel {
+                        hitch_offset: float(1)?,
+                        trailer_length: float(2)?,
+                        jackknife_limit_degree: float(3)?,
+                    },
+                })
+            }
+            "grid" => {
+                expect_args(5)?;
+                Ok(InputEvent::SetGrid {
+                    visible: boolean(0)?,
+                    snap: SnapSettings {
+                        to_grid: boolean(1)?,
+                        grid_spacing: float(2)?,
+                        to_angles: boolean(3)?,
+                        angle_increment_degree: float(4)?,
+                    },
+                })
+            }
+            "coloring" => {
+                expect_args(1)?;
+                match args[0] {
+                    "gear" => Ok(InputEvent::SetPathColoring(PathColoring::Gear)),
+                    "steering" => Ok(InputEvent::SetPathColoring(PathColoring::Steering)),
+                    other => Err(format!("unknown coloring `{}`", other)),
+                }
+            }
+            // Quoted, so the word keeps its spacing as typed.
+            "word" => {
+                let rest = line.trim_start()[keyword.len()..].trim();
+                unquote(rest).map(InputEvent::SetCustomWord)
+            }
+            "sketch" => {
+                expect_args(2)?;
+                let metric = match args[1] {
+                    "frechet" => SketchMetric::Frechet,
+                    "hausdorff" => SketchMetric::Hausdorff,
+                    other => return Err(format!("unknown sketch metric `{}`", other)),
+                };
+                Ok(InputEvent::SetSketch {
+                    enabled: boolean(0)?,
+                    metric,
+                })
+            }
+            "edit_obstacles" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetObstacleEditing(boolean(0)?))
+            }
+            "obstacle" => {
+                expect_args(4)?;
+                Ok(InputEvent::AddObstacle(Obstacle::from_corners(
+                    (float(0)?, float(1)?),
+                    (float(2)?, float(3)?),
+                )))
+            }
+            "clear_obstacles" => {
+                expect_args(0)?;
+                Ok(InputEvent::ClearObstacles)
+            }
+            "cspace" => {
+                expect_args(2)?;
+                Ok(InputEvent::SetCspace {
+                    enabled: boolean(0)?,
+                    heading_degree: float(1)?,
+                })
+            }
+            "prm" => {
+                expect_args(2)?;
+                let count = |i: usize| -> Result<usize, String> {
+                    args[i]
+                        .parse()
+                        .map_err(|e| format!("`prm` argument {}: {}", i + 1, e))
+                };
+                Ok(InputEvent::SetPrm {
+                    samples: count(0)?,
+                    neighbors: count(1)?,
+                })
+            }
+            "rebuild_roadmap" => {
+                expect_args(0)?;
+                Ok(InputEvent::RebuildRoadmap)
+            }
+            "lattice" => {
+                expect_args(2)?;
+                Ok(InputEvent::SetLattice(LatticeSettings {
+                    spacing: float(0)?,
+                    max_primitive_length: float(1)?,
+                }))
+            }
+            "construction" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetConstruction(boolean(0)?))
+            }
+            "trace" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetTrace(boolean(0)?))
+            }
+            "transforms" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetTransformExplorer(boolean(0)?))
+            }
+            "heading_sweep" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetHeadingSweep(boolean(0)?))
+            }
+            "end_heading" => {
+                expect_args(1)?;
+                Ok(InputEvent::SetEndHeading(float(0)?))
+            }
+            "reach" => {
+                expect_args(3)?;
+                let union = match args[2] {
+                    "union" => true,
+                    "heading" => false,
+                    other => return Err(format!("unknown reach headings `{}`", other)),
+                };
+                Ok(InputEvent::SetReach {
+                    enabled: boolean(0)?,
+                    settings: ReachSettings {
+                        budget: float(1)?,
+                        union,
+                    },
+                })
+            }
+            "stability" => {
+                expect_args(4)?;
+                Ok(InputEvent::SetStability(StabilitySettings {
+                    show_runner_up: boolean(0)?,
+                    gap_threshold: float(1)?,
+                    hysteresis: boolean(2)?,
+                    margin: float(3)?,
+                }))
+            }
+            other => Err(format!("unknown event `{}`", other)),
+        }
+    }
+}
+
+/// Writes a session to any writer, one frame at a time.
+pub fn write_frame<W: Write>(writer: &mut W, frame: &InputFrame) -> io::Result<()> {
+    writeln!(writer, "{}", FRAME_MARKER)?;
+    for event in &frame.events {
+        writeln!(writer, "{}", event)?;
+    }
+    Ok(())
+}
+
+/// Parses a whole session. Blank lines and lines starting with `#` are ignored.
+pub fn parse_session(text: &str) -> Result<Vec<InputFrame>, SessionError> {
+    parse_lines(text.lines().map(|l| Ok(l.to_string())))
+}
+
+pub fn load_session(path: &Path) -> Result<Vec<InputFrame>, SessionError> {
+    let reader = BufReader::new(File::open(path)?);
+    parse_lines(reader.lines())
+}
+
+fn parse_lines<I>(lines: I) -> Result<Vec<InputFrame>, SessionError>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    let mut frames: Vec<InputFrame> = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == FRAME_MARKER {
+            frames.push(InputFrame::default());
+            continue;
+        }
+        let event = line
+            .parse::<InputEvent>()
+            .map_err(|message| SessionError::Parse {
+                line: i + 1,
+                message,
+            })?;
+        match frames.last_mut() {
+            Some(frame) => frame.events.push(event),
+            None => {
+                return Err(SessionError::Parse {
+                    line: i + 1,
+                    message: "event before the first `frame`".to_string(),
+                });
+            }
+        }
+    }
+    Ok(frames)
+}
+
+/// Appends frames to a session file as they happen, so a crash or a closed
+/// window still leaves a usable recording.
+pub struct SessionRecorder {
+    writer: BufWriter<File>,
+}
+
+impl SessionRecorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "# reeds-shepp visualizer input session")?;
+        Ok(SessionRecorder { writer })
+    }
+
+    pub fn record(&mut self, frame: &InputFrame) -> io::Result<()> {
+        write_frame(&mut self.writer, frame)?;
+        self.writer.flush()
+    }
+}
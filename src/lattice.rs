@@ -0,0 +1,334 @@
+//! State lattice planner with Reeds-Shepp motion primitives.
+//!
+//! Positions are discretized on a square grid of `spacing` and headings into
+//! [`LATTICE_HEADINGS`] equal steps. For every start heading the primitive
+//! set holds the optimal Reeds-Shepp paths from the origin to each lattice
+//! state within [`PRIMITIVE_REACH`] cells, keeping those no longer than the
+//! maximum primitive length. Primitives only depend on the start heading, so
+//! A* reuses them at every lattice position by translation.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::io::{self, Write};
+
+use reeds_shepp_lib::{Path, Pose, get_optimal_path, path_length};
+
+use crate::candidates::{poses_match, reaches_goal};
+use crate::obstacle::{Obstacle, pose_collides};
+use crate::path::{path_word_with_precision, sample_path};
+use crate::prm::{COLLISION_CHECK_STEP, connect};
+
+// --- Constants ---
+pub const LATTICE_HEADINGS: usize = 16;
+/// Primitives end at most this many cells away along each axis.
+pub const PRIMITIVE_REACH: i32 = 4;
+pub const DEFAULT_LATTICE_SPACING: f64 = 0.5;
+pub const MIN_LATTICE_SPACING: f64 = 0.25;
+pub const MAX_LATTICE_SPACING: f64 = 1.0;
+pub const DEFAULT_MAX_PRIMITIVE_LENGTH: f64 = 2.0;
+pub const MIN_MAX_PRIMITIVE_LENGTH: f64 = 1.0;
+pub const MAX_MAX_PRIMITIVE_LENGTH: f64 = 4.0;
+/// A* gives up after this many expansions.
+pub const MAX_EXPANSIONS: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatticeSettings {
+    pub spacing: f64,
+    pub max_primitive_length: f64,
+}
+
+impl Default for LatticeSettings {
+    fn default() -> Self {
+        LatticeSettings {
+            spacing: DEFAULT_LATTICE_SPACING,
+            max_primitive_length: DEFAULT_MAX_PRIMITIVE_LENGTH,
+        }
+    }
+}
+
+/// Grid cell and heading index of a lattice state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LatticeState {
+    pub ix: i32,
+    pub iy: i32,
+    pub heading: usize,
+}
+
+/// Optimal path from `(0, 0, start_heading)` to `(dx, dy, end_heading)`,
+/// in cells and heading indices.
+#[derive(Debug, Clone)]
+pub struct MotionPrimitive {
+    pub start_heading: usize,
+    pub dx: i32,
+    pub dy: i32,
+    pub end_heading: usize,
+    pub path: Path,
+    pub length: f64,
+    /// Poses along the path from the origin, for collision checks.
+    footprint: Vec<Pose>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrimitiveSet {
+    pub settings: LatticeSettings,
+    /// Primitives indexed by start heading.
+    pub by_heading: Vec<Vec<MotionPrimitive>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LatticeSolution {
+    /// Connector onto the lattice, the primitives, and the connector off it,
+    /// driven from the query start.
+    pub path: Path,
+    pub length: f64,
+    pub states: Vec<LatticeState>,
+    pub expansions: usize,
+}
+
+pub fn heading_degree(heading: usize) -> f64 {
+    let degree = heading as f64 * 360.0 / LATTICE_HEADINGS as f64;
+    if degree > 180.0 {
+        degree - 360.0
+    } else {
+        degree
+    }
+}
+
+impl PrimitiveSet {
+    pub fn generate(settings: LatticeSettings) -> Self {
+        let mut by_heading = Vec::with_capacity(LATTICE_HEADINGS);
+        for start_heading in 0..LATTICE_HEADINGS {
+            let origin = Pose {
+                x: 0.0,
+                y: 0.0,
+                theta_degree: heading_degree(start_heading),
+            };
+            let mut primitives = Vec::new();
+            for dx in -PRIMITIVE_REACH..=PRIMITIVE_REACH {
+                for dy in -PRIMITIVE_REACH..=PRIMITIVE_REACH {
+                    for end_heading in 0..LATTICE_HEADINGS {
+                        if dx == 0 && dy == 0 && end_heading == start_heading {
+                            continue;
+                        }
+                        let target = Pose {
+                            x: dx as f64 * settings.spacing,
+                            y: dy as f64 * settings.spacing,
+                            theta_degree: heading_degree(end_heading),
+                        };
+                        let Some(path) = get_optimal_path(origin, target) else {
+                            continue;
+                        };
+                        let length = path_length(&path);
+                        if length > settings.max_primitive_length
+                            || !reaches_goal(&origin, &target, &path)
+                        {
+                            continue;
+                        }
+                        let footprint = sample_path(&origin, &path, COLLISION_CHECK_STEP)
+                            .iter()
+                            .map(|s| s.pose())
+                            .collect();
+                        primitives.push(MotionPrimitive {
+                            start_heading,
+                            dx,
+                            dy,
+                            end_heading,
+                            path,
+                            length,
+                            footprint,
+                        });
+                    }
+                }
+            }
+            by_heading.push(primitives);
+        }
+        PrimitiveSet {
+            settings,
+            by_heading,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_heading.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn pose(&self, state: &LatticeState) -> Pose {
+        Pose {
+            x: state.ix as f64 * self.settings.spacing,
+            y: state.iy as f64 * self.settings.spacing,
+            theta_degree: heading_degree(state.heading),
+        }
+    }
+
+    /// Closest lattice state to `pose`.
+    pub fn nearest_state(&self, pose: &Pose) -> LatticeState {
+        let step = 360.0 / LATTICE_HEADINGS as f64;
+        let heading = (pose.theta_degree / step).round() as i64;
+        LatticeState {
+            ix: (pose.x / self.settings.spacing).round() as i32,
+            iy: (pose.y / self.settings.spacing).round() as i32,
+            heading: heading.rem_euclid(LATTICE_HEADINGS as i64) as usize,
+        }
+    }
+
+    fn primitive_collides(
+        &self,
+        from: &LatticeState,
+        primitive: &MotionPrimitive,
+        obstacles: &[Obstacle],
+    ) -> bool {
+        let origin = self.pose(from);
+        primitive.footprint.iter().any(|p| {
+            let moved = Pose {
+                x: p.x + origin.x,
+                y: p.y + origin.y,
+                theta_degree: p.theta_degree,
+            };
+            pose_collides(&moved, obstacles)
+        })
+    }
+
+    /// A* from the lattice state nearest `start` to the one nearest `end`,
+    /// with collision-free optimal connectors from and to the exact poses.
+    /// The straight-line distance is the heuristic.
+    pub fn plan(
+        &self,
+        start: &Pose,
+        end: &Pose,
+        obstacles: &[Obstacle],
+    ) -> Option<LatticeSolution> {
+        let first = self.nearest_state(start);
+        let last = self.nearest_state(end);
+        let connector = |from: &Pose, to: &Pose| -> Option<Path> {
+            if poses_match(from, to) {
+                Some(Path::new())
+            } else {
+                connect(from, to, obstacles)
+            }
+        };
+        let onto = connector(start, &self.pose(&first))?;
+        let off = connector(&self.pose(&last), end)?;
+        if pose_collides(&self.pose(&first), obstacles) {
+            return None;
+        }
+
+        let goal = self.pose(&last);
+        let heuristic = |s: &LatticeState| {
+            let p = self.pose(s);
+            (p.x - goal.x).hypot(p.y - goal.y)
+        };
+        let mut cost: HashMap<LatticeState, f64> = HashMap::new();
+        let mut previous: HashMap<LatticeState, (LatticeState, usize)> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        cost.insert(first, 0.0);
+        heap.push(Entry(heuristic(&first), first));
+        let mut expansions = 0;
+        let mut found = false;
+        while let Some(Entry(f, state)) = heap.pop() {
+            let g = cost[&state];
+            if f > g + heuristic(&state) + 1e-9 {
+                continue;
+            }
+            if state == last {
+                found = true;
+                break;
+            }
+            expansions += 1;
+            if expansions > MAX_EXPANSIONS {
+                break;
+            }
+            for (i, primitive) in self.by_heading[state.heading].iter().enumerate() {
+                let next = LatticeState {
+                    ix: state.ix + primitive.dx,
+                    iy: state.iy + primitive.dy,
+                    heading: primitive.end_heading,
+                };
+                let candidate = g + primitive.length;
+                if cost.get(&next).is_some_and(|&c| c <= candidate)
+                    || self.primitive_collides(&state, primitive, obstacles)
+                {
+                    continue;
+                }
+                cost.insert(next, candidate);
+                previous.insert(next, (state, i));
+                heap.push(Entry(candidate + heuristic(&next), next));
+            }
+        }
+        if !found {
+            return None;
+        }
+
+        let mut states = vec![last];
+        let mut primitives = Vec::new();
+        let mut state = last;
+        while let Some(&(from, i)) = previous.get(&state) {
+            primitives.push(&self.by_heading[from.heading][i]);
+            states.push(from);
+            state = from;
+        }
+        states.reverse();
+        primitives.reverse();
+        let path: Path = onto
+            .into_iter()
+            .chain(primitives.iter().flat_map(|p| p.path.iter().copied()))
+            .chain(off)
+            .collect();
+        Some(LatticeSolution {
+            length: path_length(&path),
+            path,
+            states,
+            expansions,
+        })
+    }
+}
+
+/// Writes the primitive set as text, one primitive per line.
+pub fn write_primitives<W: Write>(writer: &mut W, set: &PrimitiveSet) -> io::Result<()> {
+    writeln!(writer, "# reeds-shepp lattice primitives")?;
+    writeln!(
+        writer,
+        "# spacing {} headings {} max_length {}",
+        set.settings.spacing, LATTICE_HEADINGS, set.settings.max_primitive_length
+    )?;
+    writeln!(writer, "# start_heading dx dy end_heading length word")?;
+    for p in set.by_heading.iter().flatten() {
+        writeln!(
+            writer,
+            "{} {} {} {} {:.6} {}",
+            p.start_heading,
+            p.dx,
+            p.dy,
+            p.end_heading,
+            p.length,
+            path_word_with_precision(&p.path, 6)
+        )?;
+    }
+    Ok(())
+}
+
+/// Heap entry ordered so that the smallest estimate pops first.
+struct Entry(f64, LatticeState);
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
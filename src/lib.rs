@@ -0,0 +1,32 @@
+//! Geometry and interaction core of the Reeds-Shepp visualizer.
+//!
+//! Everything in here works in world coordinates and has no rendering
+//! dependency; the macroquad binary in `main.rs` is a thin frontend on top.
+
+pub mod candidates;
+pub mod car;
+pub mod cc;
+pub mod construction;
+pub mod export;
+pub mod gallery;
+pub mod grid;
+pub mod heuristic;
+pub mod input;
+pub mod lattice;
+pub mod obstacle;
+pub mod path;
+pub mod prm;
+pub mod raster;
+pub mod reach;
+pub mod render;
+mod rng;
+pub mod scene;
+pub mod sketch;
+pub mod stability;
+pub mod state;
+pub mod stroke;
+pub mod sweep;
+pub mod trace;
+pub mod trailer;
+pub mod transforms;
+pub mod view;
@@ -1,487 +1,621 @@
 use macroquad::prelude::*;
 use macroquad::ui::{hash, root_ui, widgets};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use reeds_shepp_lib::{
-    Gear, PATH_FNS, Path, Pose, Steering, get_all_paths, get_optimal_path, path_length, utils,
+use reeds_shepp_lib::{Gear, path_length, utils};
+use visualize_reeds_shepp::candidates::transform_suffix;
+use visualize_reeds_shepp::cc;
+use visualize_reeds_shepp::export::{DEFAULT_GIF_FPS, export_gif, export_png, export_primitives};
+use visualize_reeds_shepp::gallery::GalleryLayout;
+use visualize_reeds_shepp::grid::{
+    MAX_ANGLE_INCREMENT_DEGREE, MAX_GRID_SPACING, MIN_ANGLE_INCREMENT_DEGREE, MIN_GRID_SPACING,
+    SnapSettings,
 };
+use visualize_reeds_shepp::heuristic::{HeuristicTable, LutGrid};
+use visualize_reeds_shepp::input::{
+    InputEvent, InputFrame, PointerInput, SessionRecorder, Shortcut, load_session,
+};
+use visualize_reeds_shepp::lattice::{
+    LatticeSettings, MAX_LATTICE_SPACING, MAX_MAX_PRIMITIVE_LENGTH, MIN_LATTICE_SPACING,
+    MIN_MAX_PRIMITIVE_LENGTH, heading_degree,
+};
+use visualize_reeds_shepp::path::{path_arc_length, path_word};
+use visualize_reeds_shepp::prm::{
+    MAX_PRM_NEIGHBORS, MAX_PRM_SAMPLES, MIN_PRM_NEIGHBORS, MIN_PRM_SAMPLES,
+};
+use visualize_reeds_shepp::reach::{MAX_REACH_BUDGET, MIN_REACH_BUDGET, ReachSettings};
+use visualize_reeds_shepp::render::{
+    LIGHTGRAY, Renderer, Rgba, ScreenPoint, TriangleMesh, WHITE, YELLOW,
+};
+use visualize_reeds_shepp::scene::{
+    CC_PATH_COLOR, CUSTOM_PATH_COLOR, END_CAR_COLOR, HOVERED_PATH_COLOR, INSPECTOR_COLOR,
+    JACKKNIFE_COLOR, REACH_OUTLINE_COLOR, RUNNER_UP_COLOR, SELECTED_PATH_COLOR, SKETCH_COLOR,
+    START_CAR_COLOR, draw_gallery, draw_heading_sweep, draw_scene, draw_transform_explorer,
+};
+use visualize_reeds_shepp::sketch::SketchMetric;
+use visualize_reeds_shepp::stability::{
+    MAX_GAP_THRESHOLD, MAX_HYSTERESIS_MARGIN, StabilitySettings,
+};
+use visualize_reeds_shepp::state::{AppState, DisplayMode, ModifyDragTarget, PathColoring, State};
+use visualize_reeds_shepp::sweep::SweepChart;
+use visualize_reeds_shepp::trace::GoalCircle;
+use visualize_reeds_shepp::trailer::TrailerModel;
+use visualize_reeds_shepp::transforms::{ExplorerLayout, TRANSFORMS};
+use visualize_reeds_shepp::view::View;
 
 // --- Constants ---
 const WINDOW_WIDTH: i32 = 1024;
 const WINDOW_HEIGHT: i32 = 768;
-const CAR_WIDTH: f32 = 30.0;
-const CAR_LENGTH: f32 = 50.0;
-const PATH_RESOLUTION: f64 = 30.0;
-const TURNING_RADIUS: f64 = 1.0;
-const DRAW_SCALE: f32 = 50.0;
-const HEADLIGHT_SIZE_SCREEN: f32 = 8.0;
-const BEAM_LENGTH: f32 = 60.0;
-const BEAM_WIDTH: f32 = 40.0;
-const TURNING_CIRCLE_OPACITY: f32 = 0.15;
+const UI_WIDTH: f32 = 220.0;
+const UI_MARGIN: f32 = 20.0;
+/// The panel spans the window height but never less than this.
+const UI_MIN_HEIGHT: f32 = 120.0;
+/// Indices per `draw_mesh` call, a multiple of 3 below macroquad's default
+/// draw call capacity.
+const MAX_MESH_INDICES: usize = 4998;
+/// Height macroquad gives a single-line text field.
+const TEXT_FIELD_HEIGHT: f32 = 19.0;
+const TOOLTIP_FONT_SIZE: u16 = 16;
+const TOOLTIP_LINE_HEIGHT: f32 = 18.0;
+const TOOLTIP_PADDING: f32 = 6.0;
+const PNG_EXPORT_FILE: &str = "reeds_shepp.png";
+const GIF_EXPORT_FILE: &str = "reeds_shepp.gif";
+const PRIMITIVES_EXPORT_FILE: &str = "lattice_primitives.txt";
+/// Lattice sliders snap to multiples of this.
+const LATTICE_SLIDER_STEP: f64 = 0.25;
+/// Top of the gallery, below the coordinate readout.
+const GALLERY_TOP: f32 = 80.0;
+/// Top of the overlay column on the left, below the drag mode readout.
+const OVERLAY_TOP: f32 = 110.0;
+/// Space between overlays side by side or stacked.
+const OVERLAY_GAP: f32 = 10.0;
+/// Room below the overlay column for the status lines.
+const OVERLAY_BOTTOM_MARGIN: f32 = 215.0;
+/// Room below the gallery for the pose and length status lines.
+const GALLERY_BOTTOM_MARGIN: f32 = 70.0;
 
 // --- Colors ---
-const BG_COLOR: Color = Color::new(0.15, 0.15, 0.18, 1.0);
-const START_CAR_COLOR: Color = Color::new(0.7, 0.9, 0.7, 1.0);
-const END_CAR_COLOR: Color = Color::new(0.4, 0.5, 0.9, 1.0);
-const SELECTED_PATH_COLOR: Color = Color::new(1.0, 0.6, 0.1, 1.0);
-const ALL_PATHS_COLOR: Color = Color::new(0.8, 0.8, 0.8, 0.3);
-const HEADLIGHT_COLOR: Color = Color::new(1.0, 1.0, 0.7, 1.0);
-const BEAM_COLOR: Color = Color::new(1.0, 1.0, 0.5, 0.4);
-const TURNING_CIRCLE_COLOR: Color = Color::new(0.8, 0.8, 0.8, TURNING_CIRCLE_OPACITY);
-
-// --- Enums ---
-#[derive(PartialEq, Debug, Clone, Copy)]
-enum AppState {
-    PlacingStart,
-    DefiningStartAngle,
-    PlacingEnd,
-    DefiningEndAngle,
-    DisplayingPaths,
-}
+const TOOLTIP_BG_COLOR: Rgba = Rgba::new(0.08, 0.08, 0.1, 0.85);
 
-#[derive(Clone, Copy, Debug)]
-struct InitialDragState {
-    start_pos: Vec2,
-    current_pos: Vec2,
-}
+/// Draws scene primitives straight into the macroquad frame.
+struct MacroquadRenderer;
 
-#[derive(PartialEq, Debug, Clone, Copy)]
-enum ModifyDragTarget {
-    StartBody,
-    StartAngle,
-    EndBody,
-    EndAngle,
+fn color(c: Rgba) -> Color {
+    Color::new(c.r, c.g, c.b, c.a)
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum DisplayMode {
-    ShortestPath,
-    AllPaths,
-    SinglePath(usize),
+fn point(p: ScreenPoint) -> Vec2 {
+    vec2(p.0, p.1)
 }
 
-// --- State Struct ---
-struct State {
-    app_state: AppState,
-    start_pose: Option<Pose>,
-    end_pose: Option<Pose>,
-    drag_state_initial: Option<InitialDragState>,
-    dragging_modify: Option<ModifyDragTarget>,
-    display_mode: DisplayMode,
-    reflect_path: bool,
-    timeflip_path: bool,
-    current_path_points: Option<Vec<Vec2>>,
-    current_raw_path: Option<Path>,
-    all_paths_points: Vec<Vec<Vec2>>,
-    combo_box_selected_index: usize,
-}
+impl Renderer for MacroquadRenderer {
+    fn clear(&mut self, c: Rgba) {
+        clear_background(color(c));
+    }
 
-impl State {
-    fn new() -> Self {
-        State {
-            app_state: AppState::PlacingStart,
-            start_pose: None,
-            end_pose: None,
-            drag_state_initial: None,
-            dragging_modify: None,
-            display_mode: DisplayMode::ShortestPath,
-            reflect_path: false,
-            timeflip_path: false,
-            current_path_points: None,
-            current_raw_path: None,
-            all_paths_points: Vec::new(),
-            combo_box_selected_index: 0,
-        }
-    }
-
-    fn reset(&mut self) {
-        println!("State reset.");
-        let default_mode = DisplayMode::ShortestPath;
-        let default_combo_index = 0;
-        *self = State::new();
-        self.display_mode = default_mode;
-        self.combo_box_selected_index = default_combo_index;
-    }
-
-    fn set_display_mode_from_index(&mut self, index: usize) {
-        self.combo_box_selected_index = index;
-        self.display_mode = match index {
-            0 => DisplayMode::ShortestPath,
-            1 => DisplayMode::AllPaths,
-            i if (2..=13).contains(&i) => DisplayMode::SinglePath(i - 2),
-            _ => DisplayMode::ShortestPath, // Fallback
-        };
-        if !matches!(self.display_mode, DisplayMode::SinglePath(_)) {
-            self.reflect_path = false;
-            self.timeflip_path = false;
-        }
+    fn line(&mut self, a: ScreenPoint, b: ScreenPoint, thickness: f32, c: Rgba) {
+        draw_line(a.0, a.1, b.0, b.1, thickness, color(c));
     }
 
-    fn screen_to_world(&self, screen_pos: Vec2) -> (f64, f64) {
-        (
-            ((screen_pos.x - WINDOW_WIDTH as f32 / 2.0) / DRAW_SCALE) as f64,
-            ((WINDOW_HEIGHT as f32 / 2.0 - screen_pos.y) / DRAW_SCALE) as f64,
-        )
+    fn circle(&mut self, center: ScreenPoint, radius: f32, c: Rgba) {
+        draw_circle(center.0, center.1, radius, color(c));
     }
 
-    fn world_to_screen_static(world_x: f64, world_y: f64) -> Vec2 {
-        vec2(
-            world_x as f32 * DRAW_SCALE + WINDOW_WIDTH as f32 / 2.0,
-            WINDOW_HEIGHT as f32 / 2.0 - world_y as f32 * DRAW_SCALE,
-        )
+    fn circle_lines(&mut self, center: ScreenPoint, radius: f32, thickness: f32, c: Rgba) {
+        draw_circle_lines(center.0, center.1, radius, thickness, color(c));
     }
 
-    fn calculate_initial_drag_angle(&self) -> Option<f64> {
-        if let Some(drag) = &self.drag_state_initial {
-            let d = drag.current_pos - drag.start_pos;
-            if d.length_squared() > 5.0 * 5.0 {
-                let a = d.y.atan2(d.x);
-                return Some((-a).to_degrees() as f64);
-            }
-        }
-        None
+    fn triangle(&mut self, a: ScreenPoint, b: ScreenPoint, c3: ScreenPoint, c: Rgba) {
+        draw_triangle(point(a), point(b), point(c3), color(c));
     }
 
-    fn check_body_hit(&self, world_click_pos: (f64, f64), pose: &Pose) -> bool {
-        let angle_rad = pose.theta_degree.to_radians();
-        let cos_a = angle_rad.cos();
-        let sin_a = angle_rad.sin();
-        let dx = world_click_pos.0 - pose.x;
-        let dy = world_click_pos.1 - pose.y;
-        let local_x = dx * cos_a + dy * sin_a;
-        let local_y = (-dx * sin_a) + dy * cos_a;
-        let h_len = (CAR_LENGTH / DRAW_SCALE / 2.0) as f64;
-        let h_wid = (CAR_WIDTH / DRAW_SCALE / 2.0) as f64;
-        local_x.abs() <= h_len && local_y.abs() <= h_wid
+    fn rectangle(&mut self, center: ScreenPoint, width: f32, height: f32, rotation: f32, c: Rgba) {
+        draw_rectangle_ex(
+            center.0,
+            center.1,
+            width,
+            height,
+            DrawRectangleParams {
+                offset: vec2(0.5, 0.5),
+                rotation,
+                color: color(c),
+            },
+        );
     }
 
-    fn get_headlight_world_pos(pose: &Pose) -> (f64, f64) {
-        let angle_rad = pose.theta_degree.to_radians();
-        let off = (CAR_LENGTH / DRAW_SCALE / 2.0) as f64;
-        (
-            pose.x + off * angle_rad.cos(),
-            pose.y + off * angle_rad.sin(),
-        )
+    fn text(&mut self, text: &str, position: ScreenPoint, size: f32, c: Rgba) {
+        draw_text(text, position.0, position.1, size, color(c));
     }
 
-    fn check_headlight_hit(&self, world_click_pos: (f64, f64), pose: &Pose) -> bool {
-        let (hx, hy) = Self::get_headlight_world_pos(pose);
-        let dx = world_click_pos.0 - hx;
-        let dy = world_click_pos.1 - hy;
-        let d_sq = dx * dx + dy * dy;
-        let r = (HEADLIGHT_SIZE_SCREEN / DRAW_SCALE * 1.5) as f64;
-        d_sq < r * r
-    }
-
-    // Calculate display data based on mode
-    fn calculate_display_data(&mut self) {
-        self.current_path_points = None;
-        self.current_raw_path = None;
-        self.all_paths_points.clear();
-        if let (Some(start), Some(end)) = (self.start_pose.as_ref(), self.end_pose.as_ref()) {
-            match self.display_mode {
-                DisplayMode::SinglePath(index) => {
-                    let relative_pose = utils::change_of_basis(start, end);
-                    let mut x = relative_pose.x;
-                    let mut y = relative_pose.y;
-                    let mut theta_degree = relative_pose.theta_degree;
-                    let reflect = self.reflect_path;
-                    let timeflip = self.timeflip_path;
-                    if reflect {
-                        y = -y;
-                        theta_degree = -theta_degree;
-                    }
-                    if timeflip {
-                        x = -x;
-                        theta_degree = if reflect { theta_degree } else { -theta_degree };
-                    }
-                    if let Some(path_fn) = PATH_FNS.get(index) {
-                        let mut path = path_fn(x, y, theta_degree);
-                        // *** Use Module Path for transforms ***
-                        if timeflip {
-                            path = reeds_shepp_lib::timeflip(path);
-                        }
-                        if reflect {
-                            path = reeds_shepp_lib::reflect(path);
-                        }
-                        if !path.is_empty() {
-                            let points = generate_path_points(start, &path, PATH_RESOLUTION);
-                            if !points.is_empty() {
-                                self.current_path_points = Some(points);
-                                self.current_raw_path = Some(path);
-                            }
-                        }
-                    }
-                }
-                DisplayMode::ShortestPath => {
-                    if let Some(path) = get_optimal_path(*start, *end) {
-                        if !path.is_empty() {
-                            let points = generate_path_points(start, &path, PATH_RESOLUTION);
-                            if !points.is_empty() {
-                                self.current_path_points = Some(points);
-                                self.current_raw_path = Some(path);
-                            }
-                        }
-                    }
-                }
-                DisplayMode::AllPaths => {
-                    let all_raw = get_all_paths(*start, *end);
-                    let mut shortest: Option<Path> = None;
-                    let mut shortest_len = f64::INFINITY;
-                    for path in all_raw {
-                        if path.is_empty() {
-                            continue;
-                        }
-                        let points = generate_path_points(start, &path, PATH_RESOLUTION);
-                        if !points.is_empty() {
-                            self.all_paths_points.push(points);
-                            let len = path_length(&path);
-                            if len < shortest_len {
-                                shortest_len = len;
-                                shortest = Some(path);
-                            }
-                        }
-                    }
-                    if let Some(s_path) = shortest {
-                        let s_points = generate_path_points(start, &s_path, PATH_RESOLUTION);
-                        if !s_points.is_empty() {
-                            self.current_path_points = Some(s_points);
-                            self.current_raw_path = Some(s_path);
-                        } else if !self.all_paths_points.is_empty() {
-                            self.current_path_points = self.all_paths_points.first().cloned();
-                        }
-                    }
-                }
+    fn mesh(&mut self, mesh: &TriangleMesh) {
+        // macroquad clamps oversized draw calls, so split into chunks of
+        // whole triangles with their vertices remapped.
+        for triangles in mesh.indices.chunks(MAX_MESH_INDICES) {
+            let mut remap: HashMap<u32, u16> = HashMap::new();
+            let mut chunk = Mesh {
+                vertices: Vec::new(),
+                indices: Vec::with_capacity(triangles.len()),
+                texture: None,
+            };
+            for &i in triangles {
+                let index = *remap.entry(i).or_insert_with(|| {
+                    let v = mesh.vertices[i as usize];
+                    chunk.vertices.push(Vertex::new(
+                        v.position.0,
+                        v.position.1,
+                        0.0,
+                        0.0,
+                        0.0,
+                        color(v.color),
+                    ));
+                    (chunk.vertices.len() - 1) as u16
+                });
+                chunk.indices.push(index);
             }
+            draw_mesh(&chunk);
         }
     }
 }
 
-fn generate_path_points(start_pose: &Pose, path: &Path, resolution: f64) -> Vec<Vec2> {
-    if path.is_empty() {
-        return Vec::new();
-    }
-    let mut points = Vec::new();
-    let mut current_x = start_pose.x;
-    let mut current_y = start_pose.y;
-    let mut current_theta_rad = utils::normalize_angle_rad(start_pose.theta_degree.to_radians());
-    points.push(State::world_to_screen_static(current_x, current_y));
-    for element in path {
-        let param = element.param;
-        if param < 1e-10 {
-            continue;
-        }
-        let len_res = match element.steering {
-            Steering::Straight => param,
-            Steering::Left | Steering::Right => param.abs() * TURNING_RADIUS,
-        };
-        let n_steps = ((len_res * resolution).ceil().max(1.0)) as usize;
-        let g_mult = match element.gear {
-            Gear::Forward => 1.0,
-            Gear::Backwards => -1.0,
-        };
-        let mut nx;
-        let mut ny;
-        let mut nt;
-        for _i in 1..=n_steps {
-            match element.steering {
-                Steering::Straight => {
-                    let d = param / n_steps as f64 * g_mult;
-                    nx = current_x + d * current_theta_rad.cos();
-                    ny = current_y + d * current_theta_rad.sin();
-                    nt = current_theta_rad;
-                }
-                Steering::Left => {
-                    let a = param / n_steps as f64 * g_mult;
-                    nt = utils::normalize_angle_rad(current_theta_rad + a);
-                    let dx = TURNING_RADIUS * (nt.sin() - current_theta_rad.sin());
-                    let dy = TURNING_RADIUS * (current_theta_rad.cos() - nt.cos());
-                    nx = current_x + dx;
-                    ny = current_y + dy;
-                }
-                Steering::Right => {
-                    let a = param / n_steps as f64 * g_mult;
-                    nt = utils::normalize_angle_rad(current_theta_rad - a);
-                    let dx = TURNING_RADIUS * (current_theta_rad.sin() - nt.sin());
-                    let dy = TURNING_RADIUS * (nt.cos() - current_theta_rad.cos());
-                    nx = current_x + dx;
-                    ny = current_y + dy;
-                }
-            };
-            current_x = nx;
-            current_y = ny;
-            current_theta_rad = nt;
-            points.push(State::world_to_screen_static(current_x, current_y));
-        }
-    }
-    points
+/// Side panel rectangle in logical pixels, anchored to the top right corner
+/// of the current window and as tall as it. Widgets that do not fit scroll
+/// inside the panel.
+fn ui_panel_rect(view: &View) -> Rect {
+    let height = (view.height - 2.0 * UI_MARGIN).max(UI_MIN_HEIGHT);
+    Rect::new(
+        view.width - UI_WIDTH - UI_MARGIN,
+        UI_MARGIN,
+        UI_WIDTH,
+        height,
+    )
 }
 
-fn draw_pose_elements(pose: &Pose, body_color: Color) {
-    let cs = State::world_to_screen_static(pose.x, pose.y);
-    let r_rad_w = pose.theta_degree.to_radians();
-    let r_rad_s = -r_rad_w as f32;
-    let (hx_w, hy_w) = State::get_headlight_world_pos(pose);
-    let hs = State::world_to_screen_static(hx_w, hy_w);
-    let b_dir = Vec2::from_angle(r_rad_s).normalize();
-    let b_norm = vec2(-b_dir.y, b_dir.x);
-    let bs1 = hs + b_norm * (HEADLIGHT_SIZE_SCREEN * 0.5);
-    let bs2 = hs - b_norm * (HEADLIGHT_SIZE_SCREEN * 0.5);
-    let be1 = hs + b_dir * BEAM_LENGTH + b_norm * (BEAM_WIDTH * 0.5);
-    let be2 = hs + b_dir * BEAM_LENGTH - b_norm * (BEAM_WIDTH * 0.5);
-    draw_triangle(bs1, be1, be2, BEAM_COLOR);
-    draw_triangle(bs1, be2, bs2, BEAM_COLOR);
-    draw_rectangle_ex(
-        cs.x,
-        cs.y,
-        CAR_LENGTH,
-        CAR_WIDTH,
-        DrawRectangleParams {
-            offset: vec2(0.5, 0.5),
-            rotation: r_rad_s,
-            color: body_color,
-            ..Default::default()
-        },
-    );
-    draw_circle(hs.x, hs.y, HEADLIGHT_SIZE_SCREEN, RED);
-    draw_circle(hs.x, hs.y, HEADLIGHT_SIZE_SCREEN * 0.6, HEADLIGHT_COLOR);
+/// Right edge of the canvas area that overlays may cover, left of the panel.
+fn overlay_right(view: &View) -> f32 {
+    ui_panel_rect(view).x - UI_MARGIN
 }
 
-fn draw_paths(state: &State) {
-    match state.display_mode {
-        DisplayMode::SinglePath(_) | DisplayMode::ShortestPath => {
-            if let Some(points) = &state.current_path_points {
-                if points.len() > 1 {
-                    for i in 0..(points.len() - 1) {
-                        draw_line(
-                            points[i].x,
-                            points[i].y,
-                            points[i + 1].x,
-                            points[i + 1].y,
-                            3.0,
-                            SELECTED_PATH_COLOR,
-                        );
-                    }
-                }
-            }
-        }
-        DisplayMode::AllPaths => {
-            for points in &state.all_paths_points {
-                if points.len() > 1 {
-                    for i in 0..(points.len() - 1) {
-                        draw_line(
-                            points[i].x,
-                            points[i].y,
-                            points[i + 1].x,
-                            points[i + 1].y,
-                            1.0,
-                            ALL_PATHS_COLOR,
-                        );
-                    }
-                }
-            }
-            if let Some(points) = &state.current_path_points {
-                if points.len() > 1 {
-                    for i in 0..(points.len() - 1) {
-                        draw_line(
-                            points[i].x,
-                            points[i].y,
-                            points[i + 1].x,
-                            points[i + 1].y,
-                            3.0,
-                            SELECTED_PATH_COLOR,
-                        );
-                    }
-                }
-            }
-        }
+/// Heading sweep chart at the top of the overlay column.
+fn sweep_chart(view: &View) -> SweepChart {
+    SweepChart::fit(UI_MARGIN, OVERLAY_TOP, overlay_right(view) - UI_MARGIN)
+}
+
+/// Transform grid under the sweep chart, or in its place when the chart is
+/// hidden, shrunk to end above the status lines.
+fn explorer_layout(view: &View, state: &State) -> ExplorerLayout {
+    let top = if state.heading_sweep.is_empty() {
+        OVERLAY_TOP
+    } else {
+        sweep_chart(view).bottom() + OVERLAY_GAP
+    };
+    ExplorerLayout::fit(
+        UI_MARGIN,
+        top,
+        overlay_right(view) - UI_MARGIN,
+        view.height - OVERLAY_BOTTOM_MARGIN - top,
+    )
+}
+
+/// Right edge of the overlays shown in the left column, if any.
+fn overlay_column_right(view: &View, state: &State) -> Option<f32> {
+    let chart = (!state.heading_sweep.is_empty()).then(|| sweep_chart(view).right());
+    let explorer = state.transform_explorer.as_ref().map(|_| {
+        let layout = explorer_layout(view, state);
+        layout.left + layout.width()
+    });
+    chart.into_iter().chain(explorer).reduce(f32::max)
+}
+
+/// Gallery thumbnails fitted between the overlay column, or the left edge
+/// without one, and the UI panel.
+fn gallery_layout(view: &View, state: &State, count: usize) -> GalleryLayout {
+    let left = overlay_column_right(view, state).map_or(UI_MARGIN, |right| right + OVERLAY_GAP);
+    GalleryLayout::fit(
+        left,
+        GALLERY_TOP,
+        overlay_right(view) - left,
+        view.height - GALLERY_TOP - GALLERY_BOTTOM_MARGIN,
+        count,
+    )
+}
+
+/// Events that show `family` under the given transform as the single path.
+fn select_variant_events(
+    state: &State,
+    family: usize,
+    (reflect, timeflip): (bool, bool),
+) -> Vec<InputEvent> {
+    let mut events = Vec::new();
+    let mode = DisplayMode::SinglePath(family);
+    if state.display_mode != mode {
+        events.push(InputEvent::SelectDisplayMode(mode.combo_index()));
+    }
+    events.push(InputEvent::SetReflect(reflect));
+    events.push(InputEvent::SetTimeflip(timeflip));
+    events
+}
+
+/// Key that triggers `shortcut`.
+fn shortcut_key_code(shortcut: Shortcut) -> KeyCode {
+    match shortcut {
+        Shortcut::Reset => KeyCode::R,
+        Shortcut::ExportPng => KeyCode::P,
+        Shortcut::ExportPrimitives => KeyCode::L,
+        Shortcut::ExportGif => KeyCode::G,
     }
 }
 
-fn draw_path_turning_circles(path: &Path, start_pose: &Pose) {
-    if path.is_empty() {
+fn tooltip_width(lines: &[String]) -> f32 {
+    let text_width = lines
+        .iter()
+        .map(|l| measure_text(l, None, TOOLTIP_FONT_SIZE, 1.0).width)
+        .fold(0.0, f32::max);
+    text_width + 2.0 * TOOLTIP_PADDING
+}
+
+/// What the single path's family computed, in a box left of the UI panel.
+fn draw_trace_panel(r: &mut impl Renderer, view: &View, state: &State) {
+    let Some(trace) = &state.family_trace else {
         return;
+    };
+    let degrees = |theta: f64| utils::normalize_angle_rad(theta.to_radians()).to_degrees();
+    let (rel, goal) = (&trace.relative, &trace.transformed);
+    let circle = match trace.goal_circle {
+        GoalCircle::Left => "left",
+        GoalCircle::Right => "right",
+    };
+    let mut lines = vec![
+        format!(
+            "Trace P{}{}",
+            trace.family + 1,
+            transform_suffix(trace.reflect, trace.timeflip)
+        ),
+        format!(
+            "Relative:    x={:+.3} y={:+.3} θ={:+.1}°",
+            rel.x,
+            rel.y,
+            degrees(rel.theta_degree)
+        ),
+        format!(
+            "Transformed: x={:+.3} y={:+.3} φ={:+.1}°",
+            goal.x,
+            goal.y,
+            degrees(goal.theta_degree)
+        ),
+        format!(
+            "Goal {} circle ({:+.3}, {:+.3}), ρ={:.3} in [{:.2}, {:.2}]",
+            circle,
+            trace.goal_circle_center.0,
+            trace.goal_circle_center.1,
+            trace.rho,
+            trace.rho_range.0,
+            trace.rho_range.1
+        ),
+    ];
+    if !trace.params.is_empty() {
+        let params: Vec<String> = trace
+            .params
+            .iter()
+            .map(|p| format!("{}={:+.3}", p.name, p.value))
+            .collect();
+        lines.push(format!("Raw: {}", params.join(" ")));
     }
-    let mut cx = start_pose.x;
-    let mut cy = start_pose.y;
-    let mut cth = utils::normalize_angle_rad(start_pose.theta_degree.to_radians());
-    let trs = TURNING_RADIUS as f32 * DRAW_SCALE;
-    for e in path {
-        let gm = match e.gear {
-            Gear::Forward => 1.0,
-            Gear::Backwards => -1.0,
-        };
-        match e.steering {
-            Steering::Straight => {
-                let d = e.param * gm;
-                cx += d * cth.cos();
-                cy += d * cth.sin();
-            }
-            Steering::Left => {
-                let tcx = cx - TURNING_RADIUS * cth.sin();
-                let tcy = cy + TURNING_RADIUS * cth.cos();
-                let tcs = State::world_to_screen_static(tcx, tcy);
-                draw_circle_lines(tcs.x, tcs.y, trs, 1.0, TURNING_CIRCLE_COLOR);
-                draw_circle(tcs.x, tcs.y, 2.0, TURNING_CIRCLE_COLOR);
-                let a = e.param * gm;
-                let nt = utils::normalize_angle_rad(cth + a);
-                cx = tcx + TURNING_RADIUS * nt.sin();
-                cy = tcy - TURNING_RADIUS * nt.cos();
-                cth = nt;
-            }
-            Steering::Right => {
-                let tcx = cx + TURNING_RADIUS * cth.sin();
-                let tcy = cy - TURNING_RADIUS * cth.cos();
-                let tcs = State::world_to_screen_static(tcx, tcy);
-                draw_circle_lines(tcs.x, tcs.y, trs, 1.0, TURNING_CIRCLE_COLOR);
-                draw_circle(tcs.x, tcs.y, 2.0, TURNING_CIRCLE_COLOR);
-                let a = e.param * gm;
-                let nt = utils::normalize_angle_rad(cth - a);
-                cx = tcx - TURNING_RADIUS * nt.sin();
-                cy = tcy + TURNING_RADIUS * nt.cos();
-                cth = nt;
-            }
-        }
+    let (verdict, color) = match &trace.infeasibility {
+        None => ("Feasible".to_string(), WHITE),
+        Some(reason) => (
+            format!("Infeasible: {}", reason),
+            JACKKNIFE_COLOR.with_alpha(1.0),
+        ),
+    };
+    lines.push(verdict);
+    let left = overlay_right(view) - tooltip_width(&lines);
+    draw_tooltip_at(r, view, (left, UI_MARGIN), &lines, color);
+}
+
+/// Box of text lines next to `anchor`, kept inside the window.
+fn draw_tooltip(
+    r: &mut impl Renderer,
+    view: &View,
+    anchor: ScreenPoint,
+    lines: &[String],
+    text_color: Rgba,
+) {
+    draw_tooltip_at(
+        r,
+        view,
+        (anchor.0 + 16.0, anchor.1 + 16.0),
+        lines,
+        text_color,
+    );
+}
+
+/// Box of text lines with its top left corner at `corner`, kept inside the
+/// window and at least `UI_MARGIN` from its left and top edges.
+fn draw_tooltip_at(
+    r: &mut impl Renderer,
+    view: &View,
+    corner: ScreenPoint,
+    lines: &[String],
+    text_color: Rgba,
+) {
+    let width = tooltip_width(lines);
+    let height = TOOLTIP_LINE_HEIGHT * lines.len() as f32 + TOOLTIP_PADDING;
+    // The left and top margins win when the box is wider or taller than the
+    // window, so its first characters and lines stay readable.
+    let x = corner.0.min(view.width - width).max(UI_MARGIN);
+    let y = corner.1.min(view.height - height).max(UI_MARGIN);
+    r.rectangle(
+        (x + width / 2.0, y + height / 2.0),
+        width,
+        height,
+        0.0,
+        TOOLTIP_BG_COLOR,
+    );
+    for (i, line) in lines.iter().enumerate() {
+        let baseline = y + TOOLTIP_LINE_HEIGHT * (i as f32 + 1.0);
+        r.text(
+            line,
+            (x + TOOLTIP_PADDING, baseline),
+            TOOLTIP_FONT_SIZE as f32,
+            text_color,
+        );
     }
 }
 
-fn draw_ui(state: &mut State) {
+/// Family, transform flags, word and length of the hovered candidate, drawn
+/// next to the pointer.
+fn draw_candidate_tooltip(r: &mut impl Renderer, view: &View, state: &State, mouse: ScreenPoint) {
+    let Some(c) = state
+        .hovered_candidate
+        .and_then(|i| state.all_candidates.get(i))
+    else {
+        return;
+    };
+    let lines = [
+        format!(
+            "Path {}{}",
+            c.family + 1,
+            transform_suffix(c.reflect, c.timeflip)
+        ),
+        path_word(&c.path),
+        format!("Len:{:.2}", c.length),
+    ];
+    draw_tooltip(r, view, mouse, &lines, HOVERED_PATH_COLOR);
+}
+
+/// Exact pose and driving state at the inspected point of the selected path.
+fn draw_inspector_tooltip(r: &mut impl Renderer, view: &View, state: &State) {
+    let (Some(sample), Some(path)) = (state.inspected_sample, &state.current_raw_path) else {
+        return;
+    };
+    let gear = match sample.gear {
+        Gear::Forward => "forward",
+        Gear::Backwards => "reverse",
+    };
+    let pin = if state.inspector_pinned {
+        " (pinned)"
+    } else {
+        ""
+    };
+    let lines = [
+        format!(
+            "({:.3},{:.3}) {:.2}°{}",
+            sample.x,
+            sample.y,
+            sample.theta.to_degrees(),
+            pin
+        ),
+        format!(
+            "s:{:.3} left:{:.3}",
+            sample.s,
+            path_arc_length(path) - sample.s
+        ),
+        format!(
+            "Seg {}/{} {} k:{:+.2}",
+            sample.segment + 1,
+            path.len(),
+            gear,
+            sample.curvature
+        ),
+    ];
+    let anchor = view.world_to_screen(sample.x, sample.y);
+    draw_tooltip(r, view, anchor, &lines, INSPECTOR_COLOR);
+}
+
+/// Draws the overlay text and side panel. Widget changes are returned as
+/// events instead of being written into `state` directly.
+fn draw_ui(r: &mut impl Renderer, view: &View, state: &State) -> Vec<InputEvent> {
     // Instructions Text / Coords / Pose Info / Path Info / Dragging Text / Angle Def Line
     let text = match state.app_state {
+        _ if state.obstacle_editing => "Drag to add an obstacle",
+        _ if state.sketch_enabled => "Sketch a maneuver: press, drag, release",
         AppState::PlacingStart => "Click START pos",
         AppState::DefiningStartAngle => "Drag/release START angle",
         AppState::PlacingEnd => "Click END pos",
         AppState::DefiningEndAngle => "Drag/release END angle",
-        AppState::DisplayingPaths => "Drag Body/H'light. Use UI. 'R' Reset.",
+        AppState::DisplayingPaths => {
+            "Drag Body/H'light. Use UI. 'R' Reset. 'P'/'G'/'L' PNG/GIF/prims."
+        }
     };
-    draw_text(text, 20.0, 30.0, 24.0, WHITE);
+    r.text(text, (20.0, 30.0), 24.0, WHITE);
     let mouse_pos_screen = mouse_position();
-    let (mouse_x_world, mouse_y_world) =
-        state.screen_to_world(vec2(mouse_pos_screen.0, mouse_pos_screen.1));
+    let (mouse_x_world, mouse_y_world) = view.screen_to_world(mouse_pos_screen);
     let coord_text = format!("World:({:.2},{:.2})", mouse_x_world, mouse_y_world);
-    draw_text(&coord_text, 20.0, 60.0, 20.0, LIGHTGRAY);
+    r.text(&coord_text, (20.0, 60.0), 20.0, LIGHTGRAY);
     if let Some(p) = state.start_pose {
         let t = format!("Start:({:.1},{:.1},{:.1}°)", p.x, p.y, p.theta_degree);
-        draw_text(&t, 20.0, WINDOW_HEIGHT as f32 - 60.0, 18.0, START_CAR_COLOR);
+        r.text(&t, (20.0, view.height - 60.0), 18.0, START_CAR_COLOR);
     }
     if let Some(p) = state.end_pose {
         let t = format!("End:  ({:.1},{:.1},{:.1}°)", p.x, p.y, p.theta_degree);
-        draw_text(&t, 20.0, WINDOW_HEIGHT as f32 - 40.0, 18.0, END_CAR_COLOR);
+        r.text(&t, (20.0, view.height - 40.0), 18.0, END_CAR_COLOR);
     }
     if let Some(ref p) = state.current_raw_path {
         let t = match state.display_mode {
             DisplayMode::SinglePath(idx) => format!("P{} Len:{:.2}", idx + 1, path_length(p)),
-            DisplayMode::ShortestPath | DisplayMode::AllPaths => {
+            DisplayMode::Prm => {
+                let via = state.prm_solution.as_ref().map_or(0, |s| s.via.len());
+                format!("PRM Len:{:.2} via {} nodes", path_length(p), via)
+            }
+            DisplayMode::Lattice => {
+                let steps = state
+                    .lattice_solution
+                    .as_ref()
+                    .map_or(0, |s| s.states.len().saturating_sub(1));
+                format!("Lattice Len:{:.2} in {} primitives", path_length(p), steps)
+            }
+            DisplayMode::LatticePrimitives => String::new(),
+            DisplayMode::ShortestPath | DisplayMode::AllPaths | DisplayMode::Gallery => {
                 format!("Shortest Len:{:.2}", path_length(p))
             }
         };
-        draw_text(
-            &t,
-            20.0,
-            WINDOW_HEIGHT as f32 - 20.0,
+        r.text(&t, (20.0, view.height - 20.0), 18.0, SELECTED_PATH_COLOR);
+    }
+    if let Some(c) = state.cc_comparison {
+        let t = format!("CC Len:{:.2} (+{:.2})", c.cc_length, c.length_overhead());
+        r.text(&t, (20.0, view.height - 80.0), 18.0, CC_PATH_COLOR);
+    } else if state.cc_enabled
+        && state
+            .current_raw_path
+            .as_ref()
+            .is_some_and(|p| !p.is_empty())
+    {
+        r.text(
+            "CC: no path of this word reaches the goal",
+            (20.0, view.height - 80.0),
             18.0,
-            SELECTED_PATH_COLOR,
+            CC_PATH_COLOR,
         );
     }
+    if let Some(t) = state.trailer_summary {
+        let text = format!(
+            "Trailer max hitch:{:.1}° Jackknife:{} ({} reverse)",
+            t.max_hitch_angle_degree, t.jackknifed_samples, t.jackknifed_in_reverse
+        );
+        let color = if t.jackknifed_samples > 0 {
+            JACKKNIFE_COLOR
+        } else {
+            LIGHTGRAY
+        };
+        r.text(&text, (20.0, view.height - 100.0), 18.0, color);
+    }
+    if let Some(e) = &state.custom_word_error {
+        let t = format!("Word: {}", e);
+        r.text(&t, (20.0, view.height - 120.0), 18.0, JACKKNIFE_COLOR);
+    } else if let Some(o) = state.custom_end_offset {
+        let t = format!(
+            "Word end vs goal: dx:{:+.2} dy:{:+.2} dθ:{:+.1}° (dist {:.2})",
+            o.x,
+            o.y,
+            o.theta_degree,
+            o.x.hypot(o.y)
+        );
+        r.text(&t, (20.0, view.height - 120.0), 18.0, CUSTOM_PATH_COLOR);
+    }
+    if let Some(fit) = state.sketch_fit {
+        let family = state
+            .sketch_fit_candidate
+            .as_ref()
+            .map_or(String::new(), |c| {
+                format!("P{} {} ", c.family + 1, path_word(&c.path))
+            });
+        let selected = state
+            .sketch_distance
+            .map_or(String::new(), |d| format!(", selected {:.2}", d));
+        let t = format!(
+            "Sketch best: {}{} {:.2}{}",
+            family,
+            fit.metric.name(),
+            fit.distance,
+            selected
+        );
+        r.text(&t, (20.0, view.height - 140.0), 18.0, SKETCH_COLOR);
+    }
+    if state.display_mode == DisplayMode::Prm
+        && let Some(roadmap) = &state.roadmap
+    {
+        let t = format!(
+            "Roadmap: {} nodes, {} edges{}",
+            roadmap.nodes.len(),
+            roadmap.edges.len(),
+            if state.prm_solution.is_none() {
+                ", no route"
+            } else {
+                ""
+            }
+        );
+        r.text(&t, (20.0, view.height - 160.0), 18.0, LIGHTGRAY);
+    }
+    if let Some(set) = &state.lattice_primitives
+        && matches!(
+            state.display_mode,
+            DisplayMode::Lattice | DisplayMode::LatticePrimitives
+        )
+    {
+        let shown = match (&state.display_mode, state.lattice_anchor) {
+            (DisplayMode::LatticePrimitives, Some(anchor)) => format!(
+                ", {} from {:.1}°",
+                set.by_heading[anchor.heading].len(),
+                heading_degree(anchor.heading)
+            ),
+            _ => match &state.lattice_solution {
+                Some(s) => format!(", {} expansions", s.expansions),
+                None if state.start_pose.is_some() && state.end_pose.is_some() => {
+                    ", no route".to_string()
+                }
+                None => String::new(),
+            },
+        };
+        let t = format!(
+            "Lattice: {} primitives, spacing {:.2}{} ('L' exports)",
+            set.len(),
+            set.settings.spacing,
+            shown
+        );
+        r.text(&t, (20.0, view.height - 160.0), 18.0, LIGHTGRAY);
+    }
+    if let Some(field) = state.reach_field()
+        && !state.reach_regions.is_empty()
+    {
+        let headings = match field.heading_degree {
+            Some(theta) => format!("end heading {:+.1}°", theta),
+            None => "any heading".to_string(),
+        };
+        let t = format!(
+            "Reach ≤{:.2}: {:.1} m² ({})",
+            state.reach_settings.budget,
+            field.area_under(state.reach_settings.budget),
+            headings
+        );
+        r.text(&t, (20.0, view.height - 180.0), 18.0, REACH_OUTLINE_COLOR);
+    }
+    if let Some(t) = &state.transition
+        && let Some(runner_up) = &t.runner_up
+        && (state.stability_settings.show_runner_up
+            || state.stability_settings.hysteresis
+            || t.is_near_switch(state.stability_settings.gap_threshold))
+    {
+        let held = if t.is_held() {
+            format!(", held P{} {:.2}", t.shown.family + 1, t.shown.length)
+        } else {
+            String::new()
+        };
+        let text = format!(
+            "Runner-up: P{} {} {:.2} (gap {:.3}){}",
+            runner_up.family + 1,
+            path_word(&runner_up.path),
+            runner_up.length,
+            t.gap().unwrap_or(0.0),
+            held
+        );
+        let color = if t.is_near_switch(state.stability_settings.gap_threshold) {
+            JACKKNIFE_COLOR.with_alpha(1.0)
+        } else {
+            RUNNER_UP_COLOR.with_alpha(1.0)
+        };
+        r.text(&text, (20.0, view.height - 200.0), 18.0, color);
+    }
     let drag_mode_text = match state.dragging_modify {
         Some(ModifyDragTarget::StartBody) => "Mov Start",
         Some(ModifyDragTarget::StartAngle) => "Rot Start",
@@ -490,65 +624,394 @@ fn draw_ui(state: &mut State) {
         None => "",
     };
     if !drag_mode_text.is_empty() {
-        draw_text(drag_mode_text, 20.0, 90.0, 20.0, YELLOW);
-    }
-    if let Some(drag) = &state.drag_state_initial {
-        if state.app_state == AppState::DefiningStartAngle
-            || state.app_state == AppState::DefiningEndAngle
-        {
-            draw_line(
-                drag.start_pos.x,
-                drag.start_pos.y,
-                drag.current_pos.x,
-                drag.current_pos.y,
-                2.0,
-                YELLOW,
-            );
-            if let Some(angle) = state.calculate_initial_drag_angle() {
-                let t = format!("{:.1}°", angle);
-                draw_text(
-                    &t,
-                    drag.current_pos.x + 10.0,
-                    drag.current_pos.y,
-                    20.0,
-                    YELLOW,
-                );
+        r.text(drag_mode_text, (20.0, 90.0), 20.0, YELLOW);
+    }
+    if let Some(drag) = &state.drag_state_initial
+        && (state.app_state == AppState::DefiningStartAngle
+            || state.app_state == AppState::DefiningEndAngle)
+    {
+        let start = view.world_to_screen(drag.start_pos.0, drag.start_pos.1);
+        let current = view.world_to_screen(drag.current_pos.0, drag.current_pos.1);
+        r.line(start, current, 2.0, YELLOW);
+        if let Some(angle) = state.calculate_initial_drag_angle() {
+            let t = format!("{:.1}°", angle);
+            r.text(&t, (current.0 + 10.0, current.1), 20.0, YELLOW);
+        }
+    }
+
+    if let Some(gallery) = &state.gallery {
+        let layout = gallery_layout(view, state, gallery.thumbs.len());
+        let hovered = layout.thumb_at(mouse_pos_screen, gallery.thumbs.len());
+        draw_gallery(r, &layout, state, hovered);
+        if let Some(thumb) = hovered.map(|i| &gallery.thumbs[i]) {
+            let mut lines = vec![format!("Path {} {}", thumb.family + 1, thumb.cell.label())];
+            match thumb.cell.length() {
+                Some(length) => {
+                    lines.push(path_word(&thumb.cell.path));
+                    lines.push(format!(
+                        "Len:{:.2} ({})",
+                        length,
+                        thumb.cell.feasibility.name()
+                    ));
+                }
+                None => lines.push(thumb.cell.feasibility.name().to_string()),
             }
+            draw_tooltip(r, view, mouse_pos_screen, &lines, HOVERED_PATH_COLOR);
         }
     }
+    let chart = sweep_chart(view);
+    draw_heading_sweep(r, &chart, state, chart.heading_at(mouse_pos_screen));
+    let explorer = explorer_layout(view, state);
+    draw_transform_explorer(r, &explorer, state, explorer.cell_at(mouse_pos_screen));
+    draw_trace_panel(r, view, state);
+    draw_inspector_tooltip(r, view, state);
+    draw_candidate_tooltip(r, view, state, mouse_pos_screen);
 
+    let mut events = Vec::new();
     if state.app_state == AppState::DisplayingPaths {
-        let ui_width = 220.0; // Keep wider width
-        let ui_x = WINDOW_WIDTH as f32 - ui_width - 20.0;
-        let ui_y = 20.0;
-        let ui_height = 120.0;
-
-        root_ui().window(hash!(), vec2(ui_x, ui_y), vec2(ui_width, ui_height), |ui| {
-            ui.label(None, "Display Mode:");
-            let mut mode_labels: Vec<String> =
-                vec!["Shortest Path".to_string(), "All Paths".to_string()];
-            mode_labels.extend((1..=12).map(|i| format!("Path {}", i)));
-            let mode_labels_str: Vec<&str> = mode_labels.iter().map(|s| s.as_str()).collect();
-
-            widgets::ComboBox::new(hash!("display_mode_select"), &mode_labels_str)
-                .ui(ui, &mut state.combo_box_selected_index);
-
-            ui.separator();
-
-            let is_single_path_mode = matches!(state.display_mode, DisplayMode::SinglePath(_));
-            if is_single_path_mode {
-                ui.checkbox(hash!("reflect_check"), "Reflect", &mut state.reflect_path);
+        let panel = ui_panel_rect(view);
+        let mut combo_box_selected_index = state.combo_box_selected_index;
+        let mut reflect = state.reflect_path;
+        let mut timeflip = state.timeflip_path;
+        let mut cc_enabled = state.cc_enabled;
+        let mut steering_rate = state.max_steering_rate as f32;
+        let mut color_by_steering = state.path_coloring == PathColoring::Steering;
+        let mut heading_sweep = state.heading_sweep_enabled;
+        let mut transform_explorer = state.transform_explorer_enabled;
+        let mut trace = state.trace_enabled;
+        let mut construction = state.construction_enabled;
+        let mut reach_enabled = state.reach_enabled;
+        let mut reach_union = state.reach_settings.union;
+        let mut reach_budget = state.reach_settings.budget as f32;
+        let mut show_runner_up = state.stability_settings.show_runner_up;
+        let mut gap_threshold = state.stability_settings.gap_threshold as f32;
+        let mut hysteresis = state.stability_settings.hysteresis;
+        let mut hysteresis_margin = state.stability_settings.margin as f32;
+        let mut custom_word = state.custom_word.clone();
+        let mut word_top = 0.0;
+        let mut sketch_enabled = state.sketch_enabled;
+        let mut obstacle_editing = state.obstacle_editing;
+        let mut clear_obstacles = false;
+        let mut cspace_enabled = state.cspace_enabled;
+        let mut cspace_heading = state.cspace_heading_degree as f32;
+        let mut prm_samples = state.prm_settings.samples as f32;
+        let mut prm_neighbors = state.prm_settings.neighbors as f32;
+        let mut rebuild_roadmap = false;
+        let mut lattice_spacing = state.lattice_settings.spacing as f32;
+        let mut max_primitive_length = state.lattice_settings.max_primitive_length as f32;
+        let mut sketch_hausdorff = state.sketch_metric == SketchMetric::Hausdorff;
+        let mut trailer_enabled = state.trailer_enabled;
+        let mut hitch_offset = state.trailer_model.hitch_offset as f32;
+        let mut trailer_length = state.trailer_model.trailer_length as f32;
+        let mut jackknife_limit = state.trailer_model.jackknife_limit_degree as f32;
+        let mut grid_visible = state.grid_visible;
+        let mut snap_to_grid = state.snap.to_grid;
+        let mut grid_spacing = state.snap.grid_spacing as f32;
+        let mut snap_to_angles = state.snap.to_angles;
+        let mut angle_increment = state.snap.angle_increment_degree as f32;
+
+        // Not movable, so the position given here is re-applied every frame
+        // and the panel follows the window's right edge. Its size is applied
+        // every frame too, and the window shows a scroll bar once the widgets
+        // are taller than the screen.
+        widgets::Window::new(hash!(), panel.point(), panel.size())
+            .titlebar(false)
+            .movable(false)
+            .ui(&mut root_ui(), |ui| {
+                ui.label(None, "Display Mode:");
+                let mode_labels = DisplayMode::combo_labels();
+                let mode_labels_str: Vec<&str> = mode_labels.iter().map(|s| s.as_str()).collect();
+
+                widgets::ComboBox::new(hash!("display_mode_select"), &mode_labels_str)
+                    .ui(ui, &mut combo_box_selected_index);
+
+                ui.separator();
+
+                let is_single_path_mode = matches!(state.display_mode, DisplayMode::SinglePath(_));
+                if is_single_path_mode {
+                    ui.checkbox(hash!("reflect_check"), "Reflect", &mut reflect);
+                    ui.checkbox(hash!("timeflip_check"), "Timeflip", &mut timeflip);
+                    ui.checkbox(hash!("trace_check"), "Trace solver", &mut trace);
+                } else {
+                    ui.label(None, "Reflect (N/A)");
+                    ui.label(None, "Timeflip (N/A)");
+                    ui.label(None, "Trace solver (N/A)");
+                }
                 ui.checkbox(
-                    hash!("timeflip_check"),
-                    "Timeflip",
-                    &mut state.timeflip_path,
+                    hash!("steering_color_check"),
+                    "Color by steering",
+                    &mut color_by_steering,
                 );
-            } else {
-                ui.label(None, "Reflect (N/A)");
-                ui.label(None, "Timeflip (N/A)");
+                ui.checkbox(
+                    hash!("heading_sweep_check"),
+                    "Heading sweep",
+                    &mut heading_sweep,
+                );
+                ui.checkbox(
+                    hash!("construction_check"),
+                    "Construction",
+                    &mut construction,
+                );
+                ui.checkbox(
+                    hash!("transform_explorer_check"),
+                    "Transforms",
+                    &mut transform_explorer,
+                );
+                ui.checkbox(hash!("reach_check"), "Reach", &mut reach_enabled);
+                ui.checkbox(hash!("reach_union_check"), "Any heading", &mut reach_union);
+                ui.slider(
+                    hash!("reach_budget_slider"),
+                    "Budget",
+                    MIN_REACH_BUDGET as f32..MAX_REACH_BUDGET as f32,
+                    &mut reach_budget,
+                );
+                ui.checkbox(hash!("runner_up_check"), "Runner-up", &mut show_runner_up);
+                ui.slider(
+                    hash!("gap_threshold_slider"),
+                    "Gap warn",
+                    0.0..MAX_GAP_THRESHOLD as f32,
+                    &mut gap_threshold,
+                );
+                ui.checkbox(hash!("hysteresis_check"), "Hysteresis", &mut hysteresis);
+                ui.slider(
+                    hash!("hysteresis_margin_slider"),
+                    "Margin",
+                    0.0..MAX_HYSTERESIS_MARGIN as f32,
+                    &mut hysteresis_margin,
+                );
+
+                ui.separator();
+
+                ui.input_text(hash!("custom_word_input"), "Word", &mut custom_word);
+                // The cursor stays on the row it just laid out.
+                word_top = ui.canvas().cursor().y;
+                ui.checkbox(hash!("sketch_check"), "Sketch", &mut sketch_enabled);
+                ui.checkbox(hash!("hausdorff_check"), "Hausdorff", &mut sketch_hausdorff);
+
+                ui.separator();
+
+                ui.checkbox(
+                    hash!("obstacle_edit_check"),
+                    "Edit obstacles",
+                    &mut obstacle_editing,
+                );
+                clear_obstacles = ui.button(None, "Clear obstacles");
+                ui.checkbox(hash!("cspace_check"), "C-space", &mut cspace_enabled);
+                ui.slider(
+                    hash!("cspace_heading_slider"),
+                    "Slice°",
+                    -180.0..180.0,
+                    &mut cspace_heading,
+                );
+
+                ui.separator();
+
+                ui.slider(
+                    hash!("prm_samples_slider"),
+                    "Samples",
+                    MIN_PRM_SAMPLES as f32..MAX_PRM_SAMPLES as f32,
+                    &mut prm_samples,
+                );
+                ui.slider(
+                    hash!("prm_neighbors_slider"),
+                    "Neighbors",
+                    MIN_PRM_NEIGHBORS as f32..MAX_PRM_NEIGHBORS as f32,
+                    &mut prm_neighbors,
+                );
+                rebuild_roadmap = ui.button(None, "Rebuild roadmap");
+                ui.slider(
+                    hash!("lattice_spacing_slider"),
+                    "Lattice",
+                    MIN_LATTICE_SPACING as f32..MAX_LATTICE_SPACING as f32,
+                    &mut lattice_spacing,
+                );
+                ui.slider(
+                    hash!("primitive_length_slider"),
+                    "Prim len",
+                    MIN_MAX_PRIMITIVE_LENGTH as f32..MAX_MAX_PRIMITIVE_LENGTH as f32,
+                    &mut max_primitive_length,
+                );
+
+                ui.separator();
+
+                ui.checkbox(hash!("cc_check"), "CC Path", &mut cc_enabled);
+                ui.slider(
+                    hash!("steering_rate_slider"),
+                    "Steer rate",
+                    cc::MIN_STEERING_RATE as f32..cc::MAX_STEERING_RATE as f32,
+                    &mut steering_rate,
+                );
+
+                ui.separator();
+
+                ui.checkbox(hash!("trailer_check"), "Trailer", &mut trailer_enabled);
+                ui.slider(hash!("hitch_slider"), "Hitch", 0.0..1.5, &mut hitch_offset);
+                ui.slider(
+                    hash!("trailer_len_slider"),
+                    "Length",
+                    0.5..4.0,
+                    &mut trailer_length,
+                );
+                ui.slider(
+                    hash!("jackknife_slider"),
+                    "Limit°",
+                    10.0..90.0,
+                    &mut jackknife_limit,
+                );
+
+                ui.separator();
+
+                ui.checkbox(hash!("grid_check"), "Grid", &mut grid_visible);
+                ui.checkbox(hash!("snap_grid_check"), "Snap to grid", &mut snap_to_grid);
+                ui.slider(
+                    hash!("grid_spacing_slider"),
+                    "Spacing",
+                    MIN_GRID_SPACING as f32..MAX_GRID_SPACING as f32,
+                    &mut grid_spacing,
+                );
+                ui.checkbox(hash!("snap_angle_check"), "Snap angle", &mut snap_to_angles);
+                ui.slider(
+                    hash!("angle_increment_slider"),
+                    "Step°",
+                    MIN_ANGLE_INCREMENT_DEGREE as f32..MAX_ANGLE_INCREMENT_DEGREE as f32,
+                    &mut angle_increment,
+                );
+            });
+
+        if combo_box_selected_index != state.combo_box_selected_index {
+            events.push(InputEvent::SelectDisplayMode(combo_box_selected_index));
+        }
+        if reflect != state.reflect_path {
+            events.push(InputEvent::SetReflect(reflect));
+        }
+        if timeflip != state.timeflip_path {
+            events.push(InputEvent::SetTimeflip(timeflip));
+        }
+        if custom_word != state.custom_word {
+            events.push(InputEvent::SetCustomWord(custom_word));
+        }
+        // macroquad keeps text focus to itself, so follow its rule: a click
+        // on the field focuses it and any other click takes the focus away.
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let word_row = Rect::new(panel.x, word_top, panel.w, TEXT_FIELD_HEIGHT);
+            let focused = word_row.contains(vec2(mouse_pos_screen.0, mouse_pos_screen.1));
+            if focused != state.word_focused {
+                events.push(InputEvent::SetWordFocus(focused));
             }
-        });
+        }
+        // Rounded so that a slider drag only rebuilds when the count changes.
+        let samples = prm_samples.round() as usize;
+        let neighbors = prm_neighbors.round() as usize;
+        if samples != state.prm_settings.samples || neighbors != state.prm_settings.neighbors {
+            events.push(InputEvent::SetPrm { samples, neighbors });
+        }
+        if rebuild_roadmap {
+            events.push(InputEvent::RebuildRoadmap);
+        }
+        if construction != state.construction_enabled {
+            events.push(InputEvent::SetConstruction(construction));
+        }
+        if trace != state.trace_enabled {
+            events.push(InputEvent::SetTrace(trace));
+        }
+        if transform_explorer != state.transform_explorer_enabled {
+            events.push(InputEvent::SetTransformExplorer(transform_explorer));
+        }
+        if heading_sweep != state.heading_sweep_enabled {
+            events.push(InputEvent::SetHeadingSweep(heading_sweep));
+        }
+        let reach = ReachSettings {
+            budget: reach_budget as f64,
+            union: reach_union,
+        };
+        if reach_enabled != state.reach_enabled || reach != state.reach_settings {
+            events.push(InputEvent::SetReach {
+                enabled: reach_enabled,
+                settings: reach,
+            });
+        }
+        let stability = StabilitySettings {
+            show_runner_up,
+            gap_threshold: gap_threshold as f64,
+            hysteresis,
+            margin: hysteresis_margin as f64,
+        };
+        if stability != state.stability_settings {
+            events.push(InputEvent::SetStability(stability));
+        }
+        // Regenerating primitives is slow, so apply the sliders in coarse steps.
+        let lattice = LatticeSettings {
+            spacing: (lattice_spacing as f64 / LATTICE_SLIDER_STEP).round() * LATTICE_SLIDER_STEP,
+            max_primitive_length: (max_primitive_length as f64 / LATTICE_SLIDER_STEP).round()
+                * LATTICE_SLIDER_STEP,
+        };
+        if lattice != state.lattice_settings {
+            events.push(InputEvent::SetLattice(lattice));
+        }
+        if obstacle_editing != state.obstacle_editing {
+            events.push(InputEvent::SetObstacleEditing(obstacle_editing));
+        }
+        if clear_obstacles {
+            events.push(InputEvent::ClearObstacles);
+        }
+        let heading_degree = cspace_heading as f64;
+        if cspace_enabled != state.cspace_enabled || heading_degree != state.cspace_heading_degree {
+            events.push(InputEvent::SetCspace {
+                enabled: cspace_enabled,
+                heading_degree,
+            });
+        }
+        let sketch_metric = if sketch_hausdorff {
+            SketchMetric::Hausdorff
+        } else {
+            SketchMetric::Frechet
+        };
+        if sketch_enabled != state.sketch_enabled || sketch_metric != state.sketch_metric {
+            events.push(InputEvent::SetSketch {
+                enabled: sketch_enabled,
+                metric: sketch_metric,
+            });
+        }
+        let path_coloring = if color_by_steering {
+            PathColoring::Steering
+        } else {
+            PathColoring::Gear
+        };
+        if path_coloring != state.path_coloring {
+            events.push(InputEvent::SetPathColoring(path_coloring));
+        }
+        let max_steering_rate = steering_rate as f64;
+        if cc_enabled != state.cc_enabled || max_steering_rate != state.max_steering_rate {
+            events.push(InputEvent::SetCc {
+                enabled: cc_enabled,
+                max_steering_rate,
+            });
+        }
+        let model = TrailerModel {
+            hitch_offset: hitch_offset as f64,
+            trailer_length: trailer_length as f64,
+            jackknife_limit_degree: jackknife_limit as f64,
+        };
+        if trailer_enabled != state.trailer_enabled || model != state.trailer_model {
+            events.push(InputEvent::SetTrailer {
+                enabled: trailer_enabled,
+                model,
+            });
+        }
+        let snap = SnapSettings {
+            to_grid: snap_to_grid,
+            grid_spacing: grid_spacing as f64,
+            to_angles: snap_to_angles,
+            angle_increment_degree: angle_increment as f64,
+        };
+        if grid_visible != state.grid_visible || snap != state.snap {
+            events.push(InputEvent::SetGrid {
+                visible: grid_visible,
+                snap,
+            });
+        }
     }
+    events
 }
 
 fn window_conf() -> Conf {
@@ -556,209 +1019,318 @@ fn window_conf() -> Conf {
         window_title: "Reeds-Shepp Path Visualizer".to_owned(),
         window_width: WINDOW_WIDTH,
         window_height: WINDOW_HEIGHT,
+        window_resizable: true,
+        high_dpi: true,
         ..Default::default()
     }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
+struct SessionArgs {
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    /// Export resolution in pixels; the window size if not given.
+    export_size: Option<(u32, u32)>,
+    gif_fps: u32,
+}
+
+fn parse_size(text: &str) -> Option<(u32, u32)> {
+    let (w, h) = text.split_once('x')?;
+    let size = (w.parse().ok()?, h.parse().ok()?);
+    (size.0 > 0 && size.1 > 0).then_some(size)
+}
+
+fn parse_session_args() -> SessionArgs {
+    let mut args = SessionArgs {
+        record: None,
+        replay: None,
+        export_size: None,
+        gif_fps: DEFAULT_GIF_FPS,
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--record" => args.record = iter.next().map(PathBuf::from),
+            "--replay" => args.replay = iter.next().map(PathBuf::from),
+            "--export-size" => match iter.next().as_deref().and_then(parse_size) {
+                Some(size) => args.export_size = Some(size),
+                None => eprintln!("--export-size expects WIDTHxHEIGHT, e.g. 1920x1080"),
+            },
+            "--gif-fps" => match iter.next().and_then(|v| v.parse().ok()) {
+                Some(fps) => args.gif_fps = fps,
+                None => eprintln!("--gif-fps expects a whole number"),
+            },
+            other => eprintln!("Ignoring unknown argument `{}`", other),
+        }
+    }
+    args
+}
+
+const LUT_USAGE: &str = "\
+usage: visualize-reeds-shepp lut build OUT [--x MIN:MAX:COUNT] [--y MIN:MAX:COUNT]
+                                          [--theta COUNT] [--threads N] [--check SAMPLES]
+       visualize-reeds-shepp lut check FILE [--samples N] [--seed N]";
+const DEFAULT_CHECK_SAMPLES: usize = 10_000;
+
+fn print_accuracy(table: &HeuristicTable, samples: usize, seed: u64) {
+    let report = table.accuracy(samples, seed);
+    let at = report.max_error_at;
+    println!("accuracy over {} random poses:", report.samples);
+    println!("  mean error     {:.5}", report.mean_error);
+    println!("  rms error      {:.5}", report.rms_error);
+    println!(
+        "  max error      {:.5} at ({:.3}, {:.3}, {:.2}°)",
+        report.max_error, at.x, at.y, at.theta_degree
+    );
+    println!("  max rel. error {:.2}%", report.max_relative_error * 100.0);
+}
+
+/// Builds or checks a heuristic lookup table without opening a window.
+fn run_lut_command(args: &[String]) -> Result<(), String> {
+    let (command, file) = match args {
+        [command, file, ..] => (command.as_str(), PathBuf::from(file)),
+        _ => return Err(LUT_USAGE.to_owned()),
+    };
+    let mut grid = LutGrid::default();
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut samples = match command {
+        "build" => 0,
+        _ => DEFAULT_CHECK_SAMPLES,
+    };
+    let mut seed = 1;
+    let mut iter = args[2..].iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("{} expects a value\n{}", flag, LUT_USAGE))?;
+        let number = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("{} expects a whole number", flag))
+        };
+        match (command, flag.as_str()) {
+            ("build", "--x") => grid.x = value.parse()?,
+            ("build", "--y") => grid.y = value.parse()?,
+            ("build", "--theta") => grid.theta_count = number()?.max(1),
+            ("build", "--threads") => threads = number()?.max(1),
+            ("build", "--check") | ("check", "--samples") => samples = number()?,
+            ("check", "--seed") => seed = number()? as u64,
+            _ => return Err(format!("unknown option `{}`\n{}", flag, LUT_USAGE)),
+        }
+    }
+
+    let table = match command {
+        "build" => {
+            println!(
+                "computing {} distances (x {}, y {}, theta {}) on {} threads",
+                grid.len(),
+                grid.x,
+                grid.y,
+                grid.theta_count,
+                threads
+            );
+            let started = std::time::Instant::now();
+            let table = HeuristicTable::compute(grid, threads);
+            table
+                .save(&file)
+                .map_err(|e| format!("cannot write {}: {}", file.display(), e))?;
+            println!(
+                "wrote {} in {:.1}s",
+                file.display(),
+                started.elapsed().as_secs_f64()
+            );
+            table
+        }
+        "check" => {
+            let table = HeuristicTable::load(&file)
+                .map_err(|e| format!("cannot read {}: {}", file.display(), e))?;
+            let grid = table.grid;
+            println!(
+                "{}: x {}, y {}, theta {}",
+                file.display(),
+                grid.x,
+                grid.y,
+                grid.theta_count
+            );
+            table
+        }
+        _ => return Err(LUT_USAGE.to_owned()),
+    };
+    if samples > 0 {
+        print_accuracy(&table, samples, seed);
+    }
+    Ok(())
+}
+
+fn main() {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    if argv.first().map(String::as_str) == Some("lut") {
+        if let Err(e) = run_lut_command(&argv[1..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    macroquad::Window::from_config(window_conf(), run(parse_session_args()));
+}
+
+async fn run(args: SessionArgs) {
+    let mut recorder = args.record.as_deref().and_then(|path| {
+        SessionRecorder::create(path)
+            .map_err(|e| eprintln!("Cannot record to {}: {}", path.display(), e))
+            .ok()
+    });
+    let mut replay_frames = match args.replay.as_deref().map(load_session) {
+        Some(Ok(frames)) => frames.into_iter(),
+        Some(Err(e)) => {
+            eprintln!("Cannot replay session: {}", e);
+            std::process::exit(1);
+        }
+        None => Vec::new().into_iter(),
+    };
+
     let mut state = State::new();
+    let mut renderer = MacroquadRenderer;
+    // Set while a press that started on the sweep chart is held.
+    let mut sweep_dragging = false;
     loop {
-        let mouse_screen = vec2(mouse_position().0, mouse_position().1);
-        let (world_x, world_y) = state.screen_to_world(mouse_screen);
-        let old_combo_box_index = state.combo_box_selected_index;
-        let old_reflect = state.reflect_path;
-        let old_timeflip = state.timeflip_path;
+        // Logical pixels: with `high_dpi` macroquad scales drawing and mouse
+        // positions by the DPI factor, so only the window size changes here.
+        let (physical_width, physical_height) = miniquad::window::screen_size();
+        let view = View::from_physical(physical_width, physical_height, screen_dpi_scale());
+        let replayed_frame = replay_frames.next();
+        let pressed_shortcuts: Vec<Shortcut> = Shortcut::ALL
+            .into_iter()
+            .filter(|&s| is_key_pressed(shortcut_key_code(s)))
+            .collect();
+        // Decided before the frame applies, which may move the focus.
+        let shortcuts_active = state.accepts_shortcuts();
+        let export_requested =
+            |export: Shortcut| shortcuts_active && pressed_shortcuts.contains(&export);
+        let mut frame = InputFrame::default();
         let mut needs_recalculation = false;
 
-        if is_key_pressed(KeyCode::R) {
-            state.reset();
-        }
-
-        match state.app_state {
-            AppState::PlacingStart => {
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    state.start_pose = Some(Pose {
-                        x: world_x,
-                        y: world_y,
-                        theta_degree: 0.0,
-                    });
-                    state.drag_state_initial = Some(InitialDragState {
-                        start_pos: mouse_screen,
-                        current_pos: mouse_screen,
-                    });
-                    state.app_state = AppState::DefiningStartAngle;
-                }
-            }
-            AppState::DefiningStartAngle => {
-                if is_mouse_button_down(MouseButton::Left) {
-                    if let Some(drag) = &mut state.drag_state_initial {
-                        drag.current_pos = mouse_screen;
-                    }
-                    if let Some(angle) = state.calculate_initial_drag_angle() {
-                        if let Some(start) = &mut state.start_pose {
-                            start.theta_degree = angle;
-                        }
-                    }
-                } else if is_mouse_button_released(MouseButton::Left) {
-                    if let Some(angle) = state.calculate_initial_drag_angle() {
-                        if let Some(start) = &mut state.start_pose {
-                            start.theta_degree = angle;
-                        }
-                    }
-                    state.drag_state_initial = None;
-                    state.app_state = AppState::PlacingEnd;
-                }
+        if let Some(replayed) = &replayed_frame {
+            state.apply_frame(replayed);
+        } else {
+            let mouse_screen = vec2(mouse_position().0, mouse_position().1);
+            frame
+                .events
+                .extend(pressed_shortcuts.iter().map(|&s| InputEvent::Shortcut(s)));
+            let ui_rect = ui_panel_rect(&view);
+            let chart = sweep_chart(&view);
+            let over_chart =
+                !state.heading_sweep.is_empty() && chart.contains((mouse_screen.x, mouse_screen.y));
+            let explorer = explorer_layout(&view, &state);
+            let over_explorer = state.transform_explorer.is_some()
+                && explorer.contains((mouse_screen.x, mouse_screen.y));
+            // The gallery covers the canvas, so poses are edited in other modes.
+            let over_gallery = state.gallery.is_some()
+                && mouse_screen.x < ui_rect.x
+                && mouse_screen.y >= GALLERY_TOP;
+            let pressed = is_mouse_button_pressed(MouseButton::Left);
+            let down = is_mouse_button_down(MouseButton::Left);
+            frame.events.push(InputEvent::Pointer(PointerInput {
+                world_pos: view.screen_to_world((mouse_screen.x, mouse_screen.y)),
+                pressed,
+                down,
+                released: is_mouse_button_released(MouseButton::Left),
+                over_ui: ui_rect.contains(mouse_screen)
+                    || over_chart
+                    || over_explorer
+                    || over_gallery
+                    || sweep_dragging,
+            }));
+            sweep_dragging = down && (sweep_dragging || (pressed && over_chart));
+            // Clamp to the chart while dragging so the pointer may leave it.
+            let chart_point = (
+                mouse_screen.x.clamp(chart.left, chart.right()),
+                mouse_screen.y.clamp(chart.top, chart.bottom()),
+            );
+            if sweep_dragging
+                && let (Some(theta), Some(end)) = (chart.heading_at(chart_point), state.end_pose)
+                && theta != end.theta_degree
+            {
+                frame.events.push(InputEvent::SetEndHeading(theta));
             }
-            AppState::PlacingEnd => {
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    state.end_pose = Some(Pose {
-                        x: world_x,
-                        y: world_y,
-                        theta_degree: 0.0,
-                    });
-                    state.drag_state_initial = Some(InitialDragState {
-                        start_pos: mouse_screen,
-                        current_pos: mouse_screen,
-                    });
-                    state.app_state = AppState::DefiningEndAngle;
-                }
+            // A click on a transform cell shows that variant as the single path.
+            if pressed
+                && over_explorer
+                && let (Some(i), Some(e)) = (
+                    explorer.cell_at((mouse_screen.x, mouse_screen.y)),
+                    &state.transform_explorer,
+                )
+            {
+                frame
+                    .events
+                    .extend(select_variant_events(&state, e.family, TRANSFORMS[i]));
             }
-            AppState::DefiningEndAngle => {
-                if is_mouse_button_down(MouseButton::Left) {
-                    if let Some(drag) = &mut state.drag_state_initial {
-                        drag.current_pos = mouse_screen;
-                    }
-                    if let Some(angle) = state.calculate_initial_drag_angle() {
-                        if let Some(end) = &mut state.end_pose {
-                            end.theta_degree = angle;
-                        }
-                    }
-                } else if is_mouse_button_released(MouseButton::Left) {
-                    if let Some(angle) = state.calculate_initial_drag_angle() {
-                        if let Some(end) = &mut state.end_pose {
-                            end.theta_degree = angle;
-                        }
-                    }
-                    state.drag_state_initial = None;
-                    state.calculate_display_data();
-                    state.app_state = AppState::DisplayingPaths;
-                }
+            // A click on a gallery thumbnail does the same for its path.
+            if pressed
+                && !over_chart
+                && !over_explorer
+                && let Some(gallery) = &state.gallery
+                && let Some(i) = gallery_layout(&view, &state, gallery.thumbs.len())
+                    .thumb_at((mouse_screen.x, mouse_screen.y), gallery.thumbs.len())
+            {
+                let thumb = &gallery.thumbs[i];
+                frame.events.extend(select_variant_events(
+                    &state,
+                    thumb.family,
+                    (thumb.cell.reflect, thumb.cell.timeflip),
+                ));
             }
-            AppState::DisplayingPaths => {
-                let ui_rect = Rect::new(WINDOW_WIDTH as f32 - 220.0 - 20.0, 20.0, 220.0, 120.0);
-                let mouse_over_ui = ui_rect.contains(mouse_screen);
-                if is_mouse_button_pressed(MouseButton::Left) && !mouse_over_ui {
-                    let mut tf = false;
-                    if let Some(p) = &state.start_pose {
-                        if state.check_headlight_hit((world_x, world_y), p) {
-                            state.dragging_modify = Some(ModifyDragTarget::StartAngle);
-                            tf = true;
-                        }
-                    }
-                    if !tf {
-                        if let Some(p) = &state.end_pose {
-                            if state.check_headlight_hit((world_x, world_y), p) {
-                                state.dragging_modify = Some(ModifyDragTarget::EndAngle);
-                                tf = true;
-                            }
-                        }
-                    }
-                    if !tf {
-                        if let Some(p) = &state.start_pose {
-                            if state.check_body_hit((world_x, world_y), p) {
-                                state.dragging_modify = Some(ModifyDragTarget::StartBody);
-                                tf = true;
-                            }
-                        }
-                    }
-                    if !tf {
-                        if let Some(p) = &state.end_pose {
-                            if state.check_body_hit((world_x, world_y), p) {
-                                state.dragging_modify = Some(ModifyDragTarget::EndBody);
-                            }
-                        }
-                    }
-                }
-                if let Some(target) = state.dragging_modify {
-                    if is_mouse_button_down(MouseButton::Left) {
-                        match target {
-                            ModifyDragTarget::StartBody => {
-                                if let Some(p) = &mut state.start_pose {
-                                    p.x = world_x;
-                                    p.y = world_y;
-                                    needs_recalculation = true;
-                                }
-                            }
-                            ModifyDragTarget::EndBody => {
-                                if let Some(p) = &mut state.end_pose {
-                                    p.x = world_x;
-                                    p.y = world_y;
-                                    needs_recalculation = true;
-                                }
-                            }
-                            ModifyDragTarget::StartAngle => {
-                                if let Some(p) = &mut state.start_pose {
-                                    let dx = world_x - p.x;
-                                    let dy = world_y - p.y;
-                                    if dx.hypot(dy) > 1e-6 {
-                                        p.theta_degree = dy.atan2(dx).to_degrees();
-                                        needs_recalculation = true;
-                                    }
-                                }
-                            }
-                            ModifyDragTarget::EndAngle => {
-                                if let Some(p) = &mut state.end_pose {
-                                    let dx = world_x - p.x;
-                                    let dy = world_y - p.y;
-                                    if dx.hypot(dy) > 1e-6 {
-                                        p.theta_degree = dy.atan2(dx).to_degrees();
-                                        needs_recalculation = true;
-                                    }
-                                }
-                            }
-                        }
-                    } else if is_mouse_button_released(MouseButton::Left) {
-                        state.dragging_modify = None;
-                        needs_recalculation = true;
-                    }
-                }
+            for event in &frame.events {
+                needs_recalculation |= state.apply(event);
             }
-        } // end match state.app_state
+        }
 
-        clear_background(BG_COLOR);
-        match state.display_mode {
-            DisplayMode::SinglePath(_) | DisplayMode::ShortestPath => {
-                if let (Some(path), Some(start)) = (&state.current_raw_path, &state.start_pose) {
-                    draw_path_turning_circles(path, start);
-                }
+        let export_view = match args.export_size {
+            Some((w, h)) => view.resized(w as f32, h as f32),
+            None => view,
+        };
+        if export_requested(Shortcut::ExportPng) {
+            match export_png(&state, &export_view, Path::new(PNG_EXPORT_FILE)) {
+                Ok(()) => println!("Exported {}", PNG_EXPORT_FILE),
+                Err(e) => eprintln!("PNG export failed: {}", e),
             }
-            DisplayMode::AllPaths => {}
         }
-        draw_paths(&state);
-        if let Some(ref pose) = state.start_pose {
-            draw_pose_elements(pose, START_CAR_COLOR);
+        if export_requested(Shortcut::ExportPrimitives) {
+            match export_primitives(state.primitive_set(), Path::new(PRIMITIVES_EXPORT_FILE)) {
+                Ok(count) => println!("Exported {} ({} primitives)", PRIMITIVES_EXPORT_FILE, count),
+                Err(e) => eprintln!("Primitive export failed: {}", e),
+            }
         }
-        if let Some(ref pose) = state.end_pose {
-            draw_pose_elements(pose, END_CAR_COLOR);
+        if export_requested(Shortcut::ExportGif) {
+            match export_gif(
+                &state,
+                &export_view,
+                args.gif_fps,
+                Path::new(GIF_EXPORT_FILE),
+            ) {
+                Ok(frames) => println!("Exported {} ({} frames)", GIF_EXPORT_FILE, frames),
+                Err(e) => eprintln!("GIF export failed: {}", e),
+            }
         }
-        draw_ui(&mut state);
 
-        if state.app_state == AppState::DisplayingPaths {
-            if state.combo_box_selected_index != old_combo_box_index {
-                state.set_display_mode_from_index(state.combo_box_selected_index);
-                needs_recalculation = true;
-            } else if matches!(state.display_mode, DisplayMode::SinglePath(_))
-                && (state.reflect_path != old_reflect || state.timeflip_path != old_timeflip)
+        draw_scene(&mut renderer, &view, &state);
+        let ui_events = draw_ui(&mut renderer, &view, &state);
+
+        if replayed_frame.is_none() {
+            for event in ui_events {
+                needs_recalculation |= state.apply(&event);
+                frame.events.push(event);
+            }
+            if needs_recalculation {
+                state.calculate_display_data();
+            }
+            if let Some(rec) = &mut recorder
+                && let Err(e) = rec.record(&frame)
             {
-                needs_recalculation = true;
+                eprintln!("Stopped recording: {}", e);
+                recorder = None;
             }
         }
 
-        if needs_recalculation {
-            state.calculate_display_data();
-        }
-
         next_frame().await
     }
 }
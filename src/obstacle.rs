@@ -0,0 +1,128 @@
+//! Rectangular obstacles, car footprint collisions and configuration-space
+//! slices.
+//!
+//! The car footprint is centered on the reference pose, so for a fixed
+//! heading the set of reference positions that collide with a convex
+//! obstacle is the Minkowski sum of the obstacle and the rotated footprint.
+//! Both are convex, so that sum is the convex hull of all pairwise vertex
+//! sums.
+
+use reeds_shepp_lib::Pose;
+
+use crate::car::{CAR_LENGTH, CAR_WIDTH};
+
+// --- Constants ---
+/// Obstacles thinner than this (world units) are dropped when drawn by hand.
+pub const MIN_OBSTACLE_SIZE: f64 = 0.05;
+
+/// Axis-aligned rectangle with `min` below and left of `max`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Obstacle {
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+}
+
+impl Obstacle {
+    /// Rectangle spanned by two opposite corners in any order.
+    pub fn from_corners(a: (f64, f64), b: (f64, f64)) -> Self {
+        Obstacle {
+            min: (a.0.min(b.0), a.1.min(b.1)),
+            max: (a.0.max(b.0), a.1.max(b.1)),
+        }
+    }
+
+    pub fn corners(&self) -> [(f64, f64); 4] {
+        [
+            self.min,
+            (self.max.0, self.min.1),
+            self.max,
+            (self.min.0, self.max.1),
+        ]
+    }
+
+    pub fn contains(&self, p: (f64, f64)) -> bool {
+        (self.min.0..=self.max.0).contains(&p.0) && (self.min.1..=self.max.1).contains(&p.1)
+    }
+}
+
+/// Corners of the car body at `pose`, counter-clockwise.
+pub fn car_footprint(pose: &Pose) -> [(f64, f64); 4] {
+    let (sin, cos) = pose.theta_degree.to_radians().sin_cos();
+    let (hl, hw) = (CAR_LENGTH / 2.0, CAR_WIDTH / 2.0);
+    [(hl, -hw), (hl, hw), (-hl, hw), (-hl, -hw)]
+        .map(|(lx, ly)| (pose.x + lx * cos - ly * sin, pose.y + lx * sin + ly * cos))
+}
+
+fn project(points: &[(f64, f64)], axis: (f64, f64)) -> (f64, f64) {
+    points
+        .iter()
+        .map(|p| p.0 * axis.0 + p.1 * axis.1)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+            (lo.min(d), hi.max(d))
+        })
+}
+
+/// Separating axis test between the car body at `pose` and `obstacle`.
+/// Touching counts as a collision.
+pub fn footprint_collides(pose: &Pose, obstacle: &Obstacle) -> bool {
+    let car = car_footprint(pose);
+    let rect = obstacle.corners();
+    let (sin, cos) = pose.theta_degree.to_radians().sin_cos();
+    [(1.0, 0.0), (0.0, 1.0), (cos, sin), (-sin, cos)]
+        .iter()
+        .all(|&axis| {
+            let (a_lo, a_hi) = project(&car, axis);
+            let (b_lo, b_hi) = project(&rect, axis);
+            a_lo <= b_hi && b_lo <= a_hi
+        })
+}
+
+pub fn pose_collides(pose: &Pose, obstacles: &[Obstacle]) -> bool {
+    obstacles.iter().any(|o| footprint_collides(pose, o))
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Counter-clockwise convex hull (monotone chain), without collinear points.
+/// Points within rounding noise of an edge count as collinear, so a slice at
+/// a multiple of 90° stays a rectangle.
+pub fn convex_hull(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let mut hull: Vec<(f64, f64)> = Vec::with_capacity(sorted.len() * 2);
+    for pass in [sorted.clone(), sorted.iter().rev().copied().collect()] {
+        let floor = hull.len();
+        for p in pass {
+            while hull.len() >= floor + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 1e-12
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+/// Reference positions at which the car, headed `theta_degree`, touches
+/// `obstacle`: a convex polygon, counter-clockwise.
+pub fn cspace_obstacle(obstacle: &Obstacle, theta_degree: f64) -> Vec<(f64, f64)> {
+    let footprint = car_footprint(&Pose {
+        x: 0.0,
+        y: 0.0,
+        theta_degree,
+    });
+    let sums: Vec<(f64, f64)> = obstacle
+        .corners()
+        .iter()
+        .flat_map(|c| footprint.iter().map(move |f| (c.0 + f.0, c.1 + f.1)))
+        .collect();
+    convex_hull(&sums)
+}
@@ -0,0 +1,315 @@
+use reeds_shepp_lib::{Gear, Path, PathElement, Pose, Steering, utils};
+
+// --- Constants ---
+pub const TURNING_RADIUS: f64 = 1.0;
+pub const PATH_SAMPLE_STEP: f64 = 1.0 / 30.0;
+const MIN_SEGMENT_PARAM: f64 = 1e-10;
+/// Step multiples closer than this to a segment boundary are left out, as
+/// the boundary itself is sampled.
+const BOUNDARY_TOLERANCE: f64 = 1e-9;
+
+/// A pose on a path together with the driving state at that point.
+/// `theta` is in radians, `curvature` is signed (positive turning left) and
+/// `s` is the arc length travelled from the start of the path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathSample {
+    pub x: f64,
+    pub y: f64,
+    pub theta: f64,
+    pub gear: Gear,
+    pub curvature: f64,
+    pub s: f64,
+    pub segment: usize,
+}
+
+impl PathSample {
+    pub fn pose(&self) -> Pose {
+        Pose {
+            x: self.x,
+            y: self.y,
+            theta_degree: self.theta.to_degrees(),
+        }
+    }
+}
+
+/// Arc length driven along one element.
+pub fn segment_length(element: &PathElement) -> f64 {
+    match element.steering {
+        Steering::Straight => element.param.abs(),
+        Steering::Left | Steering::Right => element.param.abs() * TURNING_RADIUS,
+    }
+}
+
+pub fn path_arc_length(path: &Path) -> f64 {
+    path.iter().map(segment_length).sum()
+}
+
+pub fn curvature(steering: Steering) -> f64 {
+    match steering {
+        Steering::Straight => 0.0,
+        Steering::Left => 1.0 / TURNING_RADIUS,
+        Steering::Right => -1.0 / TURNING_RADIUS,
+    }
+}
+
+/// Compact text form of a path, one `<steering><gear><param>` token per
+/// element, e.g. `L+0.80 S-1.20 R+1.57`. Arc params are radians.
+pub fn path_word(path: &Path) -> String {
+    path_word_with_precision(path, 2)
+}
+
+/// [`path_word`] with `digits` decimals per param.
+pub fn path_word_with_precision(path: &Path, digits: usize) -> String {
+    path.iter()
+        .map(|e| {
+            let steering = match e.steering {
+                Steering::Left => 'L',
+                Steering::Straight => 'S',
+                Steering::Right => 'R',
+            };
+            let gear = match e.gear {
+                Gear::Forward => '+',
+                Gear::Backwards => '-',
+            };
+            format!("{}{}{:.*}", steering, gear, digits, e.param.abs())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Steering and gear letters of every element without the params, e.g.
+/// `L+S-R+`, for labels too small for the full word.
+pub fn path_shape(path: &Path) -> String {
+    path_word_with_precision(path, 0)
+        .split_whitespace()
+        .map(|token| &token[..2])
+        .collect()
+}
+
+/// Parses the form written by [`path_word`], e.g. `L+0.8 S-1.2 R+1.57`.
+/// Tokens are separated by whitespace; the steering letter may be lower
+/// case and params are non-negative (radians for arcs, length for straights).
+pub fn parse_path_word(word: &str) -> Result<Path, String> {
+    word.split_whitespace()
+        .map(|token| {
+            let mut chars = token.chars();
+            let steering = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                Some('L') => Steering::Left,
+                Some('S') => Steering::Straight,
+                Some('R') => Steering::Right,
+                _ => return Err(format!("`{}`: expected L, S or R", token)),
+            };
+            let gear = match chars.next() {
+                Some('+') => Gear::Forward,
+                Some('-') => Gear::Backwards,
+                _ => return Err(format!("`{}`: expected + or - after the steering", token)),
+            };
+            let param: f64 = chars
+                .as_str()
+                .parse()
+                .map_err(|_| format!("`{}`: expected a number after the gear", token))?;
+            if !param.is_finite() || param < 0.0 {
+                return Err(format!("`{}`: param must be a non-negative number", token));
+            }
+            Ok(PathElement {
+                param,
+                steering,
+                gear,
+            })
+        })
+        .collect()
+}
+
+/// Closest point on the polyline through `samples` to `point`, as the arc
+/// length `s` interpolated between the neighbouring samples together with
+/// the distance to it. `None` for an empty polyline.
+pub fn project_onto_polyline(samples: &[PathSample], point: (f64, f64)) -> Option<(f64, f64)> {
+    let (px, py) = point;
+    if let [only] = samples {
+        return Some((only.s, (px - only.x).hypot(py - only.y)));
+    }
+    samples
+        .windows(2)
+        .map(|pair| {
+            let (ax, ay) = (pair[0].x, pair[0].y);
+            let (dx, dy) = (pair[1].x - ax, pair[1].y - ay);
+            let len_sq = dx * dx + dy * dy;
+            let t = if len_sq > 0.0 {
+                (((px - ax) * dx + (py - ay) * dy) / len_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let s = pair[0].s + (pair[1].s - pair[0].s) * t;
+            (s, (px - ax - dx * t).hypot(py - ay - dy * t))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Shortest distance from `point` to the polyline through `samples`.
+pub fn distance_to_polyline(samples: &[PathSample], point: (f64, f64)) -> f64 {
+    project_onto_polyline(samples, point).map_or(f64::INFINITY, |(_, d)| d)
+}
+
+/// Pose reached after driving `distance` along `element` from `(x, y, theta)`.
+pub fn advance(
+    x: f64,
+    y: f64,
+    theta: f64,
+    element: &PathElement,
+    distance: f64,
+) -> (f64, f64, f64) {
+    let d = match element.gear {
+        Gear::Forward => distance,
+        Gear::Backwards => -distance,
+    };
+    match element.steering {
+        Steering::Straight => (x + d * theta.cos(), y + d * theta.sin(), theta),
+        Steering::Left => {
+            let nt = theta + d / TURNING_RADIUS;
+            (
+                x + TURNING_RADIUS * (nt.sin() - theta.sin()),
+                y + TURNING_RADIUS * (theta.cos() - nt.cos()),
+                utils::normalize_angle_rad(nt),
+            )
+        }
+        Steering::Right => {
+            let nt = theta - d / TURNING_RADIUS;
+            (
+                x + TURNING_RADIUS * (theta.sin() - nt.sin()),
+                y + TURNING_RADIUS * (nt.cos() - theta.cos()),
+                utils::normalize_angle_rad(nt),
+            )
+        }
+    }
+}
+
+/// A driven element of a path with its start pose and arc-length offset.
+#[derive(Debug, Clone, Copy)]
+pub struct Segment {
+    pub index: usize,
+    pub element: PathElement,
+    pub start: (f64, f64, f64),
+    pub s_start: f64,
+    pub length: f64,
+}
+
+/// Resolves the start pose of every non-degenerate element of `path`.
+pub fn segments(start_pose: &Pose, path: &Path) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut pose = (
+        start_pose.x,
+        start_pose.y,
+        utils::normalize_angle_rad(start_pose.theta_degree.to_radians()),
+    );
+    let mut s = 0.0;
+    for (index, element) in path.iter().enumerate() {
+        if element.param.abs() < MIN_SEGMENT_PARAM {
+            continue;
+        }
+        let length = segment_length(element);
+        segments.push(Segment {
+            index,
+            element: *element,
+            start: pose,
+            s_start: s,
+            length,
+        });
+        pose = advance(pose.0, pose.1, pose.2, element, length);
+        s += length;
+    }
+    segments
+}
+
+fn sample_in_segment(segment: &Segment, s: f64) -> PathSample {
+    let d = (s - segment.s_start).clamp(0.0, segment.length);
+    let (x, y, theta) = advance(
+        segment.start.0,
+        segment.start.1,
+        segment.start.2,
+        &segment.element,
+        d,
+    );
+    PathSample {
+        x,
+        y,
+        theta,
+        gear: segment.element.gear,
+        curvature: curvature(segment.element.steering),
+        s: segment.s_start + d,
+        segment: segment.index,
+    }
+}
+
+/// Samples the path at the requested arc lengths, clamped to the path.
+/// A sample exactly on a segment boundary belongs to the following segment.
+pub fn sample_path_at(start_pose: &Pose, path: &Path, arc_lengths: &[f64]) -> Vec<PathSample> {
+    let segments = segments(start_pose, path);
+    if segments.is_empty() {
+        return Vec::new();
+    }
+    arc_lengths
+        .iter()
+        .map(|&s| {
+            let i = segments
+                .partition_point(|seg| seg.s_start <= s)
+                .saturating_sub(1);
+            sample_in_segment(&segments[i], s)
+        })
+        .collect()
+}
+
+/// Samples the path at every multiple of `step` in arc length. Every segment
+/// boundary and the final pose are included exactly as well, so cusps are
+/// never cut off. Panics unless `step` is positive and finite.
+pub fn sample_path(start_pose: &Pose, path: &Path, step: f64) -> Vec<PathSample> {
+    assert!(
+        step > 0.0 && step.is_finite(),
+        "sample step must be positive and finite, got {}",
+        step
+    );
+    let segments = segments(start_pose, path);
+    let mut samples = Vec::new();
+    for segment in &segments {
+        let end = segment.s_start + segment.length;
+        samples.push(sample_in_segment(segment, segment.s_start));
+        let mut k = (segment.s_start / step).floor() as usize + 1;
+        while k as f64 * step < end - BOUNDARY_TOLERANCE {
+            let s = k as f64 * step;
+            if s > segment.s_start + BOUNDARY_TOLERANCE {
+                samples.push(sample_in_segment(segment, s));
+            }
+            k += 1;
+        }
+    }
+    if let Some(last) = segments.last() {
+        samples.push(sample_in_segment(last, last.s_start + last.length));
+    }
+    samples
+}
+
+/// Pose at the end of `path` driven from `start_pose`.
+pub fn path_end_pose(start_pose: &Pose, path: &Path) -> Pose {
+    match segments(start_pose, path).last() {
+        Some(last) => sample_in_segment(last, last.s_start + last.length).pose(),
+        None => *start_pose,
+    }
+}
+
+/// Centers of the turning circles traversed by the arc segments of `path`.
+pub fn path_turning_circle_centers(path: &Path, start_pose: &Pose) -> Vec<(f64, f64)> {
+    segments(start_pose, path)
+        .iter()
+        .filter_map(|seg| {
+            let (x, y, th) = seg.start;
+            match seg.element.steering {
+                Steering::Straight => None,
+                Steering::Left => {
+                    Some((x - TURNING_RADIUS * th.sin(), y + TURNING_RADIUS * th.cos()))
+                }
+                Steering::Right => {
+                    Some((x + TURNING_RADIUS * th.sin(), y - TURNING_RADIUS * th.cos()))
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,312 @@
+//! Probabilistic roadmap over collision-free poses, connected by optimal
+//! Reeds-Shepp paths.
+//!
+//! Building samples poses inside the bounds, connects each to its `k`
+//! nearest neighbours under the Reeds-Shepp length and keeps the edges whose
+//! path stays clear of the obstacles. A query only links the start and end
+//! poses into the finished graph and runs Dijkstra, so queries are cheap
+//! enough to repeat on every drag frame.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use reeds_shepp_lib::{Gear, Path, Pose, get_optimal_path, path_length};
+
+use crate::candidates::reaches_goal;
+use crate::obstacle::{Obstacle, pose_collides};
+use crate::path::sample_path;
+use crate::rng::Rng;
+
+// --- Constants ---
+pub const DEFAULT_PRM_SAMPLES: usize = 150;
+pub const MIN_PRM_SAMPLES: usize = 20;
+pub const MAX_PRM_SAMPLES: usize = 500;
+pub const DEFAULT_PRM_NEIGHBORS: usize = 8;
+pub const MIN_PRM_NEIGHBORS: usize = 2;
+pub const MAX_PRM_NEIGHBORS: usize = 20;
+/// Distance the sampling area extends beyond the poses and obstacles.
+pub const PRM_MARGIN: f64 = 3.0;
+/// Arc length between the poses checked for collisions along an edge.
+pub const COLLISION_CHECK_STEP: f64 = 0.1;
+/// Rejection sampling gives up after this many draws per requested node.
+const MAX_DRAWS_PER_SAMPLE: usize = 50;
+
+/// Parameters of a roadmap build.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrmSettings {
+    pub samples: usize,
+    pub neighbors: usize,
+    pub seed: u64,
+}
+
+impl Default for PrmSettings {
+    fn default() -> Self {
+        PrmSettings {
+            samples: DEFAULT_PRM_SAMPLES,
+            neighbors: DEFAULT_PRM_NEIGHBORS,
+            seed: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RoadmapEdge {
+    pub from: usize,
+    pub to: usize,
+    /// Drives from `from` to `to`; the reverse direction uses [`reverse_path`].
+    pub path: Path,
+    pub length: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Roadmap {
+    pub nodes: Vec<Pose>,
+    pub edges: Vec<RoadmapEdge>,
+    /// Edge indices touching each node.
+    adjacency: Vec<Vec<usize>>,
+    /// Bounds the nodes were sampled in, as `(min, max)` corners.
+    pub bounds: ((f64, f64), (f64, f64)),
+}
+
+/// The graph search result, as one path driven from the query start.
+#[derive(Debug, Clone)]
+pub struct PrmSolution {
+    pub path: Path,
+    pub length: f64,
+    /// Roadmap nodes passed through, in driving order.
+    pub via: Vec<usize>,
+}
+
+/// The same maneuver driven backwards in time: elements in reverse order
+/// with the gears swapped. It leads from the end pose back to the start.
+pub fn reverse_path(path: &Path) -> Path {
+    path.iter()
+        .rev()
+        .map(|e| {
+            let mut e = *e;
+            e.gear = match e.gear {
+                Gear::Forward => Gear::Backwards,
+                Gear::Backwards => Gear::Forward,
+            };
+            e
+        })
+        .collect()
+}
+
+pub fn path_collides(start: &Pose, path: &Path, obstacles: &[Obstacle]) -> bool {
+    !obstacles.is_empty()
+        && sample_path(start, path, COLLISION_CHECK_STEP)
+            .iter()
+            .any(|s| pose_collides(&s.pose(), obstacles))
+}
+
+/// Optimal path from `from` to `to` if it really ends on `to` and is
+/// collision-free.
+pub fn connect(from: &Pose, to: &Pose, obstacles: &[Obstacle]) -> Option<Path> {
+    let path = get_optimal_path(*from, *to)?;
+    (reaches_goal(from, to, &path) && !path_collides(from, &path, obstacles)).then_some(path)
+}
+
+/// Bounding box of `poses` and `obstacles`, grown by [`PRM_MARGIN`].
+pub fn sampling_bounds(poses: &[Pose], obstacles: &[Obstacle]) -> ((f64, f64), (f64, f64)) {
+    let points = poses
+        .iter()
+        .map(|p| (p.x, p.y))
+        .chain(obstacles.iter().flat_map(|o| [o.min, o.max]));
+    let (mut min, mut max) = ((0.0f64, 0.0f64), (0.0f64, 0.0f64));
+    for (i, (x, y)) in points.enumerate() {
+        if i == 0 {
+            (min, max) = ((x, y), (x, y));
+        }
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    (
+        (min.0 - PRM_MARGIN, min.1 - PRM_MARGIN),
+        (max.0 + PRM_MARGIN, max.1 + PRM_MARGIN),
+    )
+}
+
+/// The `k` poses of `candidates` closest to `from` under the Reeds-Shepp
+/// length, as `(index, length)` with the shortest first.
+fn nearest(from: &Pose, candidates: &[Pose], skip: Option<usize>, k: usize) -> Vec<(usize, f64)> {
+    let mut lengths: Vec<(usize, f64)> = candidates
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != skip)
+        .filter_map(|(i, p)| get_optimal_path(*from, *p).map(|path| (i, path_length(&path))))
+        .collect();
+    lengths.sort_by(|a, b| a.1.total_cmp(&b.1));
+    lengths.truncate(k);
+    lengths
+}
+
+impl Roadmap {
+    pub fn build(
+        bounds: ((f64, f64), (f64, f64)),
+        obstacles: &[Obstacle],
+        settings: &PrmSettings,
+    ) -> Self {
+        let mut rng = Rng(settings.seed);
+        let (min, max) = bounds;
+        let mut nodes = Vec::with_capacity(settings.samples);
+        for _ in 0..settings.samples * MAX_DRAWS_PER_SAMPLE {
+            if nodes.len() == settings.samples {
+                break;
+            }
+            let pose = Pose {
+                x: rng.range(min.0, max.0),
+                y: rng.range(min.1, max.1),
+                theta_degree: rng.range(-180.0, 180.0),
+            };
+            if !pose_collides(&pose, obstacles) {
+                nodes.push(pose);
+            }
+        }
+
+        let mut roadmap = Roadmap {
+            adjacency: vec![Vec::new(); nodes.len()],
+            nodes,
+            edges: Vec::new(),
+            bounds,
+        };
+        for from in 0..roadmap.nodes.len() {
+            let pose = roadmap.nodes[from];
+            for (to, _) in nearest(&pose, &roadmap.nodes, Some(from), settings.neighbors) {
+                let known = roadmap.adjacency[from].iter().any(|&e| {
+                    let edge = &roadmap.edges[e];
+                    edge.from == to || edge.to == to
+                });
+                if known {
+                    continue;
+                }
+                if let Some(path) = connect(&pose, &roadmap.nodes[to], obstacles) {
+                    roadmap.add_edge(from, to, path);
+                }
+            }
+        }
+        roadmap
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, path: Path) {
+        let index = self.edges.len();
+        self.edges.push(RoadmapEdge {
+            from,
+            to,
+            length: path_length(&path),
+            path,
+        });
+        self.adjacency[from].push(index);
+        self.adjacency[to].push(index);
+    }
+
+    /// Path leaving `node` along `edge`, and the node it arrives at.
+    fn traverse(&self, edge: usize, node: usize) -> (Path, usize) {
+        let e = &self.edges[edge];
+        if e.from == node {
+            (e.path.clone(), e.to)
+        } else {
+            (reverse_path(&e.path), e.from)
+        }
+    }
+
+    /// Links `start` and `end` to their `neighbors` nearest reachable nodes
+    /// and searches the graph. A direct collision-free path wins if it is
+    /// shorter than any route through the roadmap.
+    pub fn query(
+        &self,
+        start: &Pose,
+        end: &Pose,
+        obstacles: &[Obstacle],
+        neighbors: usize,
+    ) -> Option<PrmSolution> {
+        let n = self.nodes.len();
+        let (start_id, end_id) = (n, n + 1);
+        let mut extra: Vec<(usize, usize, Path)> = Vec::new();
+        for (i, _) in nearest(start, &self.nodes, None, neighbors) {
+            if let Some(path) = connect(start, &self.nodes[i], obstacles) {
+                extra.push((start_id, i, path));
+            }
+        }
+        for (i, _) in nearest(end, &self.nodes, None, neighbors) {
+            if let Some(path) = connect(&self.nodes[i], end, obstacles) {
+                extra.push((i, end_id, path));
+            }
+        }
+        if let Some(path) = connect(start, end, obstacles) {
+            extra.push((start_id, end_id, path));
+        }
+
+        // Dijkstra over the roadmap plus the query edges, which are only
+        // driven in their own direction.
+        let mut dist = vec![f64::INFINITY; n + 2];
+        let mut previous: Vec<Option<(usize, Path)>> = vec![None; n + 2];
+        let mut heap = BinaryHeap::new();
+        dist[start_id] = 0.0;
+        heap.push(Visit(0.0, start_id));
+        while let Some(Visit(d, node)) = heap.pop() {
+            if d > dist[node] {
+                continue;
+            }
+            if node == end_id {
+                break;
+            }
+            let mut moves: Vec<(Path, usize)> = extra
+                .iter()
+                .filter(|(from, _, _)| *from == node)
+                .map(|(_, to, path)| (path.clone(), *to))
+                .collect();
+            if node < n {
+                moves.extend(self.adjacency[node].iter().map(|&e| self.traverse(e, node)));
+            }
+            for (path, next) in moves {
+                let candidate = d + path_length(&path);
+                if candidate < dist[next] {
+                    dist[next] = candidate;
+                    previous[next] = Some((node, path));
+                    heap.push(Visit(candidate, next));
+                }
+            }
+        }
+        if !dist[end_id].is_finite() {
+            return None;
+        }
+
+        let mut pieces = Vec::new();
+        let mut via = Vec::new();
+        let mut node = end_id;
+        while let Some((from, path)) = previous[node].take() {
+            pieces.push(path);
+            if from < n {
+                via.push(from);
+            }
+            node = from;
+        }
+        pieces.reverse();
+        via.reverse();
+        let path: Path = pieces.into_iter().flatten().collect();
+        Some(PrmSolution {
+            length: path_length(&path),
+            path,
+            via,
+        })
+    }
+}
+
+/// Heap entry ordered so that the smallest distance pops first.
+#[derive(PartialEq)]
+struct Visit(f64, usize);
+
+impl Eq for Visit {}
+
+impl Ord for Visit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Visit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,248 @@
+//! Software [`Renderer`] that rasterizes into an RGBA pixel buffer, so the
+//! scene can be exported at any resolution without touching the GPU.
+//!
+//! Circles, rings, lines and rectangles get one pixel of analytic
+//! anti-aliasing; bare triangles are filled by pixel-center coverage so that
+//! quads split into two triangles do not show a seam along the diagonal.
+//! Meshes combine both: edges shared by two triangles are hard, outline
+//! edges are anti-aliased. Text is not rasterized.
+
+use std::collections::HashMap;
+
+use crate::render::{Renderer, Rgba, ScreenPoint, TriangleMesh};
+
+/// Width of the anti-aliased edge in pixels.
+const AA_WIDTH: f32 = 1.0;
+
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major, top row first, non-premultiplied RGB in 0..=1.
+    pixels: Vec<[f32; 3]>,
+}
+
+fn coverage(signed_distance: f32) -> f32 {
+    (0.5 - signed_distance / AA_WIDTH).clamp(0.0, 1.0)
+}
+
+fn distance_to_segment(p: ScreenPoint, a: ScreenPoint, b: ScreenPoint) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a.0 + dx * t, a.1 + dy * t);
+    ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt()
+}
+
+fn edge(a: ScreenPoint, b: ScreenPoint, p: ScreenPoint) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![[0.0; 3]; (width * height) as usize],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [f32; 3] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// 8-bit RGBA, fully opaque, in the layout PNG and GIF encoders expect.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        self.pixels
+            .iter()
+            .flat_map(|p| [to_byte(p[0]), to_byte(p[1]), to_byte(p[2]), 255])
+            .collect()
+    }
+
+    fn blend(&mut self, x: u32, y: u32, color: Rgba, coverage: f32) {
+        let a = color.a * coverage;
+        if a <= 0.0 {
+            return;
+        }
+        let p = &mut self.pixels[(y * self.width + x) as usize];
+        p[0] += (color.r - p[0]) * a;
+        p[1] += (color.g - p[1]) * a;
+        p[2] += (color.b - p[2]) * a;
+    }
+
+    /// Inclusive pixel range covering `min..max` plus the anti-aliasing
+    /// margin, clipped to the canvas.
+    fn pixel_bounds(&self, min: ScreenPoint, max: ScreenPoint) -> Option<(u32, u32, u32, u32)> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let x0 = (min.0 - AA_WIDTH).floor().max(0.0) as u32;
+        let y0 = (min.1 - AA_WIDTH).floor().max(0.0) as u32;
+        let x1 = ((max.0 + AA_WIDTH).ceil().max(0.0) as u32).min(self.width - 1);
+        let y1 = ((max.1 + AA_WIDTH).ceil().max(0.0) as u32).min(self.height - 1);
+        (x0 <= x1 && y0 <= y1).then_some((x0, y0, x1, y1))
+    }
+
+    /// Calls `f` with every pixel center inside the (padded) bounding box and
+    /// blends with the coverage it returns.
+    fn fill<F>(&mut self, min: ScreenPoint, max: ScreenPoint, color: Rgba, f: F)
+    where
+        F: Fn(ScreenPoint) -> f32,
+    {
+        let Some((x0, y0, x1, y1)) = self.pixel_bounds(min, max) else {
+            return;
+        };
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let c = f((x as f32 + 0.5, y as f32 + 0.5));
+                self.blend(x, y, color, c);
+            }
+        }
+    }
+}
+
+impl Renderer for Canvas {
+    fn clear(&mut self, color: Rgba) {
+        self.pixels.fill([color.r, color.g, color.b]);
+    }
+
+    fn line(&mut self, a: ScreenPoint, b: ScreenPoint, thickness: f32, color: Rgba) {
+        let half = thickness * 0.5;
+        let min = (a.0.min(b.0) - half, a.1.min(b.1) - half);
+        let max = (a.0.max(b.0) + half, a.1.max(b.1) + half);
+        self.fill(min, max, color, |p| {
+            coverage(distance_to_segment(p, a, b) - half)
+        });
+    }
+
+    fn circle(&mut self, center: ScreenPoint, radius: f32, color: Rgba) {
+        let min = (center.0 - radius, center.1 - radius);
+        let max = (center.0 + radius, center.1 + radius);
+        self.fill(min, max, color, |p| {
+            coverage(((p.0 - center.0).powi(2) + (p.1 - center.1).powi(2)).sqrt() - radius)
+        });
+    }
+
+    fn circle_lines(&mut self, center: ScreenPoint, radius: f32, thickness: f32, color: Rgba) {
+        let outer = radius + thickness * 0.5;
+        let min = (center.0 - outer, center.1 - outer);
+        let max = (center.0 + outer, center.1 + outer);
+        self.fill(min, max, color, |p| {
+            let d = ((p.0 - center.0).powi(2) + (p.1 - center.1).powi(2)).sqrt();
+            coverage((d - radius).abs() - thickness * 0.5)
+        });
+    }
+
+    fn triangle(&mut self, a: ScreenPoint, b: ScreenPoint, c: ScreenPoint, color: Rgba) {
+        let area = edge(a, b, c);
+        if area == 0.0 {
+            return;
+        }
+        let min = (a.0.min(b.0).min(c.0), a.1.min(b.1).min(c.1));
+        let max = (a.0.max(b.0).max(c.0), a.1.max(b.1).max(c.1));
+        self.fill(min, max, color, |p| {
+            let w = [edge(b, c, p), edge(c, a, p), edge(a, b, p)];
+            let covered = w
+                .iter()
+                .all(|&w| if area > 0.0 { w >= 0.0 } else { w <= 0.0 });
+            if covered { 1.0 } else { 0.0 }
+        });
+    }
+
+    fn rectangle(
+        &mut self,
+        center: ScreenPoint,
+        width: f32,
+        height: f32,
+        rotation: f32,
+        color: Rgba,
+    ) {
+        let (sin, cos) = rotation.sin_cos();
+        let (hw, hh) = (width * 0.5, height * 0.5);
+        let reach = (hw * hw + hh * hh).sqrt();
+        let min = (center.0 - reach, center.1 - reach);
+        let max = (center.0 + reach, center.1 + reach);
+        self.fill(min, max, color, |p| {
+            let (dx, dy) = (p.0 - center.0, p.1 - center.1);
+            let u = dx * cos + dy * sin;
+            let v = -dx * sin + dy * cos;
+            let d = (u.abs() - hw).max(v.abs() - hh);
+            coverage(d)
+        });
+    }
+
+    fn text(&mut self, _text: &str, _position: ScreenPoint, _size: f32, _color: Rgba) {}
+
+    fn mesh(&mut self, mesh: &TriangleMesh) {
+        let mut edge_uses: HashMap<(u32, u32), u32> = HashMap::new();
+        for t in mesh.indices.chunks_exact(3) {
+            for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                *edge_uses.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        for t in mesh.indices.chunks_exact(3) {
+            let v = [t[0], t[1], t[2]].map(|i| mesh.vertices[i as usize]);
+            let p = v.map(|v| v.position);
+            let area = edge(p[0], p[1], p[2]);
+            if area == 0.0 {
+                continue;
+            }
+            // Edge k is opposite vertex k; `shared` edges get a hard cut.
+            let edges = [(1, 2), (2, 0), (0, 1)].map(|(a, b)| {
+                let key = (t[a].min(t[b]), t[a].max(t[b]));
+                let len = (p[b].0 - p[a].0).hypot(p[b].1 - p[a].1);
+                (p[a], p[b], len, edge_uses[&key] > 1)
+            });
+            let min = (
+                p[0].0.min(p[1].0).min(p[2].0),
+                p[0].1.min(p[1].1).min(p[2].1),
+            );
+            let max = (
+                p[0].0.max(p[1].0).max(p[2].0),
+                p[0].1.max(p[1].1).max(p[2].1),
+            );
+            let (x0, y0, x1, y1) = match self.pixel_bounds(min, max) {
+                Some(b) => b,
+                None => continue,
+            };
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    let c = (x as f32 + 0.5, y as f32 + 0.5);
+                    let mut weights = [0.0f32; 3];
+                    let mut cov = 1.0f32;
+                    for (k, &(a, b, len, shared)) in edges.iter().enumerate() {
+                        // Positive inside, in pixels.
+                        let d = edge(a, b, c) / area.signum() / len;
+                        weights[k] = d.max(0.0) * len;
+                        cov = cov.min(if shared {
+                            if d >= 0.0 { 1.0 } else { 0.0 }
+                        } else {
+                            coverage(-d)
+                        });
+                    }
+                    if cov <= 0.0 {
+                        continue;
+                    }
+                    let total: f32 = weights.iter().sum();
+                    let w = if total > 0.0 {
+                        weights.map(|w| w / total)
+                    } else {
+                        [1.0 / 3.0; 3]
+                    };
+                    let color = Rgba::new(
+                        v[0].color.r * w[0] + v[1].color.r * w[1] + v[2].color.r * w[2],
+                        v[0].color.g * w[0] + v[1].color.g * w[1] + v[2].color.g * w[2],
+                        v[0].color.b * w[0] + v[1].color.b * w[1] + v[2].color.b * w[2],
+                        v[0].color.a * w[0] + v[1].color.a * w[1] + v[2].color.a * w[2],
+                    );
+                    self.blend(x, y, color, cov);
+                }
+            }
+        }
+    }
+}
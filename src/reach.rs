@@ -0,0 +1,242 @@
+//! Positions reachable from the start pose with an optimal path no longer
+//! than a length budget.
+//!
+//! The optimal length only depends on the goal relative to the start, so the
+//! field is evaluated once on a grid in the start's frame and moved with the
+//! start pose. For a fixed end heading each grid point is one solver call;
+//! the union over headings keeps the minimum over [`REACH_HEADINGS`] evenly
+//! spaced headings and is cached, since it does not depend on any pose. The
+//! budget only picks the contour level, so its slider never re-evaluates.
+//!
+//! Contours are filled with marching squares: every cell keeps the part of
+//! its square where the bilinear field lies under the level, cut along the
+//! linearly interpolated edge crossings.
+
+use std::thread;
+
+use reeds_shepp_lib::Pose;
+
+use crate::heuristic::{HeuristicTable, LutAxis, LutGrid, exact_distance};
+
+// --- Constants ---
+pub const DEFAULT_REACH_BUDGET: f64 = 3.0;
+pub const MIN_REACH_BUDGET: f64 = 0.5;
+pub const MAX_REACH_BUDGET: f64 = 6.0;
+/// Grid spacing of the field, in world units.
+pub const REACH_RESOLUTION: f64 = 0.2;
+/// End headings minimized over for the union.
+pub const REACH_HEADINGS: usize = 36;
+/// Nested contours at equal fractions of the budget, the last one at it.
+pub const REACH_LEVELS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReachSettings {
+    pub budget: f64,
+    /// Union over all end headings instead of the end pose's heading.
+    pub union: bool,
+}
+
+impl Default for ReachSettings {
+    fn default() -> Self {
+        ReachSettings {
+            budget: DEFAULT_REACH_BUDGET,
+            union: false,
+        }
+    }
+}
+
+/// Optimal lengths from the origin, headed 0°, over a square grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReachField {
+    /// Both axes share this range.
+    pub axis: LutAxis,
+    /// End heading relative to the start, or `None` for the union.
+    pub heading_degree: Option<f64>,
+    /// Row-major with x varying fastest.
+    pub values: Vec<f64>,
+}
+
+/// Line segment between two points, in the same frame as its cell.
+pub type Segment = ((f64, f64), (f64, f64));
+
+/// Filled region under one contour level, in world coordinates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReachRegion {
+    pub level: f64,
+    pub triangles: Vec<[(f64, f64); 3]>,
+    /// Boundary pieces, each between two edge crossings of one cell.
+    pub outline: Vec<Segment>,
+}
+
+/// The grid axis covering every position within `extent` of the start.
+pub fn reach_axis(extent: f64, resolution: f64) -> LutAxis {
+    let cells = (2.0 * extent / resolution).ceil() as usize;
+    LutAxis {
+        min: -extent,
+        max: extent,
+        count: cells + 1,
+    }
+}
+
+impl ReachField {
+    /// Exact lengths to every grid point with the relative end heading fixed.
+    pub fn for_heading(axis: LutAxis, heading_degree: f64) -> Self {
+        let values = (0..axis.count * axis.count)
+            .map(|i| {
+                exact_distance(Pose {
+                    x: axis.value(i % axis.count),
+                    y: axis.value(i / axis.count),
+                    theta_degree: heading_degree,
+                })
+            })
+            .collect();
+        ReachField {
+            axis,
+            heading_degree: Some(heading_degree),
+            values,
+        }
+    }
+
+    /// Shortest length to every grid point over `headings` end headings,
+    /// evaluated on all available cores.
+    pub fn union(axis: LutAxis, headings: usize) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let grid = LutGrid {
+            x: axis,
+            y: axis,
+            theta_count: headings,
+        };
+        let table = HeuristicTable::compute(grid, threads);
+        let values = (0..axis.count * axis.count)
+            .map(|i| {
+                let (ix, iy) = (i % axis.count, i / axis.count);
+                (0..headings)
+                    .map(|it| table.get(ix, iy, it) as f64)
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        ReachField {
+            axis,
+            heading_degree: None,
+            values,
+        }
+    }
+
+    pub fn get(&self, ix: usize, iy: usize) -> f64 {
+        self.values[iy * self.axis.count + ix]
+    }
+
+    /// Area of the grid under `level`, in square world units, counting
+    /// whole cells whose center value (the corner mean) lies under it.
+    pub fn area_under(&self, level: f64) -> f64 {
+        let n = self.axis.count;
+        let cell = self.axis.step() * self.axis.step();
+        let mut count = 0;
+        for iy in 0..n - 1 {
+            for ix in 0..n - 1 {
+                let mean = (self.get(ix, iy)
+                    + self.get(ix + 1, iy)
+                    + self.get(ix + 1, iy + 1)
+                    + self.get(ix, iy + 1))
+                    / 4.0;
+                if mean <= level {
+                    count += 1;
+                }
+            }
+        }
+        count as f64 * cell
+    }
+
+    /// Region under `level` with the field placed at `start`.
+    pub fn region(&self, level: f64, start: &Pose) -> ReachRegion {
+        let (sin, cos) = start.theta_degree.to_radians().sin_cos();
+        let to_world =
+            |(x, y): (f64, f64)| (start.x + x * cos - y * sin, start.y + x * sin + y * cos);
+        let mut region = ReachRegion {
+            level,
+            ..Default::default()
+        };
+        let n = self.axis.count;
+        for iy in 0..n - 1 {
+            for ix in 0..n - 1 {
+                // Counter-clockwise from the lower left corner.
+                let cells = [(ix, iy), (ix + 1, iy), (ix + 1, iy + 1), (ix, iy + 1)];
+                let corners = cells.map(|(i, j)| (self.axis.value(i), self.axis.value(j)));
+                let values = cells.map(|(i, j)| self.get(i, j));
+                let (polygon, outline) = cell_region(corners, values, level);
+                for k in 1..polygon.len().saturating_sub(1) {
+                    region.triangles.push([
+                        to_world(polygon[0]),
+                        to_world(polygon[k]),
+                        to_world(polygon[k + 1]),
+                    ]);
+                }
+                region
+                    .outline
+                    .extend(outline.into_iter().map(|(a, b)| (to_world(a), to_world(b))));
+            }
+        }
+        region
+    }
+
+    /// Regions at `REACH_LEVELS` equal fractions of `budget`, smallest first.
+    pub fn regions(&self, budget: f64, start: &Pose) -> Vec<ReachRegion> {
+        (1..=REACH_LEVELS)
+            .map(|k| self.region(budget * k as f64 / REACH_LEVELS as f64, start))
+            .collect()
+    }
+}
+
+/// Part of one grid cell under `level`, as a polygon in corner order, and
+/// the boundary segments between consecutive edge crossings.
+fn cell_region(
+    corners: [(f64, f64); 4],
+    values: [f64; 4],
+    level: f64,
+) -> (Vec<(f64, f64)>, Vec<Segment>) {
+    let inside = values.map(|v| v <= level);
+    let mut polygon: Vec<(f64, f64)> = Vec::with_capacity(6);
+    let mut crossing: Vec<bool> = Vec::with_capacity(6);
+    // A corner right on the level coincides with its crossings; keep one
+    // point and let it count as a crossing.
+    let mut push = |p: (f64, f64), is_crossing: bool| {
+        if polygon.last() == Some(&p) {
+            *crossing.last_mut().unwrap() |= is_crossing;
+        } else {
+            polygon.push(p);
+            crossing.push(is_crossing);
+        }
+    };
+    for i in 0..4 {
+        let j = (i + 1) % 4;
+        if inside[i] {
+            push(corners[i], false);
+        }
+        if inside[i] != inside[j] {
+            let t = (level - values[i]) / (values[j] - values[i]);
+            push(
+                (
+                    corners[i].0 + (corners[j].0 - corners[i].0) * t,
+                    corners[i].1 + (corners[j].1 - corners[i].1) * t,
+                ),
+                true,
+            );
+        }
+    }
+    if polygon.len() > 1 && polygon.first() == polygon.last() {
+        polygon.pop();
+        let last = crossing.pop().unwrap();
+        crossing[0] |= last;
+    }
+    if polygon.len() < 3 {
+        return (Vec::new(), Vec::new());
+    }
+    let mut outline = Vec::new();
+    let len = polygon.len();
+    for k in 0..len {
+        if crossing[k] && crossing[(k + 1) % len] {
+            outline.push((polygon[k], polygon[(k + 1) % len]));
+        }
+    }
+    (polygon, outline)
+}
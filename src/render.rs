@@ -0,0 +1,301 @@
+//! Drawing backend abstraction. Scene code only talks to [`Renderer`]; the
+//! binary implements it on top of macroquad, and [`RecordingRenderer`]
+//! captures the primitives so a scene can be snapshot-tested headless.
+
+use std::fmt::Write;
+
+/// Screen-space position in pixels, y pointing down.
+pub type ScreenPoint = (f32, f32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Rgba {
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Rgba { r, g, b, a }
+    }
+
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Rgba { a, ..self }
+    }
+}
+
+// --- Colors ---
+pub const WHITE: Rgba = Rgba::new(1.0, 1.0, 1.0, 1.0);
+pub const LIGHTGRAY: Rgba = Rgba::new(0.78, 0.78, 0.78, 1.0);
+pub const YELLOW: Rgba = Rgba::new(0.99, 0.98, 0.0, 1.0);
+pub const RED: Rgba = Rgba::new(0.90, 0.16, 0.22, 1.0);
+
+/// Corner of a [`TriangleMesh`] with its own color; colors are interpolated
+/// across each triangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshVertex {
+    pub position: ScreenPoint,
+    pub color: Rgba,
+}
+
+/// Indexed triangle list, drawn as one batch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TriangleMesh {
+    pub vertices: Vec<MeshVertex>,
+    /// Three entries per triangle.
+    pub indices: Vec<u32>,
+}
+
+impl TriangleMesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Appends a vertex and returns its index.
+    pub fn push_vertex(&mut self, position: ScreenPoint, color: Rgba) -> u32 {
+        self.vertices.push(MeshVertex { position, color });
+        (self.vertices.len() - 1) as u32
+    }
+
+    pub fn push_triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend([a, b, c]);
+    }
+}
+
+pub trait Renderer {
+    fn clear(&mut self, color: Rgba);
+    fn line(&mut self, a: ScreenPoint, b: ScreenPoint, thickness: f32, color: Rgba);
+    fn circle(&mut self, center: ScreenPoint, radius: f32, color: Rgba);
+    fn circle_lines(&mut self, center: ScreenPoint, radius: f32, thickness: f32, color: Rgba);
+    fn triangle(&mut self, a: ScreenPoint, b: ScreenPoint, c: ScreenPoint, color: Rgba);
+    /// Filled `width` x `height` rectangle centered on `center`, rotated by
+    /// `rotation` radians (clockwise on screen).
+    fn rectangle(
+        &mut self,
+        center: ScreenPoint,
+        width: f32,
+        height: f32,
+        rotation: f32,
+        color: Rgba,
+    );
+    fn text(&mut self, text: &str, position: ScreenPoint, size: f32, color: Rgba);
+    fn mesh(&mut self, mesh: &TriangleMesh);
+}
+
+/// One captured draw call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    Clear(Rgba),
+    Line {
+        a: ScreenPoint,
+        b: ScreenPoint,
+        thickness: f32,
+        color: Rgba,
+    },
+    Circle {
+        center: ScreenPoint,
+        radius: f32,
+        color: Rgba,
+    },
+    CircleLines {
+        center: ScreenPoint,
+        radius: f32,
+        thickness: f32,
+        color: Rgba,
+    },
+    Triangle {
+        a: ScreenPoint,
+        b: ScreenPoint,
+        c: ScreenPoint,
+        color: Rgba,
+    },
+    Rectangle {
+        center: ScreenPoint,
+        width: f32,
+        height: f32,
+        rotation: f32,
+        color: Rgba,
+    },
+    Text {
+        text: String,
+        position: ScreenPoint,
+        size: f32,
+        color: Rgba,
+    },
+    Mesh(TriangleMesh),
+}
+
+/// Renderer that only remembers what it was asked to draw.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingRenderer {
+    pub primitives: Vec<Primitive>,
+}
+
+impl RecordingRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stable text form of the captured primitives, one per line, with
+    /// coordinates rounded to a tenth of a pixel so that snapshots survive
+    /// harmless floating point noise.
+    pub fn snapshot(&self) -> String {
+        let p = |(x, y): ScreenPoint| format!("({:.1},{:.1})", x, y);
+        let c = |c: &Rgba| format!("rgba({:.2},{:.2},{:.2},{:.2})", c.r, c.g, c.b, c.a);
+        let mut out = String::new();
+        for primitive in &self.primitives {
+            let _ = match primitive {
+                Primitive::Clear(color) => writeln!(out, "clear {}", c(color)),
+                Primitive::Line {
+                    a,
+                    b,
+                    thickness,
+                    color,
+                } => writeln!(
+                    out,
+                    "line {} {} w={:.1} {}",
+                    p(*a),
+                    p(*b),
+                    thickness,
+                    c(color)
+                ),
+                Primitive::Circle {
+                    center,
+                    radius,
+                    color,
+                } => writeln!(out, "circle {} r={:.1} {}", p(*center), radius, c(color)),
+                Primitive::CircleLines {
+                    center,
+                    radius,
+                    thickness,
+                    color,
+                } => writeln!(
+                    out,
+                    "circle_lines {} r={:.1} w={:.1} {}",
+                    p(*center),
+                    radius,
+                    thickness,
+                    c(color)
+                ),
+                Primitive::Triangle { a, b, c: v, color } => {
+                    writeln!(out, "triangle {} {} {} {}", p(*a), p(*b), p(*v), c(color))
+                }
+                Primitive::Rectangle {
+                    center,
+                    width,
+                    height,
+                    rotation,
+                    color,
+                } => writeln!(
+                    out,
+                    "rectangle {} {:.1}x{:.1} rot={:.3} {}",
+                    p(*center),
+                    width,
+                    height,
+                    rotation,
+                    c(color)
+                ),
+                Primitive::Text {
+                    text,
+                    position,
+                    size,
+                    color,
+                } => writeln!(
+                    out,
+                    "text {:?} {} size={:.1} {}",
+                    text,
+                    p(*position),
+                    size,
+                    c(color)
+                ),
+                Primitive::Mesh(mesh) => {
+                    let _ = writeln!(
+                        out,
+                        "mesh vertices={} triangles={}",
+                        mesh.vertices.len(),
+                        mesh.indices.len() / 3
+                    );
+                    for t in mesh.indices.chunks(3) {
+                        let v = t.iter().map(|&i| mesh.vertices[i as usize]);
+                        let corners: Vec<String> = v.map(|v| p(v.position)).collect();
+                        let color = c(&mesh.vertices[t[0] as usize].color);
+                        let _ = writeln!(out, "  tri {} {}", corners.join(" "), color);
+                    }
+                    Ok(())
+                }
+            };
+        }
+        out
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn clear(&mut self, color: Rgba) {
+        self.primitives.push(Primitive::Clear(color));
+    }
+
+    fn line(&mut self, a: ScreenPoint, b: ScreenPoint, thickness: f32, color: Rgba) {
+        self.primitives.push(Primitive::Line {
+            a,
+            b,
+            thickness,
+            color,
+        });
+    }
+
+    fn circle(&mut self, center: ScreenPoint, radius: f32, color: Rgba) {
+        self.primitives.push(Primitive::Circle {
+            center,
+            radius,
+            color,
+        });
+    }
+
+    fn circle_lines(&mut self, center: ScreenPoint, radius: f32, thickness: f32, color: Rgba) {
+        self.primitives.push(Primitive::CircleLines {
+            center,
+            radius,
+            thickness,
+            color,
+        });
+    }
+
+    fn triangle(&mut self, a: ScreenPoint, b: ScreenPoint, c: ScreenPoint, color: Rgba) {
+        self.primitives.push(Primitive::Triangle { a, b, c, color });
+    }
+
+    fn rectangle(
+        &mut self,
+        center: ScreenPoint,
+        width: f32,
+        height: f32,
+        rotation: f32,
+        color: Rgba,
+    ) {
+        self.primitives.push(Primitive::Rectangle {
+            center,
+            width,
+            height,
+            rotation,
+            color,
+        });
+    }
+
+    fn text(&mut self, text: &str, position: ScreenPoint, size: f32, color: Rgba) {
+        self.primitives.push(Primitive::Text {
+            text: text.to_string(),
+            position,
+            size,
+            color,
+        });
+    }
+
+    fn mesh(&mut self, mesh: &TriangleMesh) {
+        self.primitives.push(Primitive::Mesh(mesh.clone()));
+    }
+}
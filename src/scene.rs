@@ -0,0 +1,929 @@
+//! The canvas contents (turning circles, paths, trailer, cars) drawn through
+//! a [`Renderer`], shared by the window and every export backend.
+
+use reeds_shepp_lib::{Gear, Path, Pose, utils};
+
+use crate::candidates::transform_suffix;
+use crate::car::{self, CAR_LENGTH, CAR_WIDTH};
+use crate::construction::TangentKind;
+use crate::gallery::GalleryLayout;
+use crate::grid::nice_step;
+use crate::obstacle::{Obstacle, cspace_obstacle, pose_collides};
+use crate::path::{PathSample, TURNING_RADIUS, path_shape, path_turning_circle_centers};
+use crate::render::{RED, Renderer, Rgba, ScreenPoint, TriangleMesh};
+use crate::state::{DisplayMode, PathColoring, State};
+use crate::stroke::{arrowhead, stroke_polyline};
+use crate::sweep::{SweepChart, SweepSample, length_range, nearest_sample};
+use crate::trailer::{TrailerModel, TrailerSample, trailer_body_corners};
+use crate::transforms::{ExplorerLayout, Feasibility};
+use crate::view::View;
+
+// --- Constants ---
+pub const HEADLIGHT_SIZE_SCREEN: f32 = 8.0;
+pub const BEAM_LENGTH: f32 = 60.0;
+pub const BEAM_WIDTH: f32 = 40.0;
+pub const TURNING_CIRCLE_OPACITY: f32 = 0.15;
+pub const TRAILER_DRAW_INTERVAL: f64 = 0.5;
+/// Grid lines closer than this on screen are thinned out.
+pub const MIN_GRID_LINE_GAP: f32 = 12.0;
+/// Axis labels and the scale bar use the smallest 1-2-5 step at least this long.
+pub const AXIS_LABEL_GAP: f32 = 60.0;
+pub const SCALE_BAR_MIN_LENGTH: f32 = 80.0;
+pub const SCALE_BAR_MARGIN: f32 = 20.0;
+pub const SELECTED_PATH_THICKNESS: f32 = 3.0;
+/// World distance between direction arrows on the selected path.
+pub const ARROW_SPACING: f64 = 1.0;
+pub const ARROW_SIZE: f32 = 12.0;
+/// Path poses within this many degrees of the C-space slice heading are marked.
+pub const CSPACE_HEADING_TOLERANCE_DEGREE: f64 = 5.0;
+/// World length of the traced frame's axes.
+pub const TRACE_AXIS_LENGTH: f64 = 2.0;
+
+// --- Colors ---
+pub const BG_COLOR: Rgba = Rgba::new(0.15, 0.15, 0.18, 1.0);
+pub const START_CAR_COLOR: Rgba = Rgba::new(0.7, 0.9, 0.7, 1.0);
+pub const END_CAR_COLOR: Rgba = Rgba::new(0.4, 0.5, 0.9, 1.0);
+pub const SELECTED_PATH_COLOR: Rgba = Rgba::new(1.0, 0.6, 0.1, 1.0);
+pub const FORWARD_PATH_COLOR: Rgba = SELECTED_PATH_COLOR;
+pub const REVERSE_PATH_COLOR: Rgba = Rgba::new(0.95, 0.3, 0.75, 1.0);
+pub const LEFT_PATH_COLOR: Rgba = Rgba::new(0.35, 0.75, 1.0, 1.0);
+pub const RIGHT_PATH_COLOR: Rgba = Rgba::new(1.0, 0.85, 0.25, 1.0);
+pub const STRAIGHT_PATH_COLOR: Rgba = Rgba::new(0.85, 0.85, 0.85, 1.0);
+pub const ALL_PATHS_COLOR: Rgba = Rgba::new(0.8, 0.8, 0.8, 0.3);
+pub const HEADLIGHT_COLOR: Rgba = Rgba::new(1.0, 1.0, 0.7, 1.0);
+pub const BEAM_COLOR: Rgba = Rgba::new(1.0, 1.0, 0.5, 0.4);
+pub const TURNING_CIRCLE_COLOR: Rgba = Rgba::new(0.8, 0.8, 0.8, TURNING_CIRCLE_OPACITY);
+pub const CC_PATH_COLOR: Rgba = Rgba::new(0.3, 0.9, 1.0, 0.9);
+pub const TRAILER_COLOR: Rgba = Rgba::new(0.8, 0.6, 0.9, 0.25);
+pub const JACKKNIFE_COLOR: Rgba = Rgba::new(1.0, 0.2, 0.2, 0.6);
+pub const HOVERED_PATH_COLOR: Rgba = Rgba::new(0.4, 1.0, 0.6, 0.9);
+pub const INSPECTOR_COLOR: Rgba = Rgba::new(1.0, 1.0, 1.0, 0.9);
+pub const GHOST_CAR_COLOR: Rgba = Rgba::new(1.0, 1.0, 1.0, 0.35);
+pub const CUSTOM_PATH_COLOR: Rgba = Rgba::new(0.5, 1.0, 0.3, 0.9);
+pub const SKETCH_COLOR: Rgba = Rgba::new(1.0, 0.45, 0.7, 0.7);
+pub const OBSTACLE_COLOR: Rgba = Rgba::new(0.55, 0.5, 0.45, 1.0);
+pub const OBSTACLE_DRAFT_COLOR: Rgba = Rgba::new(0.55, 0.5, 0.45, 0.5);
+pub const CSPACE_COLOR: Rgba = Rgba::new(0.9, 0.25, 0.2, 0.3);
+pub const CSPACE_POSE_COLOR: Rgba = Rgba::new(1.0, 1.0, 1.0, 0.9);
+pub const ROADMAP_COLOR: Rgba = Rgba::new(0.7, 0.8, 1.0, 0.12);
+pub const ROADMAP_NODE_COLOR: Rgba = Rgba::new(0.7, 0.8, 1.0, 0.4);
+pub const LATTICE_PRIMITIVE_COLOR: Rgba = Rgba::new(0.6, 0.9, 0.8, 0.35);
+pub const LATTICE_STATE_COLOR: Rgba = Rgba::new(0.6, 0.9, 0.8, 0.9);
+pub const GRID_COLOR: Rgba = Rgba::new(1.0, 1.0, 1.0, 0.06);
+pub const AXIS_COLOR: Rgba = Rgba::new(1.0, 1.0, 1.0, 0.3);
+pub const SCALE_BAR_COLOR: Rgba = Rgba::new(0.9, 0.9, 0.9, 0.8);
+pub const REACH_COLOR: Rgba = Rgba::new(0.35, 0.9, 0.5, 0.12);
+pub const REACH_OUTLINE_COLOR: Rgba = Rgba::new(0.35, 0.9, 0.5, 0.8);
+pub const RUNNER_UP_COLOR: Rgba = Rgba::new(0.6, 0.7, 1.0, 0.6);
+pub const FEASIBLE_COLOR: Rgba = Rgba::new(0.35, 0.9, 0.4, 1.0);
+pub const MISSES_GOAL_COLOR: Rgba = Rgba::new(1.0, 0.6, 0.2, 1.0);
+pub const INFEASIBLE_PATH_COLOR: Rgba = Rgba::new(0.6, 0.6, 0.6, 0.5);
+pub const TRACE_X_AXIS_COLOR: Rgba = Rgba::new(1.0, 0.4, 0.4, 0.9);
+pub const TRACE_Y_AXIS_COLOR: Rgba = Rgba::new(0.4, 1.0, 0.4, 0.9);
+pub const TRACE_COLOR: Rgba = Rgba::new(0.95, 0.9, 0.5, 0.9);
+pub const OUTER_TANGENT_COLOR: Rgba = Rgba::new(0.45, 0.8, 1.0, 0.35);
+pub const INNER_TANGENT_COLOR: Rgba = Rgba::new(1.0, 0.7, 0.4, 0.35);
+pub const MIDDLE_CIRCLE_COLOR: Rgba = Rgba::new(0.8, 0.6, 1.0, 0.25);
+pub const SWEEP_BG_COLOR: Rgba = Rgba::new(0.08, 0.08, 0.1, 0.85);
+/// One color per `PATH_FNS` entry, for charts keyed by family.
+pub const FAMILY_COLORS: [Rgba; 12] = [
+    Rgba::new(0.9, 0.35, 0.3, 1.0),
+    Rgba::new(0.95, 0.6, 0.2, 1.0),
+    Rgba::new(0.95, 0.85, 0.3, 1.0),
+    Rgba::new(0.6, 0.85, 0.3, 1.0),
+    Rgba::new(0.3, 0.8, 0.45, 1.0),
+    Rgba::new(0.3, 0.85, 0.8, 1.0),
+    Rgba::new(0.35, 0.65, 0.95, 1.0),
+    Rgba::new(0.45, 0.45, 0.95, 1.0),
+    Rgba::new(0.7, 0.45, 0.95, 1.0),
+    Rgba::new(0.9, 0.45, 0.85, 1.0),
+    Rgba::new(0.95, 0.55, 0.6, 1.0),
+    Rgba::new(0.75, 0.75, 0.75, 1.0),
+];
+
+fn offset(p: ScreenPoint, dir: ScreenPoint, k: f32) -> ScreenPoint {
+    (p.0 + dir.0 * k, p.1 + dir.1 * k)
+}
+
+pub fn draw_pose_elements(r: &mut impl Renderer, view: &View, pose: &Pose, body_color: Rgba) {
+    let cs = view.world_to_screen(pose.x, pose.y);
+    let r_rad_w = pose.theta_degree.to_radians();
+    let r_rad_s = -r_rad_w as f32;
+    let (hx_w, hy_w) = car::headlight_world_pos(pose);
+    let hs = view.world_to_screen(hx_w, hy_w);
+    let b_dir = (r_rad_s.cos(), r_rad_s.sin());
+    let b_norm = (-b_dir.1, b_dir.0);
+    let bs1 = offset(hs, b_norm, HEADLIGHT_SIZE_SCREEN * 0.5);
+    let bs2 = offset(hs, b_norm, -HEADLIGHT_SIZE_SCREEN * 0.5);
+    let beam_end = offset(hs, b_dir, BEAM_LENGTH);
+    let be1 = offset(beam_end, b_norm, BEAM_WIDTH * 0.5);
+    let be2 = offset(beam_end, b_norm, -BEAM_WIDTH * 0.5);
+    r.triangle(bs1, be1, be2, BEAM_COLOR);
+    r.triangle(bs1, be2, bs2, BEAM_COLOR);
+    r.rectangle(
+        cs,
+        CAR_LENGTH as f32 * view.scale,
+        CAR_WIDTH as f32 * view.scale,
+        r_rad_s,
+        body_color,
+    );
+    r.circle(hs, HEADLIGHT_SIZE_SCREEN, RED);
+    r.circle(hs, HEADLIGHT_SIZE_SCREEN * 0.6, HEADLIGHT_COLOR);
+}
+
+/// Appends a uniformly colored stroke through the sample positions.
+pub fn stroke_samples(
+    mesh: &mut TriangleMesh,
+    view: &View,
+    samples: &[PathSample],
+    thickness: f32,
+    color: Rgba,
+) {
+    let points: Vec<ScreenPoint> = samples
+        .iter()
+        .map(|p| view.world_to_screen(p.x, p.y))
+        .collect();
+    stroke_polyline(mesh, &points, &vec![color; points.len()], thickness);
+}
+
+pub fn draw_polyline(
+    r: &mut impl Renderer,
+    view: &View,
+    samples: &[PathSample],
+    thickness: f32,
+    color: Rgba,
+) {
+    let mut mesh = TriangleMesh::new();
+    stroke_samples(&mut mesh, view, samples, thickness, color);
+    if !mesh.is_empty() {
+        r.mesh(&mesh);
+    }
+}
+
+/// Color of the selected path at `sample` under `coloring`.
+pub fn sample_color(sample: &PathSample, coloring: PathColoring) -> Rgba {
+    match coloring {
+        PathColoring::Gear => match sample.gear {
+            Gear::Forward => FORWARD_PATH_COLOR,
+            Gear::Backwards => REVERSE_PATH_COLOR,
+        },
+        PathColoring::Steering => {
+            if sample.curvature > 0.0 {
+                LEFT_PATH_COLOR
+            } else if sample.curvature < 0.0 {
+                RIGHT_PATH_COLOR
+            } else {
+                STRAIGHT_PATH_COLOR
+            }
+        }
+    }
+}
+
+/// Appends the selected path, colored per sample, with an arrowhead every
+/// [`ARROW_SPACING`] showing the direction of travel.
+pub fn stroke_selected_path(
+    mesh: &mut TriangleMesh,
+    view: &View,
+    samples: &[PathSample],
+    coloring: PathColoring,
+) {
+    let points: Vec<ScreenPoint> = samples
+        .iter()
+        .map(|p| view.world_to_screen(p.x, p.y))
+        .collect();
+    let colors: Vec<Rgba> = samples.iter().map(|p| sample_color(p, coloring)).collect();
+    stroke_polyline(mesh, &points, &colors, SELECTED_PATH_THICKNESS);
+
+    let mut next_s = ARROW_SPACING / 2.0;
+    for (sample, color) in samples.iter().zip(&colors) {
+        if sample.s < next_s {
+            continue;
+        }
+        next_s = sample.s + ARROW_SPACING;
+        let travel = match sample.gear {
+            Gear::Forward => sample.theta,
+            Gear::Backwards => sample.theta + std::f64::consts::PI,
+        };
+        // Screen y points down, so the world heading is mirrored.
+        let dir = (travel.cos() as f32, -travel.sin() as f32);
+        let center = view.world_to_screen(sample.x, sample.y);
+        arrowhead(mesh, center, dir, ARROW_SIZE, *color);
+    }
+}
+
+/// All path polylines of the current mode in one mesh, bottom to top:
+/// candidates, the selected path with arrows, the hovered candidate and the
+/// CC path.
+pub fn draw_paths(r: &mut impl Renderer, view: &View, state: &State) {
+    let mut mesh = TriangleMesh::new();
+    if !state.sketch_points.is_empty() {
+        let points: Vec<ScreenPoint> = state
+            .sketch_points
+            .iter()
+            .map(|&(x, y)| view.world_to_screen(x, y))
+            .collect();
+        let colors = vec![SKETCH_COLOR; points.len()];
+        stroke_polyline(&mut mesh, &points, &colors, 4.0);
+    }
+    if state.display_mode == DisplayMode::Prm {
+        for samples in &state.roadmap_samples {
+            stroke_samples(&mut mesh, view, samples, 1.0, ROADMAP_COLOR);
+        }
+    }
+    for samples in &state.lattice_primitive_samples {
+        stroke_samples(&mut mesh, view, samples, 1.5, LATTICE_PRIMITIVE_COLOR);
+    }
+    if state.display_mode == DisplayMode::AllPaths {
+        for samples in &state.all_paths_samples {
+            stroke_samples(&mut mesh, view, samples, 1.0, ALL_PATHS_COLOR);
+        }
+    }
+    if let Some(samples) = &state.runner_up_samples {
+        stroke_samples(&mut mesh, view, samples, 2.0, RUNNER_UP_COLOR);
+    }
+    if let Some(samples) = &state.current_path_samples {
+        stroke_selected_path(&mut mesh, view, samples, state.path_coloring);
+    }
+    if state.display_mode == DisplayMode::AllPaths
+        && let Some(samples) = state
+            .hovered_candidate
+            .and_then(|i| state.all_paths_samples.get(i))
+    {
+        stroke_samples(&mut mesh, view, samples, 2.0, HOVERED_PATH_COLOR);
+    }
+    if let Some(samples) = &state.current_cc_samples {
+        stroke_samples(&mut mesh, view, samples, 2.0, CC_PATH_COLOR);
+    }
+    if let Some(samples) = &state.custom_path_samples {
+        stroke_samples(&mut mesh, view, samples, 2.0, CUSTOM_PATH_COLOR);
+    }
+    if !mesh.is_empty() {
+        r.mesh(&mesh);
+    }
+}
+
+pub fn draw_trailer(
+    r: &mut impl Renderer,
+    view: &View,
+    samples: &[TrailerSample],
+    model: &TrailerModel,
+) {
+    let mut next_s = 0.0;
+    for (i, sample) in samples.iter().enumerate() {
+        if sample.jackknifed {
+            let p = view.world_to_screen(sample.hitch.0, sample.hitch.1);
+            r.circle(p, 3.0, JACKKNIFE_COLOR);
+        }
+        if sample.s + 1e-9 < next_s && i + 1 != samples.len() {
+            continue;
+        }
+        next_s = sample.s + TRAILER_DRAW_INTERVAL;
+        let color = if sample.jackknifed {
+            JACKKNIFE_COLOR
+        } else {
+            TRAILER_COLOR
+        };
+        let c = trailer_body_corners(sample, model).map(|(x, y)| view.world_to_screen(x, y));
+        r.triangle(c[0], c[1], c[2], color);
+        r.triangle(c[0], c[2], c[3], color);
+        let axle = view.world_to_screen(sample.axle.0, sample.axle.1);
+        let hitch = view.world_to_screen(sample.hitch.0, sample.hitch.1);
+        r.line(hitch, axle, 1.0, color);
+    }
+}
+
+pub fn draw_path_turning_circles(
+    r: &mut impl Renderer,
+    view: &View,
+    path: &Path,
+    start_pose: &Pose,
+) {
+    let trs = TURNING_RADIUS as f32 * view.scale;
+    for (tcx, tcy) in path_turning_circle_centers(path, start_pose) {
+        let tcs = view.world_to_screen(tcx, tcy);
+        r.circle_lines(tcs, trs, 1.0, TURNING_CIRCLE_COLOR);
+        r.circle(tcs, 2.0, TURNING_CIRCLE_COLOR);
+    }
+}
+
+/// Turning circles of both poses, their common tangents and the CCC middle
+/// circles. Tangents and middle circles the selected path uses are drawn
+/// opaque and thicker.
+pub fn draw_construction(r: &mut impl Renderer, view: &View, state: &State) {
+    let Some(construction) = &state.construction else {
+        return;
+    };
+    let radius = TURNING_RADIUS as f32 * view.scale;
+    for (i, circle) in construction.middle.iter().enumerate() {
+        let used = state.construction_middle_used.contains(&i);
+        let (color, thickness) = if used {
+            (MIDDLE_CIRCLE_COLOR.with_alpha(0.9), 2.0)
+        } else {
+            (MIDDLE_CIRCLE_COLOR, 1.0)
+        };
+        let center = view.world_to_screen(circle.center.0, circle.center.1);
+        r.circle_lines(center, radius, thickness, color);
+        r.circle(center, 2.0, color);
+    }
+    for (i, circle) in construction.circles.iter().enumerate() {
+        let color = if i < 2 {
+            START_CAR_COLOR
+        } else {
+            END_CAR_COLOR
+        };
+        let center = view.world_to_screen(circle.center.0, circle.center.1);
+        r.circle_lines(center, radius, 1.0, color.with_alpha(0.5));
+        r.circle(center, 2.0, color.with_alpha(0.5));
+    }
+    for (i, tangent) in construction.tangents.iter().enumerate() {
+        let color = match tangent.kind {
+            TangentKind::Outer => OUTER_TANGENT_COLOR,
+            TangentKind::Inner => INNER_TANGENT_COLOR,
+        };
+        let (color, thickness) = if state.construction_tangents_used.contains(&i) {
+            (color.with_alpha(0.9), 2.5)
+        } else {
+            (color, 1.0)
+        };
+        let a = view.world_to_screen(tangent.from.0, tangent.from.1);
+        let b = view.world_to_screen(tangent.to.0, tangent.to.1);
+        r.line(a, b, thickness, color);
+        r.circle(a, thickness + 1.0, color);
+        r.circle(b, thickness + 1.0, color);
+    }
+}
+
+/// Multiples of `step` inside `[min, max]`.
+fn grid_values(min: f64, max: f64, step: f64) -> impl Iterator<Item = f64> {
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(move |i| i as f64 * step)
+}
+
+fn format_grid_value(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+/// Metric grid every `spacing` world units, the labelled axes through the
+/// world origin and a scale bar in the bottom right corner.
+pub fn draw_grid(r: &mut impl Renderer, view: &View, spacing: f64) {
+    let (min_x, max_y) = view.screen_to_world((0.0, 0.0));
+    let (max_x, min_y) = view.screen_to_world((view.width, view.height));
+    let scale = view.scale as f64;
+
+    let thinning = (MIN_GRID_LINE_GAP as f64 / (spacing * scale))
+        .ceil()
+        .max(1.0);
+    let step = spacing * thinning;
+    for x in grid_values(min_x, max_x, step) {
+        let sx = view.world_to_screen(x, 0.0).0;
+        r.line((sx, 0.0), (sx, view.height), 1.0, GRID_COLOR);
+    }
+    for y in grid_values(min_y, max_y, step) {
+        let sy = view.world_to_screen(0.0, y).1;
+        r.line((0.0, sy), (view.width, sy), 1.0, GRID_COLOR);
+    }
+
+    let origin = view.world_to_screen(0.0, 0.0);
+    r.line((0.0, origin.1), (view.width, origin.1), 1.0, AXIS_COLOR);
+    r.line((origin.0, 0.0), (origin.0, view.height), 1.0, AXIS_COLOR);
+    r.text("x", (view.width - 14.0, origin.1 - 6.0), 16.0, AXIS_COLOR);
+    r.text("y", (origin.0 + 6.0, 14.0), 16.0, AXIS_COLOR);
+
+    let label_step = nice_step(AXIS_LABEL_GAP as f64 / scale);
+    for x in grid_values(min_x, max_x, label_step).filter(|x| *x != 0.0) {
+        let (sx, sy) = view.world_to_screen(x, 0.0);
+        r.line((sx, sy - 3.0), (sx, sy + 3.0), 1.0, AXIS_COLOR);
+        r.text(
+            &format_grid_value(x, label_step),
+            (sx + 2.0, sy + 14.0),
+            14.0,
+            AXIS_COLOR,
+        );
+    }
+    for y in grid_values(min_y, max_y, label_step).filter(|y| *y != 0.0) {
+        let (sx, sy) = view.world_to_screen(0.0, y);
+        r.line((sx - 3.0, sy), (sx + 3.0, sy), 1.0, AXIS_COLOR);
+        r.text(
+            &format_grid_value(y, label_step),
+            (sx + 5.0, sy - 2.0),
+            14.0,
+            AXIS_COLOR,
+        );
+    }
+
+    let bar_world = nice_step(SCALE_BAR_MIN_LENGTH as f64 / scale);
+    let bar = (bar_world * scale) as f32;
+    let right = view.width - SCALE_BAR_MARGIN;
+    let y = view.height - SCALE_BAR_MARGIN;
+    r.line((right - bar, y), (right, y), 2.0, SCALE_BAR_COLOR);
+    r.line(
+        (right - bar, y - 5.0),
+        (right - bar, y + 1.0),
+        2.0,
+        SCALE_BAR_COLOR,
+    );
+    r.line((right, y - 5.0), (right, y + 1.0), 2.0, SCALE_BAR_COLOR);
+    let label = format!("{} m", format_grid_value(bar_world, bar_world));
+    r.text(&label, (right - bar, y - 8.0), 16.0, SCALE_BAR_COLOR);
+}
+
+/// Nested reachable regions as stacked translucent fills, so shorter
+/// budgets read brighter, with the outline of the full budget on top.
+pub fn draw_reach(r: &mut impl Renderer, view: &View, state: &State) {
+    let mut mesh = TriangleMesh::new();
+    for region in &state.reach_regions {
+        for triangle in &region.triangles {
+            let [a, b, c] =
+                triangle.map(|(x, y)| mesh.push_vertex(view.world_to_screen(x, y), REACH_COLOR));
+            mesh.push_triangle(a, b, c);
+        }
+    }
+    if !mesh.is_empty() {
+        r.mesh(&mesh);
+    }
+    if let Some(region) = state.reach_regions.last() {
+        for (a, b) in &region.outline {
+            r.line(
+                view.world_to_screen(a.0, a.1),
+                view.world_to_screen(b.0, b.1),
+                1.5,
+                REACH_OUTLINE_COLOR,
+            );
+        }
+    }
+}
+
+pub fn family_color(family: usize) -> Rgba {
+    FAMILY_COLORS[family % FAMILY_COLORS.len()]
+}
+
+fn sweep_readout(sample: &SweepSample) -> String {
+    format!(
+        "θ:{:.0}° Len:{:.2} P{}{}",
+        sample.theta_degree,
+        sample.length,
+        sample.family + 1,
+        transform_suffix(sample.reflect, sample.timeflip)
+    )
+}
+
+/// Length against end heading, colored by the winning family, with a band
+/// of family colors under the plot. The curve breaks wherever the winner
+/// changes, so the jumps stand out. Marks the current end heading and, if
+/// given, the hovered one.
+pub fn draw_heading_sweep(
+    r: &mut impl Renderer,
+    chart: &SweepChart,
+    state: &State,
+    hover_heading: Option<f64>,
+) {
+    let samples = &state.heading_sweep;
+    if samples.is_empty() {
+        return;
+    }
+    r.rectangle(
+        (
+            chart.left + chart.width / 2.0,
+            chart.top + chart.height / 2.0,
+        ),
+        chart.width,
+        chart.height,
+        0.0,
+        SWEEP_BG_COLOR,
+    );
+    let (left, right) = (chart.plot_left(), chart.plot_right());
+    let (top, bottom) = (chart.plot_top(), chart.plot_bottom());
+    r.line((left, bottom), (right, bottom), 1.0, AXIS_COLOR);
+    r.line((left, top), (left, bottom), 1.0, AXIS_COLOR);
+
+    let range = length_range(samples);
+    r.text(
+        &format!("{:.1}", range.1),
+        (chart.left + 4.0, top + 8.0),
+        14.0,
+        AXIS_COLOR,
+    );
+    r.text(
+        &format!("{:.1}", range.0),
+        (chart.left + 4.0, bottom),
+        14.0,
+        AXIS_COLOR,
+    );
+    for theta in [-180.0, -90.0, 0.0, 90.0, 180.0] {
+        let x = chart.x_for(theta);
+        r.line((x, bottom), (x, bottom + 3.0), 1.0, AXIS_COLOR);
+        r.text(
+            &format!("{}", theta),
+            (x - 10.0, bottom + 28.0),
+            14.0,
+            AXIS_COLOR,
+        );
+    }
+
+    for w in samples.windows(2) {
+        let (a, b) = (&w[0], &w[1]);
+        let color = family_color(a.family);
+        let (xa, xb) = (chart.x_for(a.theta_degree), chart.x_for(b.theta_degree));
+        r.rectangle(
+            ((xa + xb) / 2.0, bottom + 10.0),
+            xb - xa + 0.5,
+            8.0,
+            0.0,
+            color,
+        );
+        if a.same_winner(b) {
+            let pa = (xa, chart.y_for(a.length, range));
+            let pb = (xb, chart.y_for(b.length, range));
+            r.line(pa, pb, 2.0, color);
+        }
+    }
+
+    let mut readout = None;
+    if let Some(end) = &state.end_pose {
+        let x = chart.x_for(end.theta_degree);
+        r.line((x, top), (x, bottom + 14.0), 1.0, END_CAR_COLOR);
+        if let Some(s) = nearest_sample(samples, end.theta_degree) {
+            r.circle((x, chart.y_for(s.length, range)), 3.0, END_CAR_COLOR);
+            readout = Some((s, END_CAR_COLOR));
+        }
+    }
+    if let Some(theta) = hover_heading {
+        let x = chart.x_for(theta);
+        r.line(
+            (x, top),
+            (x, bottom + 14.0),
+            1.0,
+            INSPECTOR_COLOR.with_alpha(0.5),
+        );
+        if let Some(s) = nearest_sample(samples, theta) {
+            readout = Some((s, INSPECTOR_COLOR));
+        }
+    }
+    if let Some((sample, color)) = readout {
+        let baseline = chart.top + chart.height - 6.0;
+        r.text(&sweep_readout(sample), (left, baseline), 16.0, color);
+    }
+}
+
+/// Filled rectangle of one obstacle.
+fn obstacle_rectangle(r: &mut impl Renderer, view: &View, o: &Obstacle, color: Rgba) {
+    let center = view.world_to_screen((o.min.0 + o.max.0) / 2.0, (o.min.1 + o.max.1) / 2.0);
+    let width = (o.max.0 - o.min.0) as f32 * view.scale;
+    let height = (o.max.1 - o.min.1) as f32 * view.scale;
+    r.rectangle(center, width, height, 0.0, color);
+}
+
+/// Obstacles, the one being dragged out, and with the C-space view on, the
+/// positions where the car at the slice heading would touch each of them.
+pub fn draw_obstacles(r: &mut impl Renderer, view: &View, state: &State) {
+    if state.cspace_enabled && !state.obstacles.is_empty() {
+        let mut mesh = TriangleMesh::new();
+        for o in &state.obstacles {
+            let polygon = cspace_obstacle(o, state.cspace_heading_degree);
+            let indices: Vec<u32> = polygon
+                .iter()
+                .map(|&(x, y)| mesh.push_vertex(view.world_to_screen(x, y), CSPACE_COLOR))
+                .collect();
+            for pair in indices[1..].windows(2) {
+                mesh.push_triangle(indices[0], pair[0], pair[1]);
+            }
+        }
+        r.mesh(&mesh);
+    }
+    for o in &state.obstacles {
+        obstacle_rectangle(r, view, o, OBSTACLE_COLOR);
+    }
+    if let Some(o) = &state.obstacle_draft {
+        obstacle_rectangle(r, view, o, OBSTACLE_DRAFT_COLOR);
+    }
+}
+
+/// Reference points of the selected path whose heading lies within
+/// [`CSPACE_HEADING_TOLERANCE_DEGREE`] of the slice, red where they collide.
+pub fn draw_cspace_poses(r: &mut impl Renderer, view: &View, state: &State) {
+    let Some(samples) = &state.current_path_samples else {
+        return;
+    };
+    let slice = state.cspace_heading_degree.to_radians();
+    for sample in samples {
+        let diff = utils::normalize_angle_rad(sample.theta - slice).to_degrees();
+        if diff.abs() > CSPACE_HEADING_TOLERANCE_DEGREE {
+            continue;
+        }
+        let color = if pose_collides(&sample.pose(), &state.obstacles) {
+            RED
+        } else {
+            CSPACE_POSE_COLOR
+        };
+        r.circle(view.world_to_screen(sample.x, sample.y), 2.5, color);
+    }
+}
+
+/// The 2x2 transform grid. The cell of the variant on the main canvas is
+/// outlined in the path color, the hovered one in the hover color.
+pub fn draw_transform_explorer(
+    r: &mut impl Renderer,
+    layout: &ExplorerLayout,
+    state: &State,
+    hovered: Option<usize>,
+) {
+    let Some(explorer) = &state.transform_explorer else {
+        return;
+    };
+    let shortest = explorer.shortest();
+    let mut mesh = TriangleMesh::new();
+    for (i, cell) in explorer.cells.iter().enumerate() {
+        let (left, top) = layout.cell_origin(i);
+        let size = layout.cell_size;
+        let center = (left + size / 2.0, top + size / 2.0);
+        r.rectangle(center, size, size, 0.0, SWEEP_BG_COLOR);
+        let shown = match state.display_mode {
+            DisplayMode::SinglePath(_) => {
+                (cell.reflect, cell.timeflip) == (state.reflect_path, state.timeflip_path)
+            }
+            _ => state
+                .shown_variant
+                .is_some_and(|v| (v.reflect, v.timeflip) == (cell.reflect, cell.timeflip)),
+        };
+        let outline = if shown {
+            Some(SELECTED_PATH_COLOR)
+        } else if hovered == Some(i) {
+            Some(HOVERED_PATH_COLOR)
+        } else {
+            None
+        };
+        if let Some(color) = outline {
+            let corners = [
+                (left, top),
+                (left + size, top),
+                (left + size, top + size),
+                (left, top + size),
+            ];
+            for k in 0..4 {
+                r.line(corners[k], corners[(k + 1) % 4], 2.0, color);
+            }
+        }
+
+        let to_screen = |p: (f64, f64)| layout.to_screen(explorer, i, p);
+        for (pose, color) in [
+            (&explorer.start, START_CAR_COLOR),
+            (&explorer.end, END_CAR_COLOR),
+        ] {
+            let p = to_screen((pose.x, pose.y));
+            let (sin, cos) = pose.theta_degree.to_radians().sin_cos();
+            r.line(
+                p,
+                (p.0 + 10.0 * cos as f32, p.1 - 10.0 * sin as f32),
+                2.0,
+                color,
+            );
+            r.circle(p, 3.0, color);
+        }
+        let path_color = match cell.feasibility {
+            Feasibility::Feasible => family_color(explorer.family),
+            Feasibility::MissesGoal => MISSES_GOAL_COLOR.with_alpha(0.6),
+            Feasibility::Infeasible => INFEASIBLE_PATH_COLOR,
+        };
+        let points: Vec<ScreenPoint> = cell.samples.iter().map(|s| to_screen((s.x, s.y))).collect();
+        stroke_polyline(&mut mesh, &points, &vec![path_color; points.len()], 2.0);
+
+        let marker = match cell.feasibility {
+            Feasibility::Feasible => FEASIBLE_COLOR,
+            Feasibility::MissesGoal => MISSES_GOAL_COLOR,
+            Feasibility::Infeasible => RED,
+        };
+        r.circle((left + size - 10.0, top + 10.0), 4.0, marker);
+        let length = match (cell.feasibility, cell.length()) {
+            (Feasibility::Infeasible, _) | (_, None) => cell.feasibility.name().to_string(),
+            (Feasibility::MissesGoal, Some(l)) => format!("{:.2} {}", l, cell.feasibility.name()),
+            (Feasibility::Feasible, Some(l)) => {
+                format!("{:.2}{}", l, if shortest == Some(i) { " *" } else { "" })
+            }
+        };
+        r.text(
+            &format!("{}: {}", cell.label(), length),
+            layout.label_origin(i),
+            14.0,
+            AXIS_COLOR.with_alpha(1.0),
+        );
+    }
+    // Paths stay inside their own cell's plot, so one mesh draws them all.
+    if !mesh.is_empty() {
+        r.mesh(&mesh);
+    }
+}
+
+/// Gallery thumbnails over the canvas. Infeasible ones are grayed out, the
+/// optimum is outlined in the path color and the hovered one in the hover
+/// color.
+pub fn draw_gallery(
+    r: &mut impl Renderer,
+    layout: &GalleryLayout,
+    state: &State,
+    hovered: Option<usize>,
+) {
+    let Some(gallery) = &state.gallery else {
+        return;
+    };
+    let size = layout.thumb_size;
+    let mut mesh = TriangleMesh::new();
+    for (i, thumb) in gallery.thumbs.iter().enumerate() {
+        let (left, top) = layout.thumb_origin(i);
+        let cell = &thumb.cell;
+        let feasible = cell.feasibility == Feasibility::Feasible;
+        r.rectangle(
+            (left + size / 2.0, top + size / 2.0),
+            size,
+            size,
+            0.0,
+            SWEEP_BG_COLOR.with_alpha(1.0),
+        );
+        let outline = if gallery.optimal == Some(i) {
+            Some(SELECTED_PATH_COLOR)
+        } else if hovered == Some(i) {
+            Some(HOVERED_PATH_COLOR)
+        } else {
+            None
+        };
+        if let Some(color) = outline {
+            let corners = [
+                (left, top),
+                (left + size, top),
+                (left + size, top + size),
+                (left, top + size),
+            ];
+            for k in 0..4 {
+                r.line(corners[k], corners[(k + 1) % 4], 2.0, color);
+            }
+        }
+
+        let to_screen = |p: (f64, f64)| layout.to_screen(gallery, i, p);
+        let pose_alpha = if feasible { 1.0 } else { 0.35 };
+        for (pose, color) in [
+            (&gallery.start, START_CAR_COLOR),
+            (&gallery.end, END_CAR_COLOR),
+        ] {
+            r.circle(
+                to_screen((pose.x, pose.y)),
+                2.5,
+                color.with_alpha(pose_alpha),
+            );
+        }
+        let path_color = if feasible {
+            family_color(thumb.family)
+        } else {
+            INFEASIBLE_PATH_COLOR
+        };
+        let points: Vec<ScreenPoint> = cell.samples.iter().map(|s| to_screen((s.x, s.y))).collect();
+        stroke_polyline(&mut mesh, &points, &vec![path_color; points.len()], 1.5);
+
+        let text_color = if feasible {
+            AXIS_COLOR.with_alpha(1.0)
+        } else {
+            INFEASIBLE_PATH_COLOR
+        };
+        let [title, detail] = layout.label_origins(i);
+        r.text(
+            &format!("P{} {}", thumb.family + 1, cell.label()),
+            title,
+            12.0,
+            text_color,
+        );
+        let detail_text = match cell.length() {
+            Some(length) if feasible => format!("{} {:.2}", path_shape(&cell.path), length),
+            _ => cell.feasibility.name().to_string(),
+        };
+        r.text(&detail_text, detail, 12.0, text_color);
+    }
+    // Labels sit below each thumbnail's plot, clear of the paths.
+    if !mesh.is_empty() {
+        r.mesh(&mesh);
+    }
+}
+
+/// The frame the traced path function works in: its axes at the start
+/// pose, mirrored by the transforms, and the line of length `rho` between
+/// the start's left circle and the goal circle the family measures to.
+pub fn draw_trace(r: &mut impl Renderer, view: &View, state: &State) {
+    let (Some(trace), Some(start)) = (&state.family_trace, &state.start_pose) else {
+        return;
+    };
+    let to_screen = |p: (f64, f64)| {
+        let (x, y) = trace.to_world(start, p);
+        view.world_to_screen(x, y)
+    };
+    let mut mesh = TriangleMesh::new();
+    let origin = to_screen((0.0, 0.0));
+    for (tip, color) in [
+        ((TRACE_AXIS_LENGTH, 0.0), TRACE_X_AXIS_COLOR),
+        ((0.0, TRACE_AXIS_LENGTH), TRACE_Y_AXIS_COLOR),
+    ] {
+        let tip = to_screen(tip);
+        r.line(origin, tip, 2.0, color);
+        let length = (tip.0 - origin.0).hypot(tip.1 - origin.1);
+        let dir = ((tip.0 - origin.0) / length, (tip.1 - origin.1) / length);
+        arrowhead(&mut mesh, tip, dir, ARROW_SIZE, color);
+    }
+    r.mesh(&mesh);
+
+    let start_circle = to_screen((0.0, TURNING_RADIUS));
+    let goal_circle = to_screen(trace.goal_circle_center);
+    let radius = TURNING_RADIUS as f32 * view.scale;
+    r.circle_lines(start_circle, radius, 1.0, TRACE_COLOR.with_alpha(0.4));
+    r.circle_lines(goal_circle, radius, 1.0, TRACE_COLOR.with_alpha(0.4));
+    let rho_color = if trace.path.is_empty() {
+        JACKKNIFE_COLOR
+    } else {
+        TRACE_COLOR
+    };
+    r.line(start_circle, goal_circle, 1.5, rho_color);
+    r.circle(start_circle, 3.0, rho_color);
+    r.circle(goal_circle, 3.0, rho_color);
+    let middle = (
+        (start_circle.0 + goal_circle.0) / 2.0,
+        (start_circle.1 + goal_circle.1) / 2.0,
+    );
+    r.text(
+        &format!("ρ={:.2}", trace.rho),
+        (middle.0 + 6.0, middle.1 - 6.0),
+        16.0,
+        rho_color,
+    );
+}
+
+/// Draws the whole canvas for `state`, background included.
+pub fn draw_scene(r: &mut impl Renderer, view: &View, state: &State) {
+    r.clear(BG_COLOR);
+    if state.grid_visible {
+        draw_grid(r, view, state.snap.grid_spacing);
+    }
+    draw_obstacles(r, view, state);
+    draw_reach(r, view, state);
+    draw_construction(r, view, state);
+    match state.display_mode {
+        DisplayMode::SinglePath(_)
+        | DisplayMode::ShortestPath
+        | DisplayMode::Prm
+        | DisplayMode::Lattice => {
+            if let (Some(path), Some(start)) = (&state.current_raw_path, &state.start_pose) {
+                draw_path_turning_circles(r, view, path, start);
+            }
+        }
+        DisplayMode::AllPaths | DisplayMode::LatticePrimitives | DisplayMode::Gallery => {}
+    }
+    if state.display_mode == DisplayMode::Prm
+        && let Some(roadmap) = &state.roadmap
+    {
+        for node in &roadmap.nodes {
+            r.circle(
+                view.world_to_screen(node.x, node.y),
+                2.0,
+                ROADMAP_NODE_COLOR,
+            );
+        }
+    }
+    if let (Some(set), Some(solution)) = (&state.lattice_primitives, &state.lattice_solution) {
+        for lattice_state in &solution.states {
+            let p = set.pose(lattice_state);
+            r.circle(view.world_to_screen(p.x, p.y), 3.0, LATTICE_STATE_COLOR);
+        }
+    }
+    draw_paths(r, view, state);
+    draw_trace(r, view, state);
+    if let (Some(t), Some(end)) = (&state.transition, &state.end_pose)
+        && t.is_near_switch(state.stability_settings.gap_threshold)
+    {
+        let center = view.world_to_screen(end.x, end.y);
+        r.circle_lines(center, 18.0, 2.0, JACKKNIFE_COLOR);
+    }
+    if state.cspace_enabled {
+        draw_cspace_poses(r, view, state);
+    }
+    if let Some(samples) = &state.trailer_samples {
+        draw_trailer(r, view, samples, &state.trailer_model);
+    }
+    if let Some(last) = state.custom_path_samples.as_ref().and_then(|s| s.last()) {
+        draw_pose_elements(r, view, &last.pose(), CUSTOM_PATH_COLOR.with_alpha(0.35));
+        if let Some(end) = &state.end_pose {
+            let a = view.world_to_screen(last.x, last.y);
+            let b = view.world_to_screen(end.x, end.y);
+            r.line(a, b, 1.0, CUSTOM_PATH_COLOR);
+        }
+    }
+    if let Some(sample) = &state.inspected_sample {
+        draw_pose_elements(r, view, &sample.pose(), GHOST_CAR_COLOR);
+        r.circle(
+            view.world_to_screen(sample.x, sample.y),
+            3.0,
+            INSPECTOR_COLOR,
+        );
+    }
+    if let Some(ref pose) = state.start_pose {
+        draw_pose_elements(r, view, pose, START_CAR_COLOR);
+    }
+    if let Some(ref pose) = state.end_pose {
+        draw_pose_elements(r, view, pose, END_CAR_COLOR);
+    }
+}
@@ -0,0 +1,192 @@
+//! Freehand sketches compared against Reeds-Shepp candidates.
+//!
+//! A sketch is the polyline the pointer traced. Its first and last tangents
+//! give the start and end poses, and every candidate between them is scored
+//! by its discrete Fréchet or Hausdorff distance to the sketch. Both curves
+//! are compared as point sequences spaced [`PATH_SAMPLE_STEP`] apart.
+
+use reeds_shepp_lib::Pose;
+
+use crate::path::{PATH_SAMPLE_STEP, PathSample};
+
+// --- Constants ---
+/// Pointer positions closer than this to the previous one are dropped.
+pub const MIN_SKETCH_POINT_GAP: f64 = 0.02;
+/// Sketches shorter than this are too short to read a heading from.
+pub const MIN_SKETCH_LENGTH: f64 = 0.5;
+/// Arc length at each end of the sketch whose chord gives the heading.
+const TANGENT_LENGTH: f64 = 0.4;
+
+// --- Enums ---
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SketchMetric {
+    /// Respects the order of travel, like walking both curves on a leash.
+    Frechet,
+    /// Only compares the point sets.
+    Hausdorff,
+}
+
+impl SketchMetric {
+    pub fn name(self) -> &'static str {
+        match self {
+            SketchMetric::Frechet => "Fréchet",
+            SketchMetric::Hausdorff => "Hausdorff",
+        }
+    }
+
+    pub fn distance(self, a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+        match self {
+            SketchMetric::Frechet => frechet_distance(a, b),
+            SketchMetric::Hausdorff => hausdorff_distance(a, b),
+        }
+    }
+}
+
+/// The candidate closest to the sketch, as an index into the candidate list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SketchFit {
+    pub candidate: usize,
+    pub metric: SketchMetric,
+    pub distance: f64,
+}
+
+fn dist(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+pub fn polyline_length(points: &[(f64, f64)]) -> f64 {
+    points.windows(2).map(|w| dist(w[0], w[1])).sum()
+}
+
+/// Points spaced `step` apart along the polyline, always keeping both ends.
+pub fn resample_polyline(points: &[(f64, f64)], step: f64) -> Vec<(f64, f64)> {
+    let Some(&first) = points.first() else {
+        return Vec::new();
+    };
+    let mut resampled = vec![first];
+    // Arc length left until the next output point.
+    let mut until_next = step;
+    for w in points.windows(2) {
+        let len = dist(w[0], w[1]);
+        let mut covered = 0.0;
+        while len - covered >= until_next {
+            covered += until_next;
+            let t = covered / len;
+            resampled.push((
+                w[0].0 + (w[1].0 - w[0].0) * t,
+                w[0].1 + (w[1].1 - w[0].1) * t,
+            ));
+            until_next = step;
+        }
+        until_next -= len - covered;
+    }
+    let last = *points.last().unwrap();
+    if resampled.last().is_some_and(|&p| dist(p, last) > 1e-9) {
+        resampled.push(last);
+    }
+    resampled
+}
+
+/// Heading from the first point of `points` to the point `TANGENT_LENGTH`
+/// further along, in degrees.
+fn leading_heading(points: &[(f64, f64)]) -> f64 {
+    let from = points[0];
+    let mut to = *points.last().unwrap();
+    let mut walked = 0.0;
+    for w in points.windows(2) {
+        walked += dist(w[0], w[1]);
+        if walked >= TANGENT_LENGTH {
+            to = w[1];
+            break;
+        }
+    }
+    (to.1 - from.1).atan2(to.0 - from.0).to_degrees()
+}
+
+/// Start and end poses read off the sketch tangents, or `None` for a sketch
+/// shorter than [`MIN_SKETCH_LENGTH`].
+pub fn sketch_poses(points: &[(f64, f64)]) -> Option<(Pose, Pose)> {
+    if polyline_length(points) < MIN_SKETCH_LENGTH {
+        return None;
+    }
+    let (first, last) = (points[0], *points.last().unwrap());
+    let reversed: Vec<(f64, f64)> = points.iter().rev().copied().collect();
+    // The end tangent points back along the sketch, so turn it around.
+    let end_heading = leading_heading(&reversed) + 180.0;
+    let start = Pose {
+        x: first.0,
+        y: first.1,
+        theta_degree: leading_heading(points),
+    };
+    let end = Pose {
+        x: last.0,
+        y: last.1,
+        theta_degree: if end_heading > 180.0 {
+            end_heading - 360.0
+        } else {
+            end_heading
+        },
+    };
+    Some((start, end))
+}
+
+/// Largest distance from a point of either sequence to the nearest point of
+/// the other. Infinite if either is empty.
+pub fn hausdorff_distance(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    let directed = |from: &[(f64, f64)], to: &[(f64, f64)]| {
+        from.iter()
+            .map(|&p| to.iter().map(|&q| dist(p, q)).fold(f64::INFINITY, f64::min))
+            .fold(0.0, f64::max)
+    };
+    if a.is_empty() || b.is_empty() {
+        return f64::INFINITY;
+    }
+    directed(a, b).max(directed(b, a))
+}
+
+/// Discrete Fréchet distance: the shortest leash that lets two walkers
+/// traverse the sequences in order, each only ever moving forward.
+/// Infinite if either is empty.
+pub fn frechet_distance(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return f64::INFINITY;
+    }
+    let mut previous = vec![0.0f64; b.len()];
+    let mut current = vec![0.0; b.len()];
+    for (i, &p) in a.iter().enumerate() {
+        for (j, &q) in b.iter().enumerate() {
+            let d = dist(p, q);
+            current[j] = match (i, j) {
+                (0, 0) => d,
+                (0, _) => current[j - 1].max(d),
+                (_, 0) => previous[0].max(d),
+                _ => previous[j].min(previous[j - 1]).min(current[j - 1]).max(d),
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len() - 1]
+}
+
+/// Scores every candidate polyline against the sketch and returns the
+/// closest one.
+pub fn fit_sketch(
+    sketch: &[(f64, f64)],
+    candidates: &[Vec<PathSample>],
+    metric: SketchMetric,
+) -> Option<SketchFit> {
+    let sketch = resample_polyline(sketch, PATH_SAMPLE_STEP);
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, samples)| !samples.is_empty())
+        .map(|(candidate, samples)| {
+            let points: Vec<(f64, f64)> = samples.iter().map(|s| (s.x, s.y)).collect();
+            SketchFit {
+                candidate,
+                metric,
+                distance: metric.distance(&sketch, &points),
+            }
+        })
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
@@ -0,0 +1,126 @@
+//! How close the optimal path is to switching to another maneuver, and an
+//! optional hysteresis that keeps the shown variant while dragging.
+//!
+//! Variants are identified by family and transform. The runner-up is the
+//! shortest candidate that drives a different maneuver, so variants that
+//! degenerate into the same path (for example two turns of zero length around
+//! a straight) do not count as a near tie.
+
+use reeds_shepp_lib::Path;
+
+use crate::candidates::{Candidate, shortest_candidate};
+
+// --- Constants ---
+pub const DEFAULT_GAP_THRESHOLD: f64 = 0.1;
+pub const MAX_GAP_THRESHOLD: f64 = 1.0;
+pub const DEFAULT_HYSTERESIS_MARGIN: f64 = 0.1;
+pub const MAX_HYSTERESIS_MARGIN: f64 = 1.0;
+/// Elements shorter than this are ignored when comparing maneuvers.
+const MIN_ELEMENT_PARAM: f64 = 1e-9;
+/// Element parameters closer than this count as equal.
+const PARAM_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StabilitySettings {
+    pub show_runner_up: bool,
+    /// Gaps below this are flagged as about to switch.
+    pub gap_threshold: f64,
+    pub hysteresis: bool,
+    /// How much shorter another variant must be to replace the shown one.
+    pub margin: f64,
+}
+
+impl Default for StabilitySettings {
+    fn default() -> Self {
+        StabilitySettings {
+            show_runner_up: false,
+            gap_threshold: DEFAULT_GAP_THRESHOLD,
+            hysteresis: false,
+            margin: DEFAULT_HYSTERESIS_MARGIN,
+        }
+    }
+}
+
+/// A `PATH_FNS` entry under one reflect/timeflip transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    pub family: usize,
+    pub reflect: bool,
+    pub timeflip: bool,
+}
+
+impl Variant {
+    pub fn of(candidate: &Candidate) -> Self {
+        Variant {
+            family: candidate.family,
+            reflect: candidate.reflect,
+            timeflip: candidate.timeflip,
+        }
+    }
+}
+
+/// The ranking at one pair of poses and what is shown for it.
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub optimal: Candidate,
+    pub runner_up: Option<Candidate>,
+    /// The optimum, or the previous variant while hysteresis holds it.
+    pub shown: Candidate,
+}
+
+impl Transition {
+    /// How much longer the runner-up is than the optimum.
+    pub fn gap(&self) -> Option<f64> {
+        self.runner_up
+            .as_ref()
+            .map(|r| r.length - self.optimal.length)
+    }
+
+    pub fn is_near_switch(&self, threshold: f64) -> bool {
+        self.gap().is_some_and(|gap| gap < threshold)
+    }
+
+    pub fn is_held(&self) -> bool {
+        Variant::of(&self.shown) != Variant::of(&self.optimal)
+    }
+}
+
+/// Whether two paths drive the same elements, ignoring empty ones.
+pub fn same_maneuver(a: &Path, b: &Path) -> bool {
+    let mut a = a.iter().filter(|e| e.param > MIN_ELEMENT_PARAM);
+    let mut b = b.iter().filter(|e| e.param > MIN_ELEMENT_PARAM);
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return true,
+            (Some(x), Some(y))
+                if x.steering == y.steering
+                    && x.gear == y.gear
+                    && (x.param - y.param).abs() < PARAM_TOLERANCE => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Ranks `candidates` and picks the one to show. With hysteresis on, the
+/// `previous` variant stays shown unless it is gone or the optimum beats it
+/// by more than the margin.
+pub fn rank(
+    candidates: &[Candidate],
+    previous: Option<Variant>,
+    settings: &StabilitySettings,
+) -> Option<Transition> {
+    let optimal = shortest_candidate(candidates)?;
+    let runner_up = candidates
+        .iter()
+        .filter(|c| !same_maneuver(&c.path, &optimal.path))
+        .min_by(|a, b| a.length.total_cmp(&b.length));
+    let held = previous
+        .filter(|_| settings.hysteresis)
+        .and_then(|v| candidates.iter().find(|c| Variant::of(c) == v))
+        .filter(|c| c.length - optimal.length <= settings.margin);
+    Some(Transition {
+        optimal: optimal.clone(),
+        runner_up: runner_up.cloned(),
+        shown: held.unwrap_or(optimal).clone(),
+    })
+}
@@ -0,0 +1,1171 @@
+use reeds_shepp_lib::{PATH_FNS, Path, Pose, get_optimal_path, utils};
+
+use crate::candidates::{self, Candidate};
+use crate::car;
+use crate::cc::{self, CcComparison, DEFAULT_MAX_STEERING_RATE};
+use crate::construction::Construction;
+use crate::gallery::Gallery;
+use crate::grid::SnapSettings;
+use crate::input::{InputEvent, InputFrame, PointerInput, Shortcut};
+use crate::lattice::{LatticeSettings, LatticeSolution, LatticeState, PrimitiveSet};
+use crate::obstacle::{MIN_OBSTACLE_SIZE, Obstacle};
+use crate::path::{
+    PATH_SAMPLE_STEP, PathSample, distance_to_polyline, parse_path_word, path_arc_length,
+    path_end_pose, project_onto_polyline, sample_path, sample_path_at,
+};
+use crate::prm::{PrmSettings, PrmSolution, Roadmap, sampling_bounds};
+use crate::reach::{
+    MAX_REACH_BUDGET, REACH_HEADINGS, REACH_RESOLUTION, ReachField, ReachRegion, ReachSettings,
+    reach_axis,
+};
+use crate::sketch::{self, SketchFit, SketchMetric, resample_polyline};
+use crate::stability::{self, StabilitySettings, Transition, Variant};
+use crate::sweep::{HEADING_SWEEP_STEPS, SweepSample, sweep_end_heading};
+use crate::trace::FamilyTrace;
+use crate::trailer::{self, TrailerModel, TrailerSample, TrailerSummary};
+use crate::transforms::TransformExplorer;
+
+// --- Constants ---
+pub const MIN_ANGLE_DRAG_DISTANCE: f64 = 0.1;
+/// How close (world units) the pointer has to be to a candidate to hover it.
+pub const HOVER_DISTANCE: f64 = 0.25;
+/// Sample spacing of the faint roadmap edges.
+pub const PRM_DRAW_STEP: f64 = 0.1;
+
+// --- Enums ---
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum AppState {
+    PlacingStart,
+    DefiningStartAngle,
+    PlacingEnd,
+    DefiningEndAngle,
+    DisplayingPaths,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct InitialDragState {
+    pub start_pos: (f64, f64),
+    pub current_pos: (f64, f64),
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ModifyDragTarget {
+    StartBody,
+    StartAngle,
+    EndBody,
+    EndAngle,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayMode {
+    ShortestPath,
+    AllPaths,
+    SinglePath(usize),
+    /// Route through the probabilistic roadmap around the obstacles.
+    Prm,
+    /// A* over the state lattice around the obstacles.
+    Lattice,
+    /// The lattice primitives leaving the start pose's lattice state.
+    LatticePrimitives,
+    /// Thumbnails of every family under every transform.
+    Gallery,
+}
+
+/// Combo box entries listed before the single paths, in order.
+const LEADING_MODES: [(DisplayMode, &str); 2] = [
+    (DisplayMode::ShortestPath, "Shortest Path"),
+    (DisplayMode::AllPaths, "All Paths"),
+];
+/// Combo box entries listed after the single paths, in order.
+const TRAILING_MODES: [(DisplayMode, &str); 4] = [
+    (DisplayMode::Prm, "PRM"),
+    (DisplayMode::Lattice, "Lattice"),
+    (DisplayMode::LatticePrimitives, "Primitives"),
+    (DisplayMode::Gallery, "Gallery"),
+];
+
+impl DisplayMode {
+    /// Labels of the mode combo box: the leading modes, one `Path n` per
+    /// `PATH_FNS` entry, then the trailing modes.
+    pub fn combo_labels() -> Vec<String> {
+        let leading = LEADING_MODES.iter().map(|(_, label)| label.to_string());
+        let paths = (1..=PATH_FNS.len()).map(|i| format!("Path {}", i));
+        let trailing = TRAILING_MODES.iter().map(|(_, label)| label.to_string());
+        leading.chain(paths).chain(trailing).collect()
+    }
+
+    /// The mode at position `index` in the mode combo box.
+    pub fn from_combo_index(index: usize) -> Option<DisplayMode> {
+        let paths_start = LEADING_MODES.len();
+        let trailing_start = paths_start + PATH_FNS.len();
+        if index < paths_start {
+            Some(LEADING_MODES[index].0.clone())
+        } else if index < trailing_start {
+            Some(DisplayMode::SinglePath(index - paths_start))
+        } else {
+            TRAILING_MODES
+                .get(index - trailing_start)
+                .map(|(mode, _)| mode.clone())
+        }
+    }
+
+    /// Position in the mode combo box, the inverse of
+    /// [`DisplayMode::from_combo_index`].
+    pub fn combo_index(&self) -> usize {
+        let paths_start = LEADING_MODES.len();
+        let trailing_start = paths_start + PATH_FNS.len();
+        if let DisplayMode::SinglePath(family) = self {
+            return paths_start + family;
+        }
+        if let Some(i) = LEADING_MODES.iter().position(|(mode, _)| mode == self) {
+            return i;
+        }
+        let i = TRAILING_MODES
+            .iter()
+            .position(|(mode, _)| mode == self)
+            .expect("every mode is listed in the combo box");
+        trailing_start + i
+    }
+}
+
+/// How the selected path is colored.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PathColoring {
+    Gear,
+    Steering,
+}
+
+// --- State Struct ---
+pub struct State {
+    pub app_state: AppState,
+    pub start_pose: Option<Pose>,
+    pub end_pose: Option<Pose>,
+    pub drag_state_initial: Option<InitialDragState>,
+    pub dragging_modify: Option<ModifyDragTarget>,
+    pub display_mode: DisplayMode,
+    pub reflect_path: bool,
+    pub timeflip_path: bool,
+    pub current_path_samples: Option<Vec<PathSample>>,
+    pub current_raw_path: Option<Path>,
+    pub all_candidates: Vec<Candidate>,
+    pub all_paths_samples: Vec<Vec<PathSample>>,
+    /// Index into `all_candidates` under the pointer in `AllPaths` mode.
+    pub hovered_candidate: Option<usize>,
+    /// Arc length of the inspected point on the selected path, following the
+    /// pointer unless pinned by a click.
+    pub inspected_s: Option<f64>,
+    pub inspector_pinned: bool,
+    /// Exact pose at `inspected_s`, kept in sync when the path changes.
+    pub inspected_sample: Option<PathSample>,
+    pub combo_box_selected_index: usize,
+    pub cc_enabled: bool,
+    pub max_steering_rate: f64,
+    pub current_cc_samples: Option<Vec<PathSample>>,
+    pub cc_comparison: Option<CcComparison>,
+    pub trailer_enabled: bool,
+    pub trailer_model: TrailerModel,
+    pub trailer_samples: Option<Vec<TrailerSample>>,
+    pub trailer_summary: Option<TrailerSummary>,
+    pub grid_visible: bool,
+    pub snap: SnapSettings,
+    pub path_coloring: PathColoring,
+    /// Hand-written segment sequence, see [`parse_path_word`].
+    pub custom_word: String,
+    pub custom_word_error: Option<String>,
+    /// Whether the word field has keyboard focus, so keys type into it
+    /// instead of triggering shortcuts.
+    pub word_focused: bool,
+    pub custom_path_samples: Option<Vec<PathSample>>,
+    /// Where the custom path ends, expressed in the frame of `end_pose`.
+    pub custom_end_offset: Option<Pose>,
+    pub sketch_enabled: bool,
+    pub sketch_metric: SketchMetric,
+    /// World positions traced by the pointer while sketching.
+    pub sketch_points: Vec<(f64, f64)>,
+    pub sketch_drawing: bool,
+    /// Best candidate for the finished sketch, kept even after the display
+    /// mode changes.
+    pub sketch_fit: Option<SketchFit>,
+    pub sketch_fit_candidate: Option<Candidate>,
+    /// Distance between the sketch and the currently selected path.
+    pub sketch_distance: Option<f64>,
+    pub obstacles: Vec<Obstacle>,
+    pub obstacle_editing: bool,
+    /// Rectangle being dragged out while editing obstacles.
+    pub obstacle_draft: Option<Obstacle>,
+    pub obstacle_draft_corner: Option<(f64, f64)>,
+    pub cspace_enabled: bool,
+    /// Heading of the displayed configuration-space slice.
+    pub cspace_heading_degree: f64,
+    pub prm_settings: PrmSettings,
+    /// Built on the first `Prm` query and kept until the obstacles or the
+    /// settings change, so dragging the poses only re-queries it.
+    pub roadmap: Option<Roadmap>,
+    /// Edge polylines of `roadmap`, in the same order as its edges.
+    pub roadmap_samples: Vec<Vec<PathSample>>,
+    pub prm_solution: Option<PrmSolution>,
+    pub lattice_settings: LatticeSettings,
+    /// Generated on first use and kept until the settings change.
+    pub lattice_primitives: Option<PrimitiveSet>,
+    pub lattice_solution: Option<LatticeSolution>,
+    /// Primitives drawn in `LatticePrimitives` mode, from `lattice_anchor`.
+    pub lattice_primitive_samples: Vec<Vec<PathSample>>,
+    pub lattice_anchor: Option<LatticeState>,
+    pub heading_sweep_enabled: bool,
+    /// Winner per end heading, recalculated with the paths while enabled.
+    pub heading_sweep: Vec<SweepSample>,
+    pub reach_enabled: bool,
+    pub reach_settings: ReachSettings,
+    /// Field for the last relative end heading, recomputed when it changes.
+    pub reach_heading_field: Option<ReachField>,
+    /// Field over all headings, computed on first use and then kept.
+    pub reach_union_field: Option<ReachField>,
+    /// Nested regions up to the budget, smallest first.
+    pub reach_regions: Vec<ReachRegion>,
+    pub stability_settings: StabilitySettings,
+    /// Variant shown in `ShortestPath` mode, which hysteresis holds on to.
+    pub shown_variant: Option<Variant>,
+    pub transition: Option<Transition>,
+    pub runner_up_samples: Option<Vec<PathSample>>,
+    pub transform_explorer_enabled: bool,
+    /// Transforms of the single path's family, or of the shown family in
+    /// `ShortestPath` mode.
+    pub transform_explorer: Option<TransformExplorer>,
+    pub gallery: Option<Gallery>,
+    pub trace_enabled: bool,
+    /// Intermediate values of the single path's family while enabled.
+    pub family_trace: Option<FamilyTrace>,
+    pub construction_enabled: bool,
+    pub construction: Option<Construction>,
+    /// Indices into the construction's tangents driven by the selected path.
+    pub construction_tangents_used: Vec<usize>,
+    /// Indices into the construction's middle circles the selected path
+    /// turns on.
+    pub construction_middle_used: Vec<usize>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl State {
+    pub fn new() -> Self {
+        State {
+            app_state: AppState::PlacingStart,
+            start_pose: None,
+            end_pose: None,
+            drag_state_initial: None,
+            dragging_modify: None,
+            display_mode: DisplayMode::ShortestPath,
+            reflect_path: false,
+            timeflip_path: false,
+            current_path_samples: None,
+            current_raw_path: None,
+            all_candidates: Vec::new(),
+            all_paths_samples: Vec::new(),
+            hovered_candidate: None,
+            inspected_s: None,
+            inspector_pinned: false,
+            inspected_sample: None,
+            combo_box_selected_index: 0,
+            cc_enabled: false,
+            max_steering_rate: DEFAULT_MAX_STEERING_RATE,
+            current_cc_samples: None,
+            cc_comparison: None,
+            trailer_enabled: false,
+            trailer_model: TrailerModel::default(),
+            trailer_samples: None,
+            trailer_summary: None,
+            grid_visible: true,
+            snap: SnapSettings::default(),
+            path_coloring: PathColoring::Gear,
+            custom_word: String::new(),
+            custom_word_error: None,
+            word_focused: false,
+            custom_path_samples: None,
+            custom_end_offset: None,
+            sketch_enabled: false,
+            sketch_metric: SketchMetric::Frechet,
+            sketch_points: Vec::new(),
+            sketch_drawing: false,
+            sketch_fit: None,
+            sketch_fit_candidate: None,
+            sketch_distance: None,
+            obstacles: Vec::new(),
+            obstacle_editing: false,
+            obstacle_draft: None,
+            obstacle_draft_corner: None,
+            cspace_enabled: false,
+            cspace_heading_degree: 0.0,
+            prm_settings: PrmSettings::default(),
+            roadmap: None,
+            roadmap_samples: Vec::new(),
+            prm_solution: None,
+            lattice_settings: LatticeSettings::default(),
+            lattice_primitives: None,
+            lattice_solution: None,
+            lattice_primitive_samples: Vec::new(),
+            lattice_anchor: None,
+            heading_sweep_enabled: false,
+            heading_sweep: Vec::new(),
+            reach_enabled: false,
+            reach_settings: ReachSettings::default(),
+            reach_heading_field: None,
+            reach_union_field: None,
+            reach_regions: Vec::new(),
+            stability_settings: StabilitySettings::default(),
+            shown_variant: None,
+            transition: None,
+            runner_up_samples: None,
+            transform_explorer_enabled: false,
+            transform_explorer: None,
+            gallery: None,
+            trace_enabled: false,
+            family_trace: None,
+            construction_enabled: false,
+            construction: None,
+            construction_tangents_used: Vec::new(),
+            construction_middle_used: Vec::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        println!("State reset.");
+        let default_mode = DisplayMode::ShortestPath;
+        let default_combo_index = 0;
+        *self = State::new();
+        self.display_mode = default_mode;
+        self.combo_box_selected_index = default_combo_index;
+    }
+
+    /// Whether shortcut keys act, rather than typing into the word field.
+    pub fn accepts_shortcuts(&self) -> bool {
+        !self.word_focused
+    }
+
+    pub fn set_display_mode_from_index(&mut self, index: usize) {
+        self.combo_box_selected_index = index;
+        self.display_mode =
+            DisplayMode::from_combo_index(index).unwrap_or(DisplayMode::ShortestPath); // Fallback
+        if !matches!(self.display_mode, DisplayMode::SinglePath(_)) {
+            self.reflect_path = false;
+            self.timeflip_path = false;
+        }
+    }
+
+    pub fn calculate_initial_drag_angle(&self) -> Option<f64> {
+        if let Some(drag) = &self.drag_state_initial {
+            let dx = drag.current_pos.0 - drag.start_pos.0;
+            let dy = drag.current_pos.1 - drag.start_pos.1;
+            if dx.hypot(dy) > MIN_ANGLE_DRAG_DISTANCE {
+                return Some(self.snap.heading(dy.atan2(dx).to_degrees()));
+            }
+        }
+        None
+    }
+
+    /// Picks what a press at `world_pos` grabs; headlights win over bodies.
+    pub fn hit_test(&self, world_pos: (f64, f64)) -> Option<ModifyDragTarget> {
+        if let Some(p) = &self.start_pose
+            && car::check_headlight_hit(world_pos, p)
+        {
+            return Some(ModifyDragTarget::StartAngle);
+        }
+        if let Some(p) = &self.end_pose
+            && car::check_headlight_hit(world_pos, p)
+        {
+            return Some(ModifyDragTarget::EndAngle);
+        }
+        if let Some(p) = &self.start_pose
+            && car::check_body_hit(world_pos, p)
+        {
+            return Some(ModifyDragTarget::StartBody);
+        }
+        if let Some(p) = &self.end_pose
+            && car::check_body_hit(world_pos, p)
+        {
+            return Some(ModifyDragTarget::EndBody);
+        }
+        None
+    }
+
+    /// Candidate whose polyline passes closest to `world_pos`, if within
+    /// [`HOVER_DISTANCE`].
+    pub fn nearest_candidate(&self, world_pos: (f64, f64)) -> Option<usize> {
+        self.all_paths_samples
+            .iter()
+            .map(|samples| distance_to_polyline(samples, world_pos))
+            .enumerate()
+            .filter(|(_, d)| *d <= HOVER_DISTANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Arc length of the point on the selected path closest to `world_pos`,
+    /// if within [`HOVER_DISTANCE`].
+    pub fn project_onto_selected_path(&self, world_pos: (f64, f64)) -> Option<f64> {
+        let samples = self.current_path_samples.as_ref()?;
+        project_onto_polyline(samples, world_pos)
+            .filter(|(_, d)| *d <= HOVER_DISTANCE)
+            .map(|(s, _)| s)
+    }
+
+    /// Re-samples the inspected point on the current path. A pinned point
+    /// keeps its arc length, clamped to the new path, while poses are dragged.
+    pub fn update_inspection(&mut self) {
+        self.inspected_sample = match (self.inspected_s, &self.current_raw_path, &self.start_pose) {
+            (Some(s), Some(path), Some(start)) => {
+                let s = s.min(path_arc_length(path));
+                sample_path_at(start, path, &[s]).pop()
+            }
+            _ => None,
+        };
+        if self.inspected_sample.is_none() {
+            self.inspected_s = None;
+            self.inspector_pinned = false;
+        }
+    }
+
+    fn handle_inspector_pointer(&mut self, input: &PointerInput) {
+        let idle = self.dragging_modify.is_none()
+            && !input.over_ui
+            && self.hit_test(input.world_pos).is_none();
+        let projected = if idle {
+            self.project_onto_selected_path(input.world_pos)
+        } else {
+            None
+        };
+        if input.pressed && idle {
+            self.inspector_pinned = projected.is_some();
+        }
+        if self.inspector_pinned {
+            if input.pressed && projected.is_some() {
+                self.inspected_s = projected;
+            }
+        } else {
+            self.inspected_s = projected;
+        }
+        self.update_inspection();
+    }
+
+    /// Promotes a candidate to the selected path: switches to its
+    /// `SinglePath` family with the matching reflect/timeflip flags.
+    pub fn select_candidate(&mut self, index: usize) {
+        let Some(candidate) = self.all_candidates.get(index) else {
+            return;
+        };
+        let (family, reflect, timeflip) = (candidate.family, candidate.reflect, candidate.timeflip);
+        self.set_display_mode_from_index(DisplayMode::SinglePath(family).combo_index());
+        self.reflect_path = reflect;
+        self.timeflip_path = timeflip;
+        self.hovered_candidate = None;
+    }
+
+    /// Applies one input event. Returns whether the displayed paths need to
+    /// be recalculated.
+    pub fn apply(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::Reset => {
+                self.reset();
+                false
+            }
+            InputEvent::Shortcut(shortcut) => {
+                // The exports write files, which the frontend does.
+                if *shortcut == Shortcut::Reset && self.accepts_shortcuts() {
+                    self.reset();
+                }
+                false
+            }
+            InputEvent::SetWordFocus(focused) => {
+                self.word_focused = *focused;
+                false
+            }
+            InputEvent::Pointer(input) => self.handle_pointer(input),
+            InputEvent::SelectDisplayMode(index) => {
+                self.set_display_mode_from_index(*index);
+                true
+            }
+            InputEvent::SetReflect(reflect) => {
+                self.reflect_path = *reflect;
+                true
+            }
+            InputEvent::SetTimeflip(timeflip) => {
+                self.timeflip_path = *timeflip;
+                true
+            }
+            InputEvent::SetCc {
+                enabled,
+                max_steering_rate,
+            } => {
+                self.cc_enabled = *enabled;
+                self.max_steering_rate = *max_steering_rate;
+                true
+            }
+            InputEvent::SetTrailer { enabled, model } => {
+                self.trailer_enabled = *enabled;
+                self.trailer_model = *model;
+                true
+            }
+            InputEvent::SetGrid { visible, snap } => {
+                self.grid_visible = *visible;
+                self.snap = *snap;
+                false
+            }
+            InputEvent::SetPathColoring(coloring) => {
+                self.path_coloring = *coloring;
+                false
+            }
+            InputEvent::SetCustomWord(word) => {
+                self.custom_word = word.clone();
+                true
+            }
+            InputEvent::SetSketch { enabled, metric } => {
+                let metric_changed = self.sketch_metric != *metric;
+                self.sketch_enabled = *enabled;
+                self.sketch_metric = *metric;
+                if !enabled {
+                    self.sketch_points.clear();
+                    self.sketch_drawing = false;
+                    self.sketch_fit = None;
+                    self.sketch_fit_candidate = None;
+                } else if metric_changed && !self.sketch_drawing {
+                    self.fit_sketch();
+                }
+                true
+            }
+            InputEvent::SetObstacleEditing(editing) => {
+                self.obstacle_editing = *editing;
+                self.obstacle_draft = None;
+                self.obstacle_draft_corner = None;
+                false
+            }
+            InputEvent::AddObstacle(obstacle) => {
+                self.obstacles.push(*obstacle);
+                self.discard_roadmap();
+                true
+            }
+            InputEvent::ClearObstacles => {
+                self.obstacles.clear();
+                self.discard_roadmap();
+                true
+            }
+            InputEvent::SetCspace {
+                enabled,
+                heading_degree,
+            } => {
+                self.cspace_enabled = *enabled;
+                self.cspace_heading_degree = *heading_degree;
+                false
+            }
+            InputEvent::SetPrm { samples, neighbors } => {
+                self.prm_settings.samples = *samples;
+                self.prm_settings.neighbors = *neighbors;
+                self.discard_roadmap();
+                true
+            }
+            InputEvent::RebuildRoadmap => {
+                self.prm_settings.seed = self.prm_settings.seed.wrapping_add(1);
+                self.discard_roadmap();
+                true
+            }
+            InputEvent::SetLattice(settings) => {
+                self.lattice_settings = *settings;
+                self.lattice_primitives = None;
+                true
+            }
+            InputEvent::SetConstruction(enabled) => {
+                self.construction_enabled = *enabled;
+                true
+            }
+            InputEvent::SetTrace(enabled) => {
+                self.trace_enabled = *enabled;
+                true
+            }
+            InputEvent::SetTransformExplorer(enabled) => {
+                self.transform_explorer_enabled = *enabled;
+                true
+            }
+            InputEvent::SetHeadingSweep(enabled) => {
+                self.heading_sweep_enabled = *enabled;
+                true
+            }
+            InputEvent::SetReach { enabled, settings } => {
+                self.reach_enabled = *enabled;
+                self.reach_settings = *settings;
+                true
+            }
+            InputEvent::SetStability(settings) => {
+                self.stability_settings = *settings;
+                true
+            }
+            InputEvent::SetEndHeading(theta_degree) => match self.end_pose.as_mut() {
+                Some(end) => {
+                    end.theta_degree =
+                        utils::normalize_angle_rad(theta_degree.to_radians()).to_degrees();
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Applies every event of a frame in order, then recalculates once if
+    /// any of them asked for it, just like the interactive main loop.
+    pub fn apply_frame(&mut self, frame: &InputFrame) {
+        let mut needs_recalculation = false;
+        for event in &frame.events {
+            needs_recalculation |= self.apply(event);
+        }
+        if needs_recalculation {
+            self.calculate_display_data();
+        }
+    }
+
+    fn discard_roadmap(&mut self) {
+        self.roadmap = None;
+        self.roadmap_samples.clear();
+    }
+
+    /// Routes through the roadmap, building it first if needed.
+    fn calculate_prm_path(&mut self, start: &Pose, end: &Pose) {
+        let roadmap = self.roadmap.get_or_insert_with(|| {
+            let bounds = sampling_bounds(&[*start, *end], &self.obstacles);
+            Roadmap::build(bounds, &self.obstacles, &self.prm_settings)
+        });
+        if self.roadmap_samples.is_empty() {
+            self.roadmap_samples = roadmap
+                .edges
+                .iter()
+                .map(|e| sample_path(&roadmap.nodes[e.from], &e.path, PRM_DRAW_STEP))
+                .collect();
+        }
+        self.prm_solution = roadmap.query(start, end, &self.obstacles, self.prm_settings.neighbors);
+        if let Some(solution) = &self.prm_solution {
+            let samples = sample_path(start, &solution.path, PATH_SAMPLE_STEP);
+            if !samples.is_empty() {
+                self.current_path_samples = Some(samples);
+                self.current_raw_path = Some(solution.path.clone());
+            }
+        }
+    }
+
+    /// The primitive set for the current settings, generated on first use.
+    pub fn primitive_set(&mut self) -> &PrimitiveSet {
+        let settings = self.lattice_settings;
+        self.lattice_primitives
+            .get_or_insert_with(|| PrimitiveSet::generate(settings))
+    }
+
+    fn calculate_lattice_path(&mut self, start: &Pose, end: &Pose) {
+        let obstacles = self.obstacles.clone();
+        self.lattice_solution = self.primitive_set().plan(start, end, &obstacles);
+        if let Some(solution) = &self.lattice_solution {
+            let samples = sample_path(start, &solution.path, PATH_SAMPLE_STEP);
+            if !samples.is_empty() {
+                self.current_path_samples = Some(samples);
+                self.current_raw_path = Some(solution.path.clone());
+            }
+        }
+    }
+
+    /// Samples every primitive leaving the lattice state nearest the start
+    /// pose, or the origin facing +x before one is placed.
+    fn calculate_lattice_primitives(&mut self) {
+        let start = self.start_pose.unwrap_or(Pose {
+            x: 0.0,
+            y: 0.0,
+            theta_degree: 0.0,
+        });
+        let set = self.primitive_set();
+        let anchor = set.nearest_state(&start);
+        let origin = set.pose(&anchor);
+        let samples = set.by_heading[anchor.heading]
+            .iter()
+            .map(|p| sample_path(&origin, &p.path, PRM_DRAW_STEP))
+            .collect();
+        self.lattice_primitive_samples = samples;
+        self.lattice_anchor = Some(anchor);
+    }
+
+    /// Drags out an obstacle rectangle between the press and release points.
+    fn handle_obstacle_pointer(&mut self, input: &PointerInput) -> bool {
+        let corner = self.snap.position(input.world_pos);
+        if input.pressed && !input.over_ui {
+            self.obstacle_draft_corner = Some(corner);
+        }
+        let Some(start) = self.obstacle_draft_corner else {
+            return false;
+        };
+        if input.down {
+            self.obstacle_draft = Some(Obstacle::from_corners(start, corner));
+        } else if input.released {
+            let draft = self.obstacle_draft.take();
+            self.obstacle_draft_corner = None;
+            if let Some(o) = draft
+                && o.max.0 - o.min.0 >= MIN_OBSTACLE_SIZE
+                && o.max.1 - o.min.1 >= MIN_OBSTACLE_SIZE
+            {
+                self.obstacles.push(o);
+                self.discard_roadmap();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Records a freehand stroke; releasing it fits the candidates.
+    fn handle_sketch_pointer(&mut self, input: &PointerInput) -> bool {
+        if input.pressed && !input.over_ui {
+            self.sketch_points = vec![input.world_pos];
+            self.sketch_drawing = true;
+            self.sketch_fit = None;
+            self.sketch_fit_candidate = None;
+            return false;
+        }
+        if !self.sketch_drawing {
+            return false;
+        }
+        let far_enough = self.sketch_points.last().is_none_or(|&(x, y)| {
+            (input.world_pos.0 - x).hypot(input.world_pos.1 - y) >= sketch::MIN_SKETCH_POINT_GAP
+        });
+        if (input.down || input.released) && far_enough {
+            self.sketch_points.push(input.world_pos);
+        }
+        if input.released {
+            self.sketch_drawing = false;
+            return self.fit_sketch();
+        }
+        false
+    }
+
+    /// Places both poses on the sketch tangents and selects the candidate
+    /// closest to the sketch under `sketch_metric`. Returns whether a fit was
+    /// found.
+    pub fn fit_sketch(&mut self) -> bool {
+        self.sketch_fit = None;
+        self.sketch_fit_candidate = None;
+        let Some((start, end)) = sketch::sketch_poses(&self.sketch_points) else {
+            return false;
+        };
+        self.start_pose = Some(start);
+        self.end_pose = Some(end);
+        self.drag_state_initial = None;
+        self.dragging_modify = None;
+        self.app_state = AppState::DisplayingPaths;
+        let all = candidates::all_candidates(&start, &end);
+        let samples: Vec<Vec<PathSample>> = all
+            .iter()
+            .map(|c| sample_path(&start, &c.path, PATH_SAMPLE_STEP))
+            .collect();
+        let Some(fit) = sketch::fit_sketch(&self.sketch_points, &samples, self.sketch_metric)
+        else {
+            return true;
+        };
+        self.all_candidates = all;
+        self.all_paths_samples = samples;
+        self.select_candidate(fit.candidate);
+        self.sketch_fit_candidate = self.all_candidates.get(fit.candidate).cloned();
+        self.sketch_fit = Some(fit);
+        true
+    }
+
+    /// Advances the placement/drag state machine by one frame. Returns whether
+    /// the displayed paths need to be recalculated.
+    pub fn handle_pointer(&mut self, input: &PointerInput) -> bool {
+        if self.obstacle_editing {
+            return self.handle_obstacle_pointer(input);
+        }
+        if self.sketch_enabled {
+            return self.handle_sketch_pointer(input);
+        }
+        let snapped_pos = self.snap.position(input.world_pos);
+        let (world_x, world_y) = snapped_pos;
+        let mut needs_recalculation = false;
+        match self.app_state {
+            AppState::PlacingStart => {
+                if input.pressed {
+                    self.start_pose = Some(Pose {
+                        x: world_x,
+                        y: world_y,
+                        theta_degree: 0.0,
+                    });
+                    self.drag_state_initial = Some(InitialDragState {
+                        start_pos: snapped_pos,
+                        current_pos: input.world_pos,
+                    });
+                    self.app_state = AppState::DefiningStartAngle;
+                }
+            }
+            AppState::DefiningStartAngle => {
+                if input.down {
+                    if let Some(drag) = &mut self.drag_state_initial {
+                        drag.current_pos = input.world_pos;
+                    }
+                    if let Some(angle) = self.calculate_initial_drag_angle()
+                        && let Some(start) = &mut self.start_pose
+                    {
+                        start.theta_degree = angle;
+                    }
+                } else if input.released {
+                    if let Some(angle) = self.calculate_initial_drag_angle()
+                        && let Some(start) = &mut self.start_pose
+                    {
+                        start.theta_degree = angle;
+                    }
+                    self.drag_state_initial = None;
+                    self.app_state = AppState::PlacingEnd;
+                }
+            }
+            AppState::PlacingEnd => {
+                if input.pressed {
+                    self.end_pose = Some(Pose {
+                        x: world_x,
+                        y: world_y,
+                        theta_degree: 0.0,
+                    });
+                    self.drag_state_initial = Some(InitialDragState {
+                        start_pos: snapped_pos,
+                        current_pos: input.world_pos,
+                    });
+                    self.app_state = AppState::DefiningEndAngle;
+                }
+            }
+            AppState::DefiningEndAngle => {
+                if input.down {
+                    if let Some(drag) = &mut self.drag_state_initial {
+                        drag.current_pos = input.world_pos;
+                    }
+                    if let Some(angle) = self.calculate_initial_drag_angle()
+                        && let Some(end) = &mut self.end_pose
+                    {
+                        end.theta_degree = angle;
+                    }
+                } else if input.released {
+                    if let Some(angle) = self.calculate_initial_drag_angle()
+                        && let Some(end) = &mut self.end_pose
+                    {
+                        end.theta_degree = angle;
+                    }
+                    self.drag_state_initial = None;
+                    self.calculate_display_data();
+                    self.app_state = AppState::DisplayingPaths;
+                }
+            }
+            AppState::DisplayingPaths => {
+                self.handle_inspector_pointer(input);
+                // The selected path itself is inspected rather than hovered.
+                let can_hover = self.display_mode == DisplayMode::AllPaths
+                    && self.dragging_modify.is_none()
+                    && !input.over_ui
+                    && self.hit_test(input.world_pos).is_none()
+                    && self.project_onto_selected_path(input.world_pos).is_none();
+                self.hovered_candidate = if can_hover {
+                    self.nearest_candidate(input.world_pos)
+                } else {
+                    None
+                };
+                if input.pressed && !input.over_ui {
+                    self.dragging_modify = self.hit_test(input.world_pos);
+                    if let Some(index) = self.hovered_candidate {
+                        self.select_candidate(index);
+                        return true;
+                    }
+                }
+                if let Some(target) = self.dragging_modify {
+                    if input.down {
+                        match target {
+                            ModifyDragTarget::StartBody => {
+                                if let Some(p) = &mut self.start_pose {
+                                    p.x = world_x;
+                                    p.y = world_y;
+                                    needs_recalculation = true;
+                                }
+                            }
+                            ModifyDragTarget::EndBody => {
+                                if let Some(p) = &mut self.end_pose {
+                                    p.x = world_x;
+                                    p.y = world_y;
+                                    needs_recalculation = true;
+                                }
+                            }
+                            ModifyDragTarget::StartAngle => {
+                                if let Some(p) = &mut self.start_pose {
+                                    let dx = input.world_pos.0 - p.x;
+                                    let dy = input.world_pos.1 - p.y;
+                                    if dx.hypot(dy) > 1e-6 {
+                                        p.theta_degree =
+                                            self.snap.heading(dy.atan2(dx).to_degrees());
+                                        needs_recalculation = true;
+                                    }
+                                }
+                            }
+                            ModifyDragTarget::EndAngle => {
+                                if let Some(p) = &mut self.end_pose {
+                                    let dx = input.world_pos.0 - p.x;
+                                    let dy = input.world_pos.1 - p.y;
+                                    if dx.hypot(dy) > 1e-6 {
+                                        p.theta_degree =
+                                            self.snap.heading(dy.atan2(dx).to_degrees());
+                                        needs_recalculation = true;
+                                    }
+                                }
+                            }
+                        }
+                    } else if input.released {
+                        self.dragging_modify = None;
+                        needs_recalculation = true;
+                    }
+                }
+            }
+        }
+        needs_recalculation
+    }
+
+    /// Field the reachable set is contoured from: the union, or the one for
+    /// the end heading relative to the start (straight ahead without an end
+    /// pose).
+    pub fn reach_field(&self) -> Option<&ReachField> {
+        if self.reach_settings.union {
+            self.reach_union_field.as_ref()
+        } else {
+            self.reach_heading_field.as_ref()
+        }
+    }
+
+    fn calculate_reach(&mut self, start: &Pose) {
+        let axis = reach_axis(MAX_REACH_BUDGET, REACH_RESOLUTION);
+        if self.reach_settings.union {
+            if self.reach_union_field.is_none() {
+                self.reach_union_field = Some(ReachField::union(axis, REACH_HEADINGS));
+            }
+        } else {
+            let heading = self.end_pose.map_or(0.0, |end| {
+                utils::normalize_angle_rad((end.theta_degree - start.theta_degree).to_radians())
+                    .to_degrees()
+            });
+            let current = self
+                .reach_heading_field
+                .as_ref()
+                .and_then(|f| f.heading_degree);
+            if current != Some(heading) {
+                self.reach_heading_field = Some(ReachField::for_heading(axis, heading));
+            }
+        }
+        if let Some(field) = self.reach_field() {
+            self.reach_regions = field.regions(self.reach_settings.budget, start);
+        }
+    }
+
+    // Calculate display data based on mode
+    pub fn calculate_display_data(&mut self) {
+        self.current_path_samples = None;
+        self.current_raw_path = None;
+        self.all_candidates.clear();
+        self.all_paths_samples.clear();
+        self.hovered_candidate = None;
+        self.current_cc_samples = None;
+        self.cc_comparison = None;
+        self.trailer_samples = None;
+        self.trailer_summary = None;
+        self.prm_solution = None;
+        self.lattice_solution = None;
+        self.lattice_primitive_samples.clear();
+        self.lattice_anchor = None;
+        self.heading_sweep.clear();
+        self.reach_regions.clear();
+        self.transition = None;
+        self.runner_up_samples = None;
+        self.transform_explorer = None;
+        self.gallery = None;
+        self.family_trace = None;
+        self.construction = None;
+        self.construction_tangents_used.clear();
+        self.construction_middle_used.clear();
+        if self.reach_enabled
+            && let Some(start) = self.start_pose
+        {
+            self.calculate_reach(&start);
+        }
+        if let (Some(start), Some(end)) = (self.start_pose, self.end_pose) {
+            if self.heading_sweep_enabled {
+                self.heading_sweep = sweep_end_heading(&start, &end, HEADING_SWEEP_STEPS);
+            }
+            match self.display_mode {
+                DisplayMode::Prm => self.calculate_prm_path(&start, &end),
+                DisplayMode::Lattice => self.calculate_lattice_path(&start, &end),
+                _ => {}
+            }
+        }
+        if self.display_mode == DisplayMode::LatticePrimitives {
+            self.calculate_lattice_primitives();
+        }
+        if let (Some(start), Some(end)) = (self.start_pose.as_ref(), self.end_pose.as_ref()) {
+            match self.display_mode {
+                DisplayMode::SinglePath(index) => {
+                    let path = candidates::evaluate_family(
+                        start,
+                        end,
+                        index,
+                        self.reflect_path,
+                        self.timeflip_path,
+                    );
+                    let samples = sample_path(start, &path, PATH_SAMPLE_STEP);
+                    if !samples.is_empty() {
+                        self.current_path_samples = Some(samples);
+                        self.current_raw_path = Some(path);
+                    }
+                    if self.trace_enabled {
+                        self.family_trace = FamilyTrace::new(
+                            start,
+                            end,
+                            index,
+                            self.reflect_path,
+                            self.timeflip_path,
+                        );
+                    }
+                }
+                DisplayMode::ShortestPath => {
+                    let candidates = candidates::reaching_candidates(start, end);
+                    self.transition =
+                        stability::rank(&candidates, self.shown_variant, &self.stability_settings);
+                    if let Some(t) = &self.transition {
+                        self.shown_variant = Some(Variant::of(&t.shown));
+                        if self.stability_settings.show_runner_up
+                            && let Some(r) = &t.runner_up
+                        {
+                            self.runner_up_samples =
+                                Some(sample_path(start, &r.path, PATH_SAMPLE_STEP));
+                        }
+                    }
+                    // The ranked variant, so the path matches the gap readout;
+                    // the library optimum only when no candidate reaches the goal.
+                    let shown = match &self.transition {
+                        Some(t) => Some(t.shown.path.clone()),
+                        None => get_optimal_path(*start, *end),
+                    };
+                    if let Some(path) = shown {
+                        let samples = sample_path(start, &path, PATH_SAMPLE_STEP);
+                        if !samples.is_empty() {
+                            self.current_path_samples = Some(samples);
+                            self.current_raw_path = Some(path);
+                        }
+                    }
+                }
+                DisplayMode::Prm | DisplayMode::Lattice | DisplayMode::LatticePrimitives => {}
+                DisplayMode::Gallery => {
+                    let gallery = Gallery::new(start, end);
+                    if let Some(optimal) = gallery.optimal.map(|i| &gallery.thumbs[i].cell) {
+                        self.current_path_samples =
+                            Some(sample_path(start, &optimal.path, PATH_SAMPLE_STEP));
+                        self.current_raw_path = Some(optimal.path.clone());
+                    }
+                    self.gallery = Some(gallery);
+                }
+                DisplayMode::AllPaths => {
+                    self.all_candidates = candidates::all_candidates(start, end);
+                    for candidate in &self.all_candidates {
+                        self.all_paths_samples.push(sample_path(
+                            start,
+                            &candidate.path,
+                            PATH_SAMPLE_STEP,
+                        ));
+                    }
+                    if let Some(shortest) = candidates::shortest_candidate(&self.all_candidates) {
+                        let samples = sample_path(start, &shortest.path, PATH_SAMPLE_STEP);
+                        if !samples.is_empty() {
+                            self.current_path_samples = Some(samples);
+                            self.current_raw_path = Some(shortest.path.clone());
+                        }
+                    }
+                }
+            }
+        }
+        if self.transform_explorer_enabled
+            && let (Some(start), Some(end), Some(family)) =
+                (self.start_pose, self.end_pose, self.explored_family())
+        {
+            self.transform_explorer = Some(TransformExplorer::new(&start, &end, family));
+        }
+        if self.construction_enabled
+            && let (Some(start), Some(end)) = (self.start_pose, self.end_pose)
+        {
+            let construction = Construction::new(&start, &end);
+            if let Some(path) = &self.current_raw_path {
+                self.construction_tangents_used = construction.used_tangents(&start, path);
+                self.construction_middle_used = construction.used_middle(&start, path);
+            }
+            self.construction = Some(construction);
+        }
+        self.calculate_custom_path();
+        self.sketch_distance = match &self.current_path_samples {
+            Some(samples) if !self.sketch_drawing && !self.sketch_points.is_empty() => {
+                let sketch = resample_polyline(&self.sketch_points, PATH_SAMPLE_STEP);
+                let points: Vec<(f64, f64)> = samples.iter().map(|s| (s.x, s.y)).collect();
+                Some(self.sketch_metric.distance(&sketch, &points))
+            }
+            _ => None,
+        };
+        if self.cc_enabled {
+            self.calculate_cc_data();
+        }
+        if self.trailer_enabled
+            && let Some(samples) = &self.current_path_samples
+        {
+            let trailer_samples = self.trailer_model.propagate(samples);
+            self.trailer_summary = Some(trailer::summarize(&trailer_samples));
+            self.trailer_samples = Some(trailer_samples);
+        }
+        self.update_inspection();
+    }
+
+    /// Family shown in the transform explorer for the current mode.
+    fn explored_family(&self) -> Option<usize> {
+        match self.display_mode {
+            DisplayMode::SinglePath(index) => Some(index),
+            DisplayMode::ShortestPath => self.shown_variant.map(|v| v.family),
+            _ => None,
+        }
+    }
+
+    fn calculate_custom_path(&mut self) {
+        self.custom_word_error = None;
+        self.custom_path_samples = None;
+        self.custom_end_offset = None;
+        let path = match parse_path_word(&self.custom_word) {
+            Ok(path) if !path.is_empty() => path,
+            Ok(_) => return,
+            Err(e) => {
+                self.custom_word_error = Some(e);
+                return;
+            }
+        };
+        if let Some(start) = &self.start_pose {
+            self.custom_path_samples = Some(sample_path(start, &path, PATH_SAMPLE_STEP));
+            if let Some(end) = &self.end_pose {
+                let mut offset = utils::change_of_basis(end, &path_end_pose(start, &path));
+                offset.theta_degree =
+                    utils::normalize_angle_rad(offset.theta_degree.to_radians()).to_degrees();
+                self.custom_end_offset = Some(offset);
+            }
+        }
+    }
+
+    fn calculate_cc_data(&mut self) {
+        if let (Some(path), Some(start), Some(end)) =
+            (&self.current_raw_path, &self.start_pose, &self.end_pose)
+        {
+            // Lengths only compare when both paths end on the goal.
+            if !candidates::reaches_goal(start, end, path) {
+                return;
+            }
+            if let Some(solved) = cc::solve_cc_path(start, end, path, self.max_steering_rate) {
+                let pieces = cc::cc_pieces(&solved, self.max_steering_rate);
+                self.current_cc_samples =
+                    Some(cc::sample_cc_path(start, &pieces, PATH_SAMPLE_STEP));
+                self.cc_comparison = Some(cc::compare(path, &pieces));
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Tessellation of thick polylines and arrowheads into a [`TriangleMesh`].
+//!
+//! A polyline becomes one triangle strip with mitered joins, so consecutive
+//! pieces neither overlap (no darker spots on translucent paths) nor leave
+//! gaps. Joins sharper than the miter limit, which on Reeds-Shepp paths only
+//! happen at cusps, end the strip and start a new one in place.
+
+use crate::render::{Rgba, ScreenPoint, TriangleMesh};
+
+// --- Constants ---
+/// Longest allowed miter, in multiples of half the line thickness.
+pub const MITER_LIMIT: f32 = 4.0;
+/// Points closer than this to the previous one are skipped.
+const MIN_POINT_GAP: f32 = 1e-3;
+
+fn sub(a: ScreenPoint, b: ScreenPoint) -> ScreenPoint {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn normalize(v: ScreenPoint) -> ScreenPoint {
+    let len = v.0.hypot(v.1);
+    (v.0 / len, v.1 / len)
+}
+
+/// Left-hand normal of a unit direction.
+fn normal(d: ScreenPoint) -> ScreenPoint {
+    (-d.1, d.0)
+}
+
+/// Offset from a join to the left edge of the strip, or `None` if the join
+/// is too sharp to miter.
+fn miter(d_in: ScreenPoint, d_out: ScreenPoint, half: f32) -> Option<ScreenPoint> {
+    let (n_in, n_out) = (normal(d_in), normal(d_out));
+    let sum = (n_in.0 + n_out.0, n_in.1 + n_out.1);
+    if sum.0.hypot(sum.1) < 1e-6 {
+        return None;
+    }
+    let m = normalize(sum);
+    let cos_half_angle = m.0 * n_in.0 + m.1 * n_in.1;
+    if cos_half_angle < 1.0 / MITER_LIMIT {
+        return None;
+    }
+    let len = half / cos_half_angle;
+    Some((m.0 * len, m.1 * len))
+}
+
+fn push_pair(
+    mesh: &mut TriangleMesh,
+    p: ScreenPoint,
+    offset: ScreenPoint,
+    color: Rgba,
+) -> (u32, u32) {
+    let left = mesh.push_vertex((p.0 + offset.0, p.1 + offset.1), color);
+    let right = mesh.push_vertex((p.0 - offset.0, p.1 - offset.1), color);
+    (left, right)
+}
+
+fn push_quad(mesh: &mut TriangleMesh, from: (u32, u32), to: (u32, u32)) {
+    mesh.push_triangle(from.0, from.1, to.0);
+    mesh.push_triangle(from.1, to.1, to.0);
+}
+
+/// Appends a stroke of `thickness` pixels through `points`. The piece from
+/// `points[i]` to the next point is drawn in `colors[i]`; a color change
+/// starts a new run of vertices at the shared point, so the switch is crisp.
+pub fn stroke_polyline(
+    mesh: &mut TriangleMesh,
+    points: &[ScreenPoint],
+    colors: &[Rgba],
+    thickness: f32,
+) {
+    debug_assert_eq!(points.len(), colors.len());
+    let half = thickness * 0.5;
+    let mut kept: Vec<usize> = Vec::with_capacity(points.len());
+    for (i, p) in points.iter().enumerate() {
+        let far_enough = kept.last().is_none_or(|&k| {
+            let d = sub(*p, points[k]);
+            d.0.hypot(d.1) > MIN_POINT_GAP
+        });
+        if far_enough {
+            kept.push(i);
+        }
+    }
+    if kept.len() < 2 {
+        return;
+    }
+
+    let direction = |a: usize, b: usize| normalize(sub(points[b], points[a]));
+    let mut open: Option<(u32, u32)> = None;
+    for (k, &i) in kept.iter().enumerate() {
+        let p = points[i];
+        let d_in = (k > 0).then(|| direction(kept[k - 1], i));
+        let d_out = (k + 1 < kept.len()).then(|| direction(i, kept[k + 1]));
+        let (end_offset, start_offset) = match (d_in, d_out) {
+            (Some(a), Some(b)) => match miter(a, b, half) {
+                Some(m) => (m, m),
+                None => {
+                    let (na, nb) = (normal(a), normal(b));
+                    ((na.0 * half, na.1 * half), (nb.0 * half, nb.1 * half))
+                }
+            },
+            (Some(a), None) => {
+                let n = normal(a);
+                let offset = (n.0 * half, n.1 * half);
+                (offset, offset)
+            }
+            (None, Some(b)) => {
+                let n = normal(b);
+                let offset = (n.0 * half, n.1 * half);
+                (offset, offset)
+            }
+            (None, None) => unreachable!("at least two points are kept"),
+        };
+
+        let color_in = (k > 0).then(|| colors[kept[k - 1]]);
+        let color_out = colors[i];
+        if let (Some(from), Some(color_in)) = (open, color_in) {
+            let to = push_pair(mesh, p, end_offset, color_in);
+            push_quad(mesh, from, to);
+            if d_out.is_some() && end_offset == start_offset && color_in == color_out {
+                open = Some(to);
+                continue;
+            }
+        }
+        open = d_out.map(|_| push_pair(mesh, p, start_offset, color_out));
+    }
+}
+
+/// Appends a filled arrowhead centered on `center` pointing along the unit
+/// screen direction `dir`.
+pub fn arrowhead(
+    mesh: &mut TriangleMesh,
+    center: ScreenPoint,
+    dir: ScreenPoint,
+    size: f32,
+    color: Rgba,
+) {
+    let n = normal(dir);
+    let tip = (center.0 + dir.0 * size * 0.6, center.1 + dir.1 * size * 0.6);
+    let back = (center.0 - dir.0 * size * 0.4, center.1 - dir.1 * size * 0.4);
+    let a = mesh.push_vertex(tip, color);
+    let b = mesh.push_vertex(
+        (back.0 + n.0 * size * 0.5, back.1 + n.1 * size * 0.5),
+        color,
+    );
+    let c = mesh.push_vertex(
+        (back.0 - n.0 * size * 0.5, back.1 - n.1 * size * 0.5),
+        color,
+    );
+    mesh.push_triangle(a, b, c);
+}
@@ -0,0 +1,156 @@
+//! End heading sweep: the optimal length and winning family as the end
+//! heading turns through a full circle while both positions stay fixed.
+//!
+//! The chart is laid out in logical pixels wherever the frontend's layout
+//! puts it. Its x axis is the heading from -180° to 180°, so a click maps
+//! straight back to the heading under the pointer.
+
+use reeds_shepp_lib::Pose;
+
+use crate::candidates::{reaching_candidates, shortest_candidate};
+use crate::render::ScreenPoint;
+
+// --- Constants ---
+/// Heading intervals per sweep, one per degree.
+pub const HEADING_SWEEP_STEPS: usize = 360;
+/// Width of the chart when there is room for it.
+pub const SWEEP_CHART_WIDTH: f32 = 360.0;
+pub const SWEEP_CHART_HEIGHT: f32 = 160.0;
+/// Room left of the plot for the length labels.
+const PLOT_LEFT_INSET: f32 = 36.0;
+const PLOT_RIGHT_INSET: f32 = 10.0;
+const PLOT_TOP_INSET: f32 = 10.0;
+/// Room below the plot for the family band, heading labels and readout.
+const PLOT_BOTTOM_INSET: f32 = 50.0;
+
+/// Winner at one end heading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepSample {
+    pub theta_degree: f64,
+    pub length: f64,
+    pub family: usize,
+    pub reflect: bool,
+    pub timeflip: bool,
+}
+
+impl SweepSample {
+    /// Whether `other` is won by the same family under the same transform.
+    pub fn same_winner(&self, other: &SweepSample) -> bool {
+        (self.family, self.reflect, self.timeflip) == (other.family, other.reflect, other.timeflip)
+    }
+}
+
+/// Shortest candidate from `start` to `end` with the end heading replaced,
+/// at `steps + 1` headings from -180° to 180°. Candidates that do not really
+/// end on the goal are skipped, and headings without any are left out.
+pub fn sweep_end_heading(start: &Pose, end: &Pose, steps: usize) -> Vec<SweepSample> {
+    (0..=steps)
+        .filter_map(|i| {
+            let theta_degree = -180.0 + 360.0 * i as f64 / steps as f64;
+            let goal = Pose {
+                theta_degree,
+                ..*end
+            };
+            let candidates = reaching_candidates(start, &goal);
+            shortest_candidate(&candidates).map(|c| SweepSample {
+                theta_degree,
+                length: c.length,
+                family: c.family,
+                reflect: c.reflect,
+                timeflip: c.timeflip,
+            })
+        })
+        .collect()
+}
+
+/// Sample whose heading is closest to `theta_degree`.
+pub fn nearest_sample(samples: &[SweepSample], theta_degree: f64) -> Option<&SweepSample> {
+    samples.iter().min_by(|a, b| {
+        (a.theta_degree - theta_degree)
+            .abs()
+            .total_cmp(&(b.theta_degree - theta_degree).abs())
+    })
+}
+
+/// Shortest and longest length of the sweep, padded so that a flat sweep
+/// still gets a usable scale.
+pub fn length_range(samples: &[SweepSample]) -> (f64, f64) {
+    let min = samples
+        .iter()
+        .map(|s| s.length)
+        .fold(f64::INFINITY, f64::min);
+    let max = samples.iter().map(|s| s.length).fold(0.0, f64::max);
+    let pad = ((max - min) * 0.05).max(0.05);
+    ((min - pad).max(0.0), max + pad)
+}
+
+/// Screen rectangles of the chart and of its plot area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepChart {
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl SweepChart {
+    /// Chart with its top left corner at `(left, top)`, narrowed to
+    /// `max_width` when the full width does not fit.
+    pub fn fit(left: f32, top: f32, max_width: f32) -> Self {
+        SweepChart {
+            left,
+            top,
+            width: SWEEP_CHART_WIDTH.min(max_width).max(0.0),
+            height: SWEEP_CHART_HEIGHT,
+        }
+    }
+
+    pub fn right(&self) -> f32 {
+        self.left + self.width
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.top + self.height
+    }
+    pub fn contains(&self, p: ScreenPoint) -> bool {
+        (self.left..=self.left + self.width).contains(&p.0)
+            && (self.top..=self.top + self.height).contains(&p.1)
+    }
+
+    pub fn plot_left(&self) -> f32 {
+        self.left + PLOT_LEFT_INSET
+    }
+
+    pub fn plot_right(&self) -> f32 {
+        self.left + self.width - PLOT_RIGHT_INSET
+    }
+
+    pub fn plot_top(&self) -> f32 {
+        self.top + PLOT_TOP_INSET
+    }
+
+    pub fn plot_bottom(&self) -> f32 {
+        self.top + self.height - PLOT_BOTTOM_INSET
+    }
+
+    pub fn x_for(&self, theta_degree: f64) -> f32 {
+        let t = ((theta_degree + 180.0) / 360.0) as f32;
+        self.plot_left() + t * (self.plot_right() - self.plot_left())
+    }
+
+    /// Screen y of `length` on a scale from `range.0` to `range.1`.
+    pub fn y_for(&self, length: f64, range: (f64, f64)) -> f32 {
+        let t = ((length - range.0) / (range.1 - range.0)) as f32;
+        self.plot_bottom() - t * (self.plot_bottom() - self.plot_top())
+    }
+
+    /// Heading under the screen point, clamped to the plot's x range, or
+    /// `None` outside the chart. Rounded to whole degrees like the samples.
+    pub fn heading_at(&self, p: ScreenPoint) -> Option<f64> {
+        if !self.contains(p) {
+            return None;
+        }
+        let t = ((p.0 - self.plot_left()) / (self.plot_right() - self.plot_left())).clamp(0.0, 1.0);
+        Some((t as f64 * 360.0 - 180.0).round())
+    }
+}
@@ -0,0 +1,223 @@
+//! What one path function computed, for debugging a `SinglePath` family.
+//!
+//! The solver functions only return a path, so the intermediate values are
+//! rebuilt here from the same formulas' first step: every family measures
+//! the distance `rho` from the start's left turning circle (at `(0, 1)` in
+//! the transformed frame) to one turning circle of the goal, and returns an
+//! empty path when `rho` is outside the range its construction needs.
+
+use reeds_shepp_lib::{Gear, PATH_FNS, Path, Pose, Steering, utils};
+
+use crate::candidates::{reaches_goal, transform_goal};
+use crate::path::path_end_pose;
+
+// --- Constants ---
+/// The untransformed word of every `PATH_FNS` entry, as written in the
+/// solver, with the name of the parameter of each element.
+const FAMILY_ELEMENTS: [&[(&str, Steering, Gear)]; 12] = {
+    use Gear::{Backwards as B, Forward as F};
+    use Steering::{Left as L, Right as R, Straight as S};
+    [
+        &[("t", L, F), ("u", S, F), ("v", L, F)],
+        &[("t", L, F), ("u", S, F), ("v", R, F)],
+        &[("t", L, F), ("u", R, B), ("v", L, F)],
+        &[("t", L, F), ("u", R, B), ("v", L, B)],
+        &[("t", L, F), ("u", R, F), ("v", L, B)],
+        &[("t", L, F), ("u", R, F), ("u", L, B), ("v", R, B)],
+        &[("t", L, F), ("u", R, B), ("u", L, B), ("v", R, F)],
+        &[("t", L, F), ("π/2", R, B), ("u", S, B), ("v", L, B)],
+        &[("t", L, F), ("u", S, F), ("π/2", R, F), ("v", L, B)],
+        &[("t", L, F), ("π/2", R, B), ("u", S, B), ("v", R, B)],
+        &[("t", L, F), ("u", S, F), ("π/2", L, F), ("v", R, B)],
+        &[
+            ("t", L, F),
+            ("π/2", R, B),
+            ("u", S, B),
+            ("π/2", L, B),
+            ("v", R, F),
+        ],
+    ]
+};
+
+/// Turning circle of the goal that a family measures `rho` to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalCircle {
+    Left,
+    Right,
+}
+
+/// Goal circle and feasible `rho` range of `family`, from the guards of
+/// the solver's path functions.
+pub fn family_guard(family: usize) -> (GoalCircle, f64, f64) {
+    match family {
+        0 => (GoalCircle::Left, 0.0, f64::INFINITY),
+        1 => (GoalCircle::Right, 2.0, f64::INFINITY),
+        2..=4 => (GoalCircle::Left, 0.0, 4.0),
+        5 => (GoalCircle::Right, 0.0, 4.0),
+        // `(20 - rho²) / 16` must lie in `[0, 1]`.
+        6 => (GoalCircle::Right, 2.0, 20f64.sqrt()),
+        7 | 8 => (GoalCircle::Left, 2.0, f64::INFINITY),
+        9 | 10 => (GoalCircle::Right, 2.0, f64::INFINITY),
+        _ => (GoalCircle::Right, 4.0, f64::INFINITY),
+    }
+}
+
+/// Why a family gave no usable path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Infeasibility {
+    /// The goal circle is closer than the construction needs.
+    TooClose { rho: f64, min: f64 },
+    /// The goal circle is farther than the construction can bridge.
+    TooFar { rho: f64, max: f64 },
+    /// A formula left its domain and produced a NaN or infinite parameter.
+    NonFinite,
+    /// A path came out but ends elsewhere.
+    MissesGoal {
+        position_error: f64,
+        heading_error_degree: f64,
+    },
+}
+
+impl std::fmt::Display for Infeasibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Infeasibility::TooClose { rho, min } => {
+                write!(f, "ρ={:.3} below {:.3}: circles too close", rho, min)
+            }
+            Infeasibility::TooFar { rho, max } => {
+                write!(f, "ρ={:.3} above {:.3}: circles too far apart", rho, max)
+            }
+            Infeasibility::NonFinite => write!(f, "non-finite parameter"),
+            Infeasibility::MissesGoal {
+                position_error,
+                heading_error_degree,
+            } => write!(
+                f,
+                "misses goal by {:.3} / {:.1}°",
+                position_error, heading_error_degree
+            ),
+        }
+    }
+}
+
+/// One element as the path function returned it, before the transforms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceParam {
+    pub name: &'static str,
+    /// Signed against the family's written gear, so a negative value means
+    /// the solver flipped the gear of this element.
+    pub value: f64,
+    pub steering: Steering,
+    pub gear: Gear,
+}
+
+/// Intermediate values of one family under one transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FamilyTrace {
+    pub family: usize,
+    pub reflect: bool,
+    pub timeflip: bool,
+    /// The end pose in the start's frame, as `utils::change_of_basis` gives it.
+    pub relative: Pose,
+    /// The goal the path function received.
+    pub transformed: Pose,
+    pub goal_circle: GoalCircle,
+    /// Center of the goal circle in the transformed frame.
+    pub goal_circle_center: (f64, f64),
+    pub rho: f64,
+    pub rho_range: (f64, f64),
+    pub params: Vec<TraceParam>,
+    /// The path after the transforms, as drawn.
+    pub path: Path,
+    pub infeasibility: Option<Infeasibility>,
+}
+
+impl FamilyTrace {
+    /// Traces `family` between two poses, or `None` when it is out of range.
+    pub fn new(
+        start: &Pose,
+        end: &Pose,
+        family: usize,
+        reflect: bool,
+        timeflip: bool,
+    ) -> Option<Self> {
+        let path_fn = PATH_FNS.get(family)?;
+        let relative = utils::change_of_basis(start, end);
+        let transformed = transform_goal(&relative, reflect, timeflip);
+        let (goal_circle, min, max) = family_guard(family);
+        let (sin, cos) = transformed.theta_degree.to_radians().sin_cos();
+        let goal_circle_center = match goal_circle {
+            GoalCircle::Left => (transformed.x - sin, transformed.y + cos),
+            GoalCircle::Right => (transformed.x + sin, transformed.y - cos),
+        };
+        let rho = goal_circle_center.0.hypot(goal_circle_center.1 - 1.0);
+
+        let raw = path_fn(transformed.x, transformed.y, transformed.theta_degree);
+        let params: Vec<TraceParam> = raw
+            .iter()
+            .zip(FAMILY_ELEMENTS[family])
+            .map(|(element, &(name, steering, gear))| TraceParam {
+                name,
+                value: if element.gear == gear {
+                    element.param
+                } else {
+                    -element.param
+                },
+                steering,
+                gear,
+            })
+            .collect();
+        let mut path = raw;
+        if timeflip {
+            path = reeds_shepp_lib::timeflip(path);
+        }
+        if reflect {
+            path = reeds_shepp_lib::reflect(path);
+        }
+
+        let infeasibility = if path.is_empty() {
+            Some(if rho < min {
+                Infeasibility::TooClose { rho, min }
+            } else if rho > max {
+                Infeasibility::TooFar { rho, max }
+            } else {
+                Infeasibility::NonFinite
+            })
+        } else if params.iter().any(|p| !p.value.is_finite()) {
+            Some(Infeasibility::NonFinite)
+        } else if !reaches_goal(start, end, &path) {
+            let actual = path_end_pose(start, &path);
+            let heading_error =
+                utils::normalize_angle_rad((actual.theta_degree - end.theta_degree).to_radians());
+            Some(Infeasibility::MissesGoal {
+                position_error: (actual.x - end.x).hypot(actual.y - end.y),
+                heading_error_degree: heading_error.to_degrees(),
+            })
+        } else {
+            None
+        };
+        Some(FamilyTrace {
+            family,
+            reflect,
+            timeflip,
+            relative,
+            transformed,
+            goal_circle,
+            goal_circle_center,
+            rho,
+            rho_range: (min, max),
+            params,
+            path,
+            infeasibility,
+        })
+    }
+
+    /// World position of a point given in the transformed frame. Reflect
+    /// mirrors the frame's y axis and timeflip its x axis.
+    pub fn to_world(&self, start: &Pose, (x, y): (f64, f64)) -> (f64, f64) {
+        let x = if self.timeflip { -x } else { x };
+        let y = if self.reflect { -y } else { y };
+        let (sin, cos) = start.theta_degree.to_radians().sin_cos();
+        (start.x + x * cos - y * sin, start.y + x * sin + y * cos)
+    }
+}
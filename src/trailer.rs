@@ -0,0 +1,179 @@
+//! Off-axle hitched trailer propagated along a tractor path.
+//!
+//! The hitch sits `hitch_offset` behind the tractor reference pose and the
+//! trailer axle `trailer_length` behind the hitch. Per unit of arc length
+//! driven by the tractor (with `g = ±1` for the gear and signed curvature
+//! `kappa`) the trailer heading `phi` evolves as
+//!
+//! ```text
+//! dphi/ds = g / L * (sin(theta - phi) - M * kappa * cos(theta - phi))
+//! ```
+
+use reeds_shepp_lib::{Gear, utils};
+
+use crate::path::PathSample;
+
+// --- Constants ---
+pub const DEFAULT_HITCH_OFFSET: f64 = 0.5;
+pub const DEFAULT_TRAILER_LENGTH: f64 = 1.5;
+pub const DEFAULT_JACKKNIFE_LIMIT_DEGREE: f64 = 60.0;
+pub const TRAILER_WIDTH: f64 = 0.6;
+const INTEGRATION_STEP: f64 = 1e-2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailerModel {
+    pub hitch_offset: f64,
+    pub trailer_length: f64,
+    pub jackknife_limit_degree: f64,
+}
+
+impl Default for TrailerModel {
+    fn default() -> Self {
+        TrailerModel {
+            hitch_offset: DEFAULT_HITCH_OFFSET,
+            trailer_length: DEFAULT_TRAILER_LENGTH,
+            jackknife_limit_degree: DEFAULT_JACKKNIFE_LIMIT_DEGREE,
+        }
+    }
+}
+
+/// Trailer state at one tractor sample. `theta` and `hitch_angle` are in radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailerSample {
+    pub hitch: (f64, f64),
+    pub axle: (f64, f64),
+    pub theta: f64,
+    pub hitch_angle: f64,
+    pub gear: Gear,
+    pub s: f64,
+    pub jackknifed: bool,
+}
+
+impl TrailerModel {
+    fn hitch_angle_rate(
+        &self,
+        tractor_theta: f64,
+        trailer_theta: f64,
+        gear: Gear,
+        kappa: f64,
+    ) -> f64 {
+        let g = match gear {
+            Gear::Forward => 1.0,
+            Gear::Backwards => -1.0,
+        };
+        let beta = tractor_theta - trailer_theta;
+        g / self.trailer_length * (beta.sin() - self.hitch_offset * kappa * beta.cos())
+    }
+
+    fn sample(&self, tractor: &PathSample, trailer_theta: f64) -> TrailerSample {
+        let hitch = (
+            tractor.x - self.hitch_offset * tractor.theta.cos(),
+            tractor.y - self.hitch_offset * tractor.theta.sin(),
+        );
+        let axle = (
+            hitch.0 - self.trailer_length * trailer_theta.cos(),
+            hitch.1 - self.trailer_length * trailer_theta.sin(),
+        );
+        let hitch_angle = utils::normalize_angle_rad(tractor.theta - trailer_theta);
+        TrailerSample {
+            hitch,
+            axle,
+            theta: trailer_theta,
+            hitch_angle,
+            gear: tractor.gear,
+            s: tractor.s,
+            jackknifed: hitch_angle.abs() > self.jackknife_limit_degree.to_radians(),
+        }
+    }
+
+    /// Propagates the trailer along the tractor samples, starting aligned with
+    /// the tractor. The tractor heading between two samples is reconstructed
+    /// from the curvature of the earlier one, which is exact for the
+    /// piecewise constant curvature of Reeds-Shepp paths.
+    pub fn propagate(&self, tractor: &[PathSample]) -> Vec<TrailerSample> {
+        self.propagate_from(tractor, 0.0)
+    }
+
+    /// [`propagate`](Self::propagate) with the trailer starting at
+    /// `initial_hitch_angle` radians to the tractor.
+    pub fn propagate_from(
+        &self,
+        tractor: &[PathSample],
+        initial_hitch_angle: f64,
+    ) -> Vec<TrailerSample> {
+        let Some(first) = tractor.first() else {
+            return Vec::new();
+        };
+        let mut trailer_theta = first.theta - initial_hitch_angle;
+        let mut samples = vec![self.sample(first, trailer_theta)];
+        for pair in tractor.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let ds = b.s - a.s;
+            let g = match a.gear {
+                Gear::Forward => 1.0,
+                Gear::Backwards => -1.0,
+            };
+            let n = (ds / INTEGRATION_STEP).ceil().max(1.0) as usize;
+            let h = ds / n as f64;
+            for i in 0..n {
+                let u = i as f64 * h;
+                let theta0 = a.theta + g * a.curvature * u;
+                let theta_mid = theta0 + g * a.curvature * h * 0.5;
+                let k1 = self.hitch_angle_rate(theta0, trailer_theta, a.gear, a.curvature);
+                let k2 = self.hitch_angle_rate(
+                    theta_mid,
+                    trailer_theta + 0.5 * h * k1,
+                    a.gear,
+                    a.curvature,
+                );
+                trailer_theta += h * k2;
+            }
+            trailer_theta = utils::normalize_angle_rad(trailer_theta);
+            samples.push(self.sample(b, trailer_theta));
+        }
+        samples
+    }
+}
+
+/// Corners of the trailer body, which spans from just behind the hitch to
+/// slightly past the axle.
+pub fn trailer_body_corners(sample: &TrailerSample, model: &TrailerModel) -> [(f64, f64); 4] {
+    let (c, s) = (sample.theta.cos(), sample.theta.sin());
+    let front = 0.15;
+    let back = model.trailer_length + 0.2;
+    let half_w = TRAILER_WIDTH / 2.0;
+    let at = |along: f64, side: f64| {
+        (
+            sample.hitch.0 - along * c - side * s,
+            sample.hitch.1 - along * s + side * c,
+        )
+    };
+    [
+        at(front, half_w),
+        at(back, half_w),
+        at(back, -half_w),
+        at(front, -half_w),
+    ]
+}
+
+/// Headline numbers for the trailer run along a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailerSummary {
+    pub max_hitch_angle_degree: f64,
+    pub jackknifed_samples: usize,
+    pub jackknifed_in_reverse: usize,
+}
+
+pub fn summarize(samples: &[TrailerSample]) -> TrailerSummary {
+    TrailerSummary {
+        max_hitch_angle_degree: samples
+            .iter()
+            .map(|t| t.hitch_angle.abs().to_degrees())
+            .fold(0.0, f64::max),
+        jackknifed_samples: samples.iter().filter(|t| t.jackknifed).count(),
+        jackknifed_in_reverse: samples
+            .iter()
+            .filter(|t| t.jackknifed && matches!(t.gear, Gear::Backwards))
+            .count(),
+    }
+}
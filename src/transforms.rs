@@ -0,0 +1,244 @@
+//! Small multiples of one `PATH_FNS` entry under all four reflect/timeflip
+//! transforms, side by side in a 2x2 grid of mini-canvases.
+//!
+//! All four canvases share one world-to-screen mapping fitted around the
+//! poses and every drawable path, so the shapes and lengths compare at a
+//! glance. The grid is laid out in logical pixels, sized to the room the
+//! frontend's layout gives it.
+
+use reeds_shepp_lib::{Path, Pose, path_length};
+
+use crate::candidates::{evaluate_family, reaches_goal, transform_name};
+use crate::path::{PathSample, sample_path};
+use crate::render::ScreenPoint;
+
+// --- Constants ---
+/// Reflect and timeflip flags of the four cells, row by row.
+pub const TRANSFORMS: [(bool, bool); 4] =
+    [(false, false), (true, false), (false, true), (true, true)];
+/// Side of a cell when there is room for it.
+pub const EXPLORER_CELL_SIZE: f32 = 150.0;
+pub const EXPLORER_GAP: f32 = 6.0;
+/// Room at the bottom of a cell for its label.
+const CELL_LABEL_HEIGHT: f32 = 18.0;
+const CELL_PADDING: f32 = 10.0;
+/// Sampling step of the mini paths, coarser than the main canvas.
+const CELL_SAMPLE_STEP: f64 = 0.05;
+
+/// Whether a transform of the family yields a usable path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feasibility {
+    Feasible,
+    /// The family has no solution for the transformed goal.
+    Infeasible,
+    /// A path exists but does not end on the goal.
+    MissesGoal,
+}
+
+impl Feasibility {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feasibility::Feasible => "feasible",
+            Feasibility::Infeasible => "infeasible",
+            Feasibility::MissesGoal => "misses goal",
+        }
+    }
+}
+
+/// The family under one transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformCell {
+    pub reflect: bool,
+    pub timeflip: bool,
+    pub path: Path,
+    pub feasibility: Feasibility,
+    pub samples: Vec<PathSample>,
+}
+
+impl TransformCell {
+    pub fn new(start: &Pose, end: &Pose, family: usize, reflect: bool, timeflip: bool) -> Self {
+        let path = evaluate_family(start, end, family, reflect, timeflip);
+        let feasibility = if path.is_empty() {
+            Feasibility::Infeasible
+        } else if reaches_goal(start, end, &path) {
+            Feasibility::Feasible
+        } else {
+            Feasibility::MissesGoal
+        };
+        let samples = sample_path(start, &path, CELL_SAMPLE_STEP);
+        TransformCell {
+            reflect,
+            timeflip,
+            path,
+            feasibility,
+            samples,
+        }
+    }
+
+    /// Name of the cell's transform, shortened to `both` for the combined
+    /// one so it fits under a small plot.
+    pub fn label(&self) -> &'static str {
+        match (self.reflect, self.timeflip) {
+            (true, true) => "both",
+            (reflect, timeflip) => transform_name(reflect, timeflip),
+        }
+    }
+
+    /// Length of the path, or `None` when there is none.
+    pub fn length(&self) -> Option<f64> {
+        (!self.path.is_empty()).then(|| path_length(&self.path))
+    }
+}
+
+/// All four transforms of `family` between two poses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformExplorer {
+    pub family: usize,
+    pub start: Pose,
+    pub end: Pose,
+    pub cells: Vec<TransformCell>,
+    /// World point shown in the middle of every cell.
+    pub center: (f64, f64),
+    /// Half the side of the world square every cell shows.
+    pub half_extent: f64,
+}
+
+impl TransformExplorer {
+    pub fn new(start: &Pose, end: &Pose, family: usize) -> Self {
+        let cells: Vec<TransformCell> = TRANSFORMS
+            .iter()
+            .map(|&(reflect, timeflip)| TransformCell::new(start, end, family, reflect, timeflip))
+            .collect();
+        let (center, half_extent) = bounding_square(start, end, &cells);
+        TransformExplorer {
+            family,
+            start: *start,
+            end: *end,
+            cells,
+            center,
+            half_extent,
+        }
+    }
+
+    /// Index of the feasible cell with the shortest path.
+    pub fn shortest(&self) -> Option<usize> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.feasibility == Feasibility::Feasible)
+            .min_by(|(_, a), (_, b)| path_length(&a.path).total_cmp(&path_length(&b.path)))
+            .map(|(i, _)| i)
+    }
+}
+
+/// Center and half side of the square around both poses and every sample of
+/// `cells`, at least one world unit across.
+pub fn bounding_square<'a>(
+    start: &Pose,
+    end: &Pose,
+    cells: impl IntoIterator<Item = &'a TransformCell>,
+) -> ((f64, f64), f64) {
+    let points = [(start.x, start.y), (end.x, end.y)].into_iter().chain(
+        cells
+            .into_iter()
+            .flat_map(|c| c.samples.iter().map(|s| (s.x, s.y))),
+    );
+    let (mut min, mut max) = (
+        (f64::INFINITY, f64::INFINITY),
+        (f64::NEG_INFINITY, f64::NEG_INFINITY),
+    );
+    for (x, y) in points {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    (
+        ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0),
+        ((max.0 - min.0).max(max.1 - min.1) / 2.0).max(0.5),
+    )
+}
+
+/// Screen position of a world point when the square at `center` with half
+/// side `half_extent` fills the square plot of side `size` at `origin`.
+pub fn square_to_screen(
+    origin: ScreenPoint,
+    size: f32,
+    (center, half_extent): ((f64, f64), f64),
+    (x, y): (f64, f64),
+) -> ScreenPoint {
+    let scale = size / (2.0 * half_extent) as f32;
+    (
+        origin.0 + size / 2.0 + (x - center.0) as f32 * scale,
+        origin.1 + size / 2.0 - (y - center.1) as f32 * scale,
+    )
+}
+
+/// Screen layout of the 2x2 grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExplorerLayout {
+    pub left: f32,
+    pub top: f32,
+    pub cell_size: f32,
+    pub gap: f32,
+}
+
+impl ExplorerLayout {
+    /// Grid with its top left corner at `(left, top)` and cells shrunk so
+    /// that it fits `max_width` by `max_height`.
+    pub fn fit(left: f32, top: f32, max_width: f32, max_height: f32) -> Self {
+        let fitting = (max_width.min(max_height) - EXPLORER_GAP) / 2.0;
+        ExplorerLayout {
+            left,
+            top,
+            cell_size: EXPLORER_CELL_SIZE.min(fitting).max(0.0),
+            gap: EXPLORER_GAP,
+        }
+    }
+    /// Top left corner of cell `index`, in `TRANSFORMS` order.
+    pub fn cell_origin(&self, index: usize) -> ScreenPoint {
+        let (col, row) = ((index % 2) as f32, (index / 2) as f32);
+        (
+            self.left + col * (self.cell_size + self.gap),
+            self.top + row * (self.cell_size + self.gap),
+        )
+    }
+
+    pub fn width(&self) -> f32 {
+        2.0 * self.cell_size + self.gap
+    }
+
+    pub fn contains(&self, p: ScreenPoint) -> bool {
+        (self.left..=self.left + self.width()).contains(&p.0)
+            && (self.top..=self.top + self.width()).contains(&p.1)
+    }
+
+    /// Cell under the screen point, if any.
+    pub fn cell_at(&self, p: ScreenPoint) -> Option<usize> {
+        (0..TRANSFORMS.len()).find(|&i| {
+            let (x, y) = self.cell_origin(i);
+            (x..=x + self.cell_size).contains(&p.0) && (y..=y + self.cell_size).contains(&p.1)
+        })
+    }
+
+    /// Screen position of a world point inside cell `index`.
+    pub fn to_screen(
+        &self,
+        explorer: &TransformExplorer,
+        index: usize,
+        point: (f64, f64),
+    ) -> ScreenPoint {
+        let (left, top) = self.cell_origin(index);
+        let plot = self.cell_size - CELL_LABEL_HEIGHT - 2.0 * CELL_PADDING;
+        square_to_screen(
+            (left + (self.cell_size - plot) / 2.0, top + CELL_PADDING),
+            plot,
+            (explorer.center, explorer.half_extent),
+            point,
+        )
+    }
+
+    /// Baseline of the label text of cell `index`.
+    pub fn label_origin(&self, index: usize) -> ScreenPoint {
+        let (left, top) = self.cell_origin(index);
+        (left + 6.0, top + self.cell_size - 5.0)
+    }
+}
@@ -0,0 +1,62 @@
+use crate::render::ScreenPoint;
+
+// --- Constants ---
+pub const DRAW_SCALE: f32 = 50.0;
+
+/// Maps world coordinates (y up, origin in the middle of the canvas) to
+/// screen pixels (y down, origin top-left). Screen pixels are logical; the
+/// window has `dpi_scale` physical pixels per logical one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    pub width: f32,
+    pub height: f32,
+    pub scale: f32,
+    pub dpi_scale: f32,
+}
+
+impl View {
+    pub fn new(width: f32, height: f32) -> Self {
+        View {
+            width,
+            height,
+            scale: DRAW_SCALE,
+            dpi_scale: 1.0,
+        }
+    }
+
+    /// View of a window measured in physical pixels. Layout and transforms
+    /// keep working in logical pixels, so the world appears at the same size
+    /// on any screen.
+    pub fn from_physical(width: f32, height: f32, dpi_scale: f32) -> Self {
+        View {
+            dpi_scale,
+            ..View::new(width / dpi_scale, height / dpi_scale)
+        }
+    }
+
+    /// Same world region at another pixel size, e.g. for exporting at a
+    /// higher resolution than the window. The visible area is kept whole, so a
+    /// different aspect ratio adds margin instead of cropping.
+    pub fn resized(&self, width: f32, height: f32) -> Self {
+        View {
+            width,
+            height,
+            scale: self.scale * (width / self.width).min(height / self.height),
+            dpi_scale: 1.0,
+        }
+    }
+
+    pub fn screen_to_world(&self, screen_pos: ScreenPoint) -> (f64, f64) {
+        (
+            ((screen_pos.0 - self.width / 2.0) / self.scale) as f64,
+            ((self.height / 2.0 - screen_pos.1) / self.scale) as f64,
+        )
+    }
+
+    pub fn world_to_screen(&self, world_x: f64, world_y: f64) -> ScreenPoint {
+        (
+            world_x as f32 * self.scale + self.width / 2.0,
+            self.height / 2.0 - world_y as f32 * self.scale,
+        )
+    }
+}
@@ -0,0 +1,106 @@
+use reeds_shepp_lib::{Pose, get_optimal_path};
+use visualize_reeds_shepp::cc::{
+    DEFAULT_MAX_STEERING_RATE, cc_end_pose, cc_length, cc_pieces, sample_cc_path, solve_cc_path,
+};
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::path::{PATH_SAMPLE_STEP, TURNING_RADIUS, path_arc_length};
+use visualize_reeds_shepp::state::State;
+
+fn pose(x: f64, y: f64, theta_degree: f64) -> Pose {
+    Pose { x, y, theta_degree }
+}
+
+fn heading_error_degree(a: &Pose, b: &Pose) -> f64 {
+    ((a.theta_degree - b.theta_degree + 540.0).rem_euclid(360.0) - 180.0).abs()
+}
+
+#[test]
+fn cc_paths_end_on_the_goal_with_continuous_curvature() {
+    let start = pose(0.5, -1.0, 20.0);
+    // A forward and a reversed CSC word, and two CCSC words with a cusp.
+    for end in [
+        pose(6.0, 3.0, 90.0),
+        pose(-4.0, 2.0, -45.0),
+        pose(3.0, -4.0, 160.0),
+        pose(-2.0, 1.0, 90.0),
+    ] {
+        let classic = get_optimal_path(start, end).unwrap();
+        for rate in [1.0, DEFAULT_MAX_STEERING_RATE, 8.0] {
+            let solved = solve_cc_path(&start, &end, &classic, rate)
+                .unwrap_or_else(|| panic!("no CC path to {:?} at rate {}", end, rate));
+            let pieces = cc_pieces(&solved, rate);
+            let reached = cc_end_pose(&start, &pieces);
+            assert!((reached.x - end.x).hypot(reached.y - end.y) < 1e-6);
+            assert!(heading_error_degree(&reached, &end) < 1e-6);
+
+            let samples = sample_cc_path(&start, &pieces, PATH_SAMPLE_STEP);
+            let last = samples.last().unwrap();
+            assert!((last.x - end.x).hypot(last.y - end.y) < 1e-6);
+            for w in samples.windows(2) {
+                let jump = (w[1].curvature - w[0].curvature).abs();
+                assert!(jump <= rate * (w[1].s - w[0].s) + 1e-9, "jump {}", jump);
+                assert!(w[1].curvature.abs() <= 1.0 / TURNING_RADIUS + 1e-12);
+                // Every junction and cusp is passed with straight wheels.
+                if w[1].segment != w[0].segment {
+                    assert_eq!(w[1].curvature, 0.0);
+                }
+            }
+            assert_eq!(samples[0].curvature, 0.0);
+            assert!(last.curvature.abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn cc_paths_are_never_shorter_than_the_classic_path() {
+    let start = pose(0.0, 0.0, 0.0);
+    let (mut solved, mut total) = (0, 0);
+    for x in -5..=5 {
+        for y in -5..=5 {
+            for theta in [-135.0, -45.0, 0.0, 60.0, 150.0] {
+                let end = pose(x as f64, y as f64, theta);
+                let classic = get_optimal_path(start, end).unwrap();
+                total += 1;
+                let Some(cc) = solve_cc_path(&start, &end, &classic, DEFAULT_MAX_STEERING_RATE)
+                else {
+                    continue;
+                };
+                solved += 1;
+                let length = cc_length(&cc_pieces(&cc, DEFAULT_MAX_STEERING_RATE));
+                assert!(length >= path_arc_length(&classic) - 1e-9, "{:?}", end);
+            }
+        }
+    }
+    // Only words with fewer than three usable elements or circles too close
+    // for the wider CC turns are left without a path.
+    assert!(solved * 10 > total * 8, "{}/{}", solved, total);
+}
+
+#[test]
+fn state_reports_the_overhead_of_a_cc_path_on_the_goal() {
+    let session = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+cc 1 2
+";
+    let mut state = State::new();
+    for frame in parse_session(session).unwrap() {
+        state.apply_frame(&frame);
+    }
+    let end = state.end_pose.unwrap();
+    let comparison = state.cc_comparison.unwrap();
+    assert!(comparison.length_overhead() >= 0.0);
+    let classic = path_arc_length(state.current_raw_path.as_ref().unwrap());
+    assert!((comparison.classic_length - classic).abs() < 1e-12);
+    let last = state.current_cc_samples.as_ref().unwrap().last().unwrap();
+    assert!((last.x - end.x).hypot(last.y - end.y) < 1e-6);
+}
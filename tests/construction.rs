@@ -0,0 +1,99 @@
+use reeds_shepp_lib::Pose;
+use visualize_reeds_shepp::construction::{
+    Construction, TangentKind, common_tangents, middle_circles, turning_circles,
+};
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::path::TURNING_RADIUS;
+use visualize_reeds_shepp::state::State;
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+#[test]
+fn tangents_touch_both_circles_at_right_angles() {
+    let a = (0.0, 0.0);
+    for (b, expected) in [((3.0, 1.0), 4), ((1.5, 0.5), 2), ((0.0, 0.0), 0)] {
+        let tangents = common_tangents(a, b);
+        assert_eq!(tangents.len(), expected, "{:?}", b);
+        for t in &tangents {
+            assert!((distance(t.from, a) - TURNING_RADIUS).abs() < 1e-9);
+            assert!((distance(t.to, b) - TURNING_RADIUS).abs() < 1e-9);
+            // The radius at each touching point is perpendicular to the line.
+            let dir = (t.to.0 - t.from.0, t.to.1 - t.from.1);
+            let ra = (t.from.0 - a.0, t.from.1 - a.1);
+            let rb = (t.to.0 - b.0, t.to.1 - b.1);
+            assert!((dir.0 * ra.0 + dir.1 * ra.1).abs() < 1e-9);
+            assert!((dir.0 * rb.0 + dir.1 * rb.1).abs() < 1e-9);
+        }
+        let inner = tangents
+            .iter()
+            .filter(|t| t.kind == TangentKind::Inner)
+            .count();
+        assert_eq!(inner, expected.saturating_sub(2));
+    }
+}
+
+#[test]
+fn middle_circles_touch_both_circles() {
+    let a = (0.0, 0.0);
+    let b = (3.0, 1.0);
+    let middle = middle_circles(a, b);
+    assert_eq!(middle.len(), 2);
+    for c in middle {
+        assert!((distance(c, a) - 2.0 * TURNING_RADIUS).abs() < 1e-9);
+        assert!((distance(c, b) - 2.0 * TURNING_RADIUS).abs() < 1e-9);
+    }
+    assert!(middle_circles(a, (4.5, 0.0)).is_empty());
+
+    let pose = Pose {
+        x: 1.0,
+        y: 2.0,
+        theta_degree: 90.0,
+    };
+    let [left, right] = turning_circles(&pose);
+    assert!(distance(left.center, (0.0, 2.0)) < 1e-9);
+    assert!(distance(right.center, (2.0, 2.0)) < 1e-9);
+}
+
+#[test]
+fn selected_paths_are_matched_to_their_construction() {
+    let place = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+construction 1
+";
+    let replay = |extra: &str| {
+        let mut state = State::new();
+        for frame in parse_session(&format!("{}{}", place, extra)).unwrap() {
+            state.apply_frame(&frame);
+        }
+        state
+    };
+    // Path 2 drives LSR along an inner tangent.
+    let csc = replay("mode 3\n");
+    let construction: &Construction = csc.construction.as_ref().unwrap();
+    assert_eq!(construction.circles.len(), 4);
+    assert_eq!(csc.construction_tangents_used.len(), 1);
+    let used = construction.tangents[csc.construction_tangents_used[0]];
+    assert_eq!(used.kind, TangentKind::Inner);
+    assert!(csc.construction_middle_used.is_empty());
+
+    // Path 3 turns LRL through a middle circle and has no straight.
+    let ccc = replay("mode 4\n");
+    assert!(ccc.construction_tangents_used.is_empty());
+    assert_eq!(ccc.construction_middle_used.len(), 1);
+
+    let off = replay("mode 3\nframe\nconstruction 0\n");
+    assert!(off.construction.is_none());
+    assert!(off.construction_tangents_used.is_empty());
+}
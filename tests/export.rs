@@ -0,0 +1,108 @@
+use std::fs::File;
+
+use reeds_shepp_lib::{Gear, Path, PathElement, Pose, Steering};
+use visualize_reeds_shepp::export::{
+    ExportError, export_gif, export_png, playback_arc_lengths, render_scene,
+};
+use visualize_reeds_shepp::path::{PATH_SAMPLE_STEP, path_end_pose, sample_path};
+use visualize_reeds_shepp::render::Rgba;
+use visualize_reeds_shepp::scene::{BG_COLOR, START_CAR_COLOR};
+use visualize_reeds_shepp::state::{AppState, State};
+use visualize_reeds_shepp::view::View;
+
+fn displaying_state() -> State {
+    let path: Path = vec![
+        PathElement {
+            param: 1.0,
+            steering: Steering::Left,
+            gear: Gear::Forward,
+        },
+        PathElement {
+            param: 1.5,
+            steering: Steering::Straight,
+            gear: Gear::Forward,
+        },
+    ];
+    let start = Pose {
+        x: -2.0,
+        y: -1.0,
+        theta_degree: 0.0,
+    };
+    let mut state = State::new();
+    state.app_state = AppState::DisplayingPaths;
+    state.start_pose = Some(start);
+    state.end_pose = Some(path_end_pose(&start, &path));
+    state.current_path_samples = Some(sample_path(&start, &path, PATH_SAMPLE_STEP));
+    state.current_raw_path = Some(path);
+    state
+}
+
+fn assert_color(actual: [f32; 3], expected: Rgba) {
+    let close = (actual[0] - expected.r).abs() < 1e-3
+        && (actual[1] - expected.g).abs() < 1e-3
+        && (actual[2] - expected.b).abs() < 1e-3;
+    assert!(close, "pixel {:?} is not {:?}", actual, expected);
+}
+
+#[test]
+fn rasterized_scene_matches_view_layout() {
+    let state = displaying_state();
+    let start = state.start_pose.unwrap();
+    for view in [
+        View::new(400.0, 300.0),
+        View::new(400.0, 300.0).resized(1000.0, 600.0),
+    ] {
+        let canvas = render_scene(&state, &view);
+        assert_eq!(
+            (canvas.width, canvas.height),
+            (view.width as u32, view.height as u32)
+        );
+        let (x, y) = view.world_to_screen(start.x, start.y);
+        assert_color(canvas.pixel(x as u32, y as u32), START_CAR_COLOR);
+        assert_color(canvas.pixel(10, 10), BG_COLOR);
+    }
+}
+
+#[test]
+fn png_export_decodes_at_chosen_size() {
+    let file = std::env::temp_dir().join("visualize_reeds_shepp_export_test.png");
+    let view = View::new(400.0, 300.0).resized(320.0, 200.0);
+    export_png(&displaying_state(), &view, &file).unwrap();
+    let decoder = png::Decoder::new(File::open(&file).unwrap());
+    let reader = decoder.read_info().unwrap();
+    assert_eq!((reader.info().width, reader.info().height), (320, 200));
+    std::fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn gif_export_has_one_frame_per_playback_step() {
+    let file = std::env::temp_dir().join("visualize_reeds_shepp_export_test.gif");
+    let view = View::new(160.0, 120.0);
+    let state = displaying_state();
+    let frames = export_gif(&state, &view, 10, &file).unwrap();
+    assert_eq!(frames, playback_arc_lengths(2.5, 10).len());
+
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(File::open(&file).unwrap()).unwrap();
+    let mut decoded = 0;
+    while decoder.read_next_frame().unwrap().is_some() {
+        decoded += 1;
+    }
+    assert_eq!(decoded, frames);
+    std::fs::remove_file(&file).unwrap();
+
+    let empty = State::new();
+    assert!(matches!(
+        export_gif(&empty, &view, 10, &file),
+        Err(ExportError::NoPath)
+    ));
+}
+
+#[test]
+fn playback_ends_exactly_on_the_goal() {
+    let s = playback_arc_lengths(2.5, 10);
+    assert_eq!(s.first(), Some(&0.0));
+    assert_eq!(s.last(), Some(&2.5));
+    assert!(s.windows(2).all(|w| w[1] > w[0]));
+}
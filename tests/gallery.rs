@@ -0,0 +1,84 @@
+use reeds_shepp_lib::{PATH_FNS, get_optimal_path, path_length};
+use visualize_reeds_shepp::gallery::GalleryLayout;
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::path::{parse_path_word, path_shape};
+use visualize_reeds_shepp::state::{DisplayMode, State};
+use visualize_reeds_shepp::transforms::{Feasibility, TRANSFORMS};
+
+const PLACE_AND_SHOW_GALLERY: &str = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+mode 17
+";
+
+fn replay(text: &str) -> State {
+    let mut state = State::new();
+    for frame in parse_session(text).unwrap() {
+        state.apply_frame(&frame);
+    }
+    state
+}
+
+#[test]
+fn gallery_tiles_every_family_and_highlights_the_optimum() {
+    let state = replay(PLACE_AND_SHOW_GALLERY);
+    assert_eq!(state.display_mode, DisplayMode::Gallery);
+    let gallery = state.gallery.as_ref().unwrap();
+    assert_eq!(gallery.thumbs.len(), PATH_FNS.len() * TRANSFORMS.len());
+    for (i, thumb) in gallery.thumbs.iter().enumerate() {
+        assert_eq!(thumb.family, i / TRANSFORMS.len());
+        let (reflect, timeflip) = TRANSFORMS[i % TRANSFORMS.len()];
+        assert_eq!(
+            (thumb.cell.reflect, thumb.cell.timeflip),
+            (reflect, timeflip)
+        );
+    }
+    let optimal = &gallery.thumbs[gallery.optimal.unwrap()].cell;
+    assert_eq!(optimal.feasibility, Feasibility::Feasible);
+    let (start, end) = (state.start_pose.unwrap(), state.end_pose.unwrap());
+    let expected = path_length(&get_optimal_path(start, end).unwrap());
+    assert!((optimal.length().unwrap() - expected).abs() < 1e-9);
+    assert_eq!(state.current_raw_path.as_ref(), Some(&optimal.path));
+    assert!(
+        gallery
+            .thumbs
+            .iter()
+            .any(|t| t.cell.feasibility == Feasibility::Infeasible)
+    );
+}
+
+#[test]
+fn layout_keeps_families_on_one_row_and_finds_thumbnails() {
+    let count = PATH_FNS.len() * TRANSFORMS.len();
+    let layout = GalleryLayout::fit(20.0, 80.0, 760.0, 620.0, count);
+    assert_eq!(layout.columns % TRANSFORMS.len(), 0);
+    let rows = count.div_ceil(layout.columns);
+    let (_, last_top) = layout.thumb_origin(count - 1);
+    assert!(last_top + layout.thumb_size <= 80.0 + 620.0 + 1e-3);
+    assert!(layout.thumb_origin(layout.columns - 1).0 + layout.thumb_size <= 20.0 + 760.0 + 1e-3);
+    assert!(rows > 1);
+    for i in [0, 5, count - 1] {
+        let (x, y) = layout.thumb_origin(i);
+        assert_eq!(layout.thumb_at((x + 2.0, y + 2.0), count), Some(i));
+    }
+    assert_eq!(layout.thumb_at((10.0, 10.0), count), None);
+
+    // A wide strip puts more families on a row.
+    let wide = GalleryLayout::fit(0.0, 0.0, 2000.0, 300.0, count);
+    assert!(wide.columns > layout.columns);
+}
+
+#[test]
+fn shapes_drop_the_params() {
+    let path = parse_path_word("L+0.8 S-1.2 R+1.57").unwrap();
+    assert_eq!(path_shape(&path), "L+S-R+");
+}
@@ -0,0 +1,119 @@
+use reeds_shepp_lib::{Pose, get_optimal_path, path_length, utils};
+use visualize_reeds_shepp::heuristic::{
+    HeuristicTable, LUT_HEADER_SIZE, LutAxis, LutError, LutGrid, exact_distance,
+};
+
+fn small_grid() -> LutGrid {
+    LutGrid {
+        x: "-3:3:13".parse().unwrap(),
+        y: "-2:2:9".parse().unwrap(),
+        theta_count: 12,
+    }
+}
+
+#[test]
+fn axes_parse_and_reject_bad_ranges() {
+    let axis: LutAxis = "-10:10:81".parse().unwrap();
+    assert_eq!(axis.step(), 0.25);
+    assert_eq!(axis.to_string(), "-10:10:81");
+    for bad in ["1:0:5", "0:1:1", "0:1", "a:1:5"] {
+        assert!(bad.parse::<LutAxis>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn lookups_are_exact_at_grid_points_and_threads_agree() {
+    let grid = small_grid();
+    let table = HeuristicTable::compute(grid, 3);
+    assert_eq!(table, HeuristicTable::compute(grid, 1));
+    for (ix, iy, it) in [(0, 0, 0), (4, 7, 5), (12, 8, 11), (6, 4, 3)] {
+        let goal = grid.pose(ix, iy, it);
+        let exact = exact_distance(goal);
+        assert!((table.lookup(&goal).unwrap() - exact).abs() < 1e-5);
+    }
+    // Theta wraps, so 180° reads the same slice as -180°.
+    let wrapped = Pose {
+        theta_degree: 180.0,
+        ..grid.pose(2, 3, 0)
+    };
+    assert!((table.lookup(&wrapped).unwrap() - table.get(2, 3, 0) as f64).abs() < 1e-5);
+    assert!(
+        table
+            .lookup(&Pose {
+                x: 3.5,
+                y: 0.0,
+                theta_degree: 0.0
+            })
+            .is_none()
+    );
+
+    // Distances between arbitrary poses are looked up in the start's frame.
+    let start = Pose {
+        x: 4.0,
+        y: -1.0,
+        theta_degree: 90.0,
+    };
+    let end = Pose {
+        x: 3.0,
+        y: 1.0,
+        theta_degree: 150.0,
+    };
+    let relative = utils::change_of_basis(&start, &end);
+    assert_eq!(table.distance(&start, &end), table.lookup(&relative));
+    let exact = path_length(&get_optimal_path(start, end).unwrap());
+    assert!((table.distance(&start, &end).unwrap() - exact).abs() < 0.5);
+}
+
+#[test]
+fn tables_round_trip_through_the_binary_format() {
+    let table = HeuristicTable::compute(small_grid(), 2);
+    let mut bytes = Vec::new();
+    table.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), LUT_HEADER_SIZE + small_grid().len() * 4);
+    assert_eq!(HeuristicTable::read_from(&mut &bytes[..]).unwrap(), table);
+
+    let truncated = &bytes[..bytes.len() - 4];
+    assert!(matches!(
+        HeuristicTable::read_from(&mut &truncated[..]),
+        Err(LutError::SizeMismatch { .. })
+    ));
+    let mut corrupt = bytes.clone();
+    corrupt[0] = b'X';
+    assert!(matches!(
+        HeuristicTable::read_from(&mut &corrupt[..]),
+        Err(LutError::BadMagic)
+    ));
+    let mut future = bytes.clone();
+    future[8] = 99;
+    assert!(matches!(
+        HeuristicTable::read_from(&mut &future[..]),
+        Err(LutError::UnsupportedVersion(99))
+    ));
+    // Axis counts whose product overflows are rejected, not multiplied.
+    let mut huge = bytes;
+    for at in [28, 48, 52] {
+        huge[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    }
+    assert!(matches!(
+        HeuristicTable::read_from(&mut &huge[..]),
+        Err(LutError::InvalidGrid)
+    ));
+}
+
+#[test]
+fn accuracy_report_tightens_with_resolution() {
+    let coarse = HeuristicTable::compute(small_grid(), 2);
+    let fine = HeuristicTable::compute(
+        LutGrid {
+            x: "-3:3:25".parse().unwrap(),
+            y: "-2:2:17".parse().unwrap(),
+            theta_count: 24,
+        },
+        2,
+    );
+    let (coarse, fine) = (coarse.accuracy(300, 7), fine.accuracy(300, 7));
+    assert_eq!(coarse.samples, 300);
+    assert!(coarse.mean_error <= coarse.rms_error && coarse.rms_error <= coarse.max_error);
+    assert!(fine.mean_error < coarse.mean_error);
+    assert!(fine.mean_error < 0.1, "{:?}", fine);
+}
@@ -0,0 +1,125 @@
+use reeds_shepp_lib::{Pose, get_optimal_path, path_length, utils};
+use visualize_reeds_shepp::lattice::{
+    LATTICE_HEADINGS, LatticeSettings, LatticeState, PrimitiveSet, write_primitives,
+};
+use visualize_reeds_shepp::obstacle::{Obstacle, pose_collides};
+use visualize_reeds_shepp::path::{PATH_SAMPLE_STEP, parse_path_word, path_end_pose, sample_path};
+
+fn primitives() -> PrimitiveSet {
+    PrimitiveSet::generate(LatticeSettings::default())
+}
+
+fn assert_reaches(actual: Pose, expected: Pose) {
+    let dtheta =
+        utils::normalize_angle_rad((actual.theta_degree - expected.theta_degree).to_radians());
+    assert!(
+        (actual.x - expected.x).hypot(actual.y - expected.y) < 1e-6 && dtheta.abs() < 1e-6,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn primitives_end_on_their_lattice_states() {
+    let set = primitives();
+    assert_eq!(set.by_heading.len(), LATTICE_HEADINGS);
+    for (heading, primitives) in set.by_heading.iter().enumerate() {
+        assert!(
+            !primitives.is_empty(),
+            "no primitives from heading {}",
+            heading
+        );
+        let origin = LatticeState {
+            ix: 0,
+            iy: 0,
+            heading,
+        };
+        for p in primitives {
+            assert!(p.length <= set.settings.max_primitive_length);
+            let target = set.pose(&LatticeState {
+                ix: p.dx,
+                iy: p.dy,
+                heading: p.end_heading,
+            });
+            assert_reaches(path_end_pose(&set.pose(&origin), &p.path), target);
+        }
+    }
+    let straight = set.by_heading[0]
+        .iter()
+        .find(|p| (p.dx, p.dy, p.end_heading) == (1, 0, 0))
+        .expect("one cell straight ahead");
+    assert!((straight.length - set.settings.spacing).abs() < 1e-9);
+}
+
+#[test]
+fn plans_reach_the_goal_around_obstacles() {
+    let set = primitives();
+    let start = Pose {
+        x: -3.0,
+        y: 0.0,
+        theta_degree: 0.0,
+    };
+    let end = Pose {
+        x: 3.0,
+        y: 0.0,
+        theta_degree: 0.0,
+    };
+    let free = set.plan(&start, &end, &[]).expect("open space is solvable");
+    assert_reaches(path_end_pose(&start, &free.path), end);
+    assert!((free.length - 6.0).abs() < 1e-9);
+
+    let wall = Obstacle::from_corners((-0.5, -1.5), (0.5, 1.5));
+    let solution = set
+        .plan(&start, &end, &[wall])
+        .expect("the wall can be avoided");
+    assert_reaches(path_end_pose(&start, &solution.path), end);
+    assert!(solution.length > free.length);
+    assert!(
+        sample_path(&start, &solution.path, PATH_SAMPLE_STEP)
+            .iter()
+            .all(|s| !pose_collides(&s.pose(), &[wall]))
+    );
+}
+
+#[test]
+fn off_lattice_poses_are_connected_exactly() {
+    let set = primitives();
+    let start = Pose {
+        x: 0.1,
+        y: -0.05,
+        theta_degree: 5.0,
+    };
+    let end = Pose {
+        x: 2.9,
+        y: 2.1,
+        theta_degree: 85.0,
+    };
+    let solution = set.plan(&start, &end, &[]).unwrap();
+    assert_reaches(path_end_pose(&start, &solution.path), end);
+    let optimal = path_length(&get_optimal_path(start, end).unwrap());
+    assert!(solution.length >= optimal - 1e-9);
+}
+
+#[test]
+fn exported_primitives_parse_back() {
+    let set = primitives();
+    let mut text = Vec::new();
+    write_primitives(&mut text, &set).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    let lines: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+    assert_eq!(lines.len(), set.len());
+    let first = &set.by_heading[0][0];
+    let fields: Vec<&str> = lines[0].splitn(6, ' ').collect();
+    assert_eq!(
+        fields[..4],
+        [
+            "0".to_string(),
+            first.dx.to_string(),
+            first.dy.to_string(),
+            first.end_heading.to_string()
+        ]
+    );
+    let path = parse_path_word(fields[5]).unwrap();
+    assert!((path_length(&path) - first.length).abs() < 1e-5);
+}
@@ -0,0 +1,72 @@
+use reeds_shepp_lib::Pose;
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::obstacle::{Obstacle, cspace_obstacle, footprint_collides};
+use visualize_reeds_shepp::state::State;
+
+fn inside_convex(polygon: &[(f64, f64)], p: (f64, f64)) -> bool {
+    (0..polygon.len()).all(|i| {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0) >= -1e-9
+    })
+}
+
+#[test]
+fn cspace_slice_matches_the_footprint_collision_test() {
+    let obstacle = Obstacle::from_corners((1.0, 2.0), (0.2, 0.5));
+    for theta_degree in [0.0, 30.0, 90.0, -135.0] {
+        let polygon = cspace_obstacle(&obstacle, theta_degree);
+        assert_eq!(
+            polygon.len(),
+            if theta_degree % 90.0 == 0.0 { 4 } else { 8 }
+        );
+        for i in 0..40 {
+            for j in 0..40 {
+                // Offset so that no probe lies exactly on a boundary.
+                let (x, y) = (-1.013 + i as f64 * 0.08, -0.507 + j as f64 * 0.1);
+                let pose = Pose { x, y, theta_degree };
+                assert_eq!(
+                    footprint_collides(&pose, &obstacle),
+                    inside_convex(&polygon, (x, y)),
+                    "({}, {}) at {}°",
+                    x,
+                    y,
+                    theta_degree
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn dragging_in_edit_mode_adds_an_obstacle_without_placing_poses() {
+    let mut state = State::new();
+    let session = "
+frame
+edit_obstacles 1
+frame
+pointer 2 1 1 1 0 0
+frame
+pointer 1 3 0 1 0 0
+frame
+pointer 1 3 0 0 1 0
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 0.01 0.01 0 1 0 0
+frame
+pointer 0.01 0.01 0 0 1 0
+";
+    for frame in parse_session(session).unwrap() {
+        state.apply_frame(&frame);
+    }
+    // The second drag is too small to keep.
+    assert_eq!(
+        state.obstacles,
+        vec![Obstacle {
+            min: (1.0, 1.0),
+            max: (2.0, 3.0),
+        }]
+    );
+    assert!(state.start_pose.is_none() && state.obstacle_draft.is_none());
+}
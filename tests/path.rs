@@ -0,0 +1,103 @@
+use reeds_shepp_lib::{Gear, Pose};
+use visualize_reeds_shepp::path::{
+    parse_path_word, path_arc_length, path_end_pose, sample_path, sample_path_at,
+};
+
+fn start() -> Pose {
+    Pose {
+        x: 1.0,
+        y: -2.0,
+        theta_degree: 30.0,
+    }
+}
+
+#[test]
+fn samples_lie_on_step_multiples_and_segment_boundaries() {
+    let path = parse_path_word("L+1.2 S-1.5 R-0.8").unwrap();
+    let step = 0.1;
+    let samples = sample_path(&start(), &path, step);
+    // Every multiple of the step plus the segment boundaries, once each.
+    let mut expected: Vec<f64> = (1..35).map(|k| k as f64 * step).collect();
+    expected.extend([0.0, 1.2, 2.7, 3.5]);
+    expected.sort_by(f64::total_cmp);
+    expected.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+    let actual: Vec<f64> = samples.iter().map(|p| p.s).collect();
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(&expected) {
+        assert!((a - e).abs() < 1e-9, "{} != {}", a, e);
+    }
+
+    // Consecutive samples are as far apart in space as in arc length, up to
+    // the chord shortening on arcs.
+    for w in samples.windows(2) {
+        let chord = (w[1].x - w[0].x).hypot(w[1].y - w[0].y);
+        assert!(chord <= w[1].s - w[0].s + 1e-9);
+        assert!(chord >= (w[1].s - w[0].s) * 0.99);
+    }
+
+    let last = samples.last().unwrap();
+    let end = path_end_pose(&start(), &path);
+    assert!((last.s - path_arc_length(&path)).abs() < 1e-12);
+    assert!((last.x - end.x).abs() < 1e-12 && (last.y - end.y).abs() < 1e-12);
+    assert!((last.pose().theta_degree - end.theta_degree).abs() < 1e-9);
+}
+
+#[test]
+fn non_positive_or_non_finite_steps_are_rejected() {
+    let path = parse_path_word("L+1 S+2").unwrap();
+    for step in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+        let result = std::panic::catch_unwind(|| sample_path(&start(), &path, step));
+        assert!(result.is_err(), "step {} was accepted", step);
+    }
+}
+
+#[test]
+fn requested_arc_lengths_are_clamped_to_the_path() {
+    let path = parse_path_word("L+1 S+2").unwrap();
+    let [before, after] = sample_path_at(&start(), &path, &[-5.0, 50.0])[..] else {
+        panic!("expected two samples");
+    };
+    assert_eq!(before.s, 0.0);
+    assert!((before.x - 1.0).abs() < 1e-12 && (before.y + 2.0).abs() < 1e-12);
+    assert_eq!(after.s, 3.0);
+    let end = path_end_pose(&start(), &path);
+    assert!((after.x - end.x).abs() < 1e-12 && (after.y - end.y).abs() < 1e-12);
+    assert!(sample_path_at(&start(), &Vec::new(), &[1.0]).is_empty());
+}
+
+#[test]
+fn boundary_samples_take_the_state_of_the_following_segment() {
+    // Cusp after the left arc, then a reversed straight and a reversed right arc.
+    let path = parse_path_word("L+1 S-2 R-1").unwrap();
+    let at = |s: f64| sample_path_at(&start(), &path, &[s])[0];
+
+    let before_cusp = at(1.0 - 1e-9);
+    assert_eq!(
+        (before_cusp.gear, before_cusp.curvature),
+        (Gear::Forward, 1.0)
+    );
+    let cusp = at(1.0);
+    assert_eq!(
+        (cusp.gear, cusp.curvature, cusp.segment),
+        (Gear::Backwards, 0.0, 1)
+    );
+    // The pose is continuous through the cusp.
+    assert!((cusp.x - before_cusp.x).hypot(cusp.y - before_cusp.y) < 1e-8);
+
+    let junction = at(3.0);
+    assert_eq!(
+        (junction.gear, junction.curvature, junction.segment),
+        (Gear::Backwards, -1.0, 2)
+    );
+    assert_eq!(at(2.999).curvature, 0.0);
+
+    let samples = sample_path(&start(), &path, 0.25);
+    let cusp_sample = samples.iter().find(|p| p.s == 1.0).unwrap();
+    assert_eq!(cusp_sample.gear, Gear::Backwards);
+    assert!(
+        samples
+            .iter()
+            .filter(|p| p.s < 1.0)
+            .all(|p| p.gear == Gear::Forward && p.curvature == 1.0)
+    );
+}
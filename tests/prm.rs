@@ -0,0 +1,120 @@
+use reeds_shepp_lib::{Pose, get_optimal_path, utils};
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::obstacle::{Obstacle, pose_collides};
+use visualize_reeds_shepp::path::{PATH_SAMPLE_STEP, path_end_pose, sample_path};
+use visualize_reeds_shepp::prm::{PrmSettings, Roadmap, reverse_path, sampling_bounds};
+use visualize_reeds_shepp::state::{DisplayMode, State};
+
+fn assert_reaches(actual: Pose, expected: Pose) {
+    assert!(
+        (actual.x - expected.x).abs() < 1e-6 && (actual.y - expected.y).abs() < 1e-6,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+    let dtheta =
+        utils::normalize_angle_rad((actual.theta_degree - expected.theta_degree).to_radians());
+    assert!(dtheta.abs() < 1e-6, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn reversed_paths_lead_back_to_the_start() {
+    let a = Pose {
+        x: -1.0,
+        y: 0.5,
+        theta_degree: 30.0,
+    };
+    let b = Pose {
+        x: 2.0,
+        y: -1.0,
+        theta_degree: 150.0,
+    };
+    let path = get_optimal_path(a, b).unwrap();
+    assert_reaches(path_end_pose(&a, &path), b);
+    assert_reaches(path_end_pose(&b, &reverse_path(&path)), a);
+}
+
+#[test]
+fn queries_route_around_the_obstacles() {
+    let wall = Obstacle::from_corners((-0.5, -3.0), (0.5, 3.0));
+    let start = Pose {
+        x: -3.0,
+        y: 0.0,
+        theta_degree: 0.0,
+    };
+    let end = Pose {
+        x: 3.0,
+        y: 0.0,
+        theta_degree: 0.0,
+    };
+    let settings = PrmSettings {
+        samples: 80,
+        neighbors: 8,
+        seed: 7,
+    };
+    let roadmap = Roadmap::build(sampling_bounds(&[start, end], &[wall]), &[wall], &settings);
+    assert_eq!(roadmap.nodes.len(), 80);
+    assert!(roadmap.nodes.iter().all(|n| !pose_collides(n, &[wall])));
+
+    let solution = roadmap
+        .query(&start, &end, &[wall], settings.neighbors)
+        .expect("the roadmap should lead around the wall");
+    assert!(!solution.via.is_empty());
+    assert_reaches(path_end_pose(&start, &solution.path), end);
+    let samples = sample_path(&start, &solution.path, PATH_SAMPLE_STEP);
+    assert!(samples.iter().all(|s| !pose_collides(&s.pose(), &[wall])));
+    // The wall forces a detour well beyond the straight-line distance.
+    assert!(solution.length > 6.5);
+}
+
+#[test]
+fn dragging_a_pose_requeries_the_existing_roadmap() {
+    let mut state = State::new();
+    let session = "
+frame
+obstacle -0.5 -1 0.5 1
+prm 60 6
+frame
+pointer -3 0 1 1 0 0
+frame
+pointer -2 0 0 1 0 0
+frame
+pointer -2 0 0 0 1 0
+frame
+pointer 3 0 1 1 0 0
+frame
+pointer 4 0 0 1 0 0
+frame
+pointer 4 0 0 0 1 0
+frame
+mode 14
+";
+    for frame in parse_session(session).unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert_eq!(state.display_mode, DisplayMode::Prm);
+    let roadmap = state.roadmap.clone().expect("the first query builds it");
+    assert_eq!(state.roadmap_samples.len(), roadmap.edges.len());
+    assert!(state.prm_solution.is_some());
+
+    // Drag the end body upwards; a rebuild would pick different bounds.
+    for frame in parse_session(
+        "frame\npointer 3 0 1 1 0 0\nframe\npointer 3 1.5 0 1 0 0\nframe\npointer 3 1.5 0 0 1 0\n",
+    )
+    .unwrap()
+    {
+        state.apply_frame(&frame);
+    }
+    assert_eq!(state.end_pose.unwrap().y, 1.5);
+    let after = state.roadmap.as_ref().unwrap();
+    assert_eq!(after.bounds, roadmap.bounds);
+    assert_eq!(after.nodes, roadmap.nodes);
+    let solution = state.prm_solution.as_ref().unwrap();
+    assert_reaches(
+        path_end_pose(&state.start_pose.unwrap(), &solution.path),
+        state.end_pose.unwrap(),
+    );
+
+    state.apply_frame(&parse_session("frame\nrebuild_roadmap\n").unwrap()[0]);
+    assert_ne!(state.roadmap.as_ref().unwrap().nodes, roadmap.nodes);
+}
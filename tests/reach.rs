@@ -0,0 +1,113 @@
+use reeds_shepp_lib::Pose;
+use visualize_reeds_shepp::heuristic::exact_distance;
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::reach::{ReachField, ReachRegion, reach_axis};
+use visualize_reeds_shepp::state::State;
+
+const ORIGIN: Pose = Pose {
+    x: 0.0,
+    y: 0.0,
+    theta_degree: 0.0,
+};
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn covers(region: &ReachRegion, p: (f64, f64)) -> bool {
+    region.triangles.iter().any(|&[a, b, c]| {
+        let d = [cross(a, b, p), cross(b, c, p), cross(c, a, p)];
+        d.iter().all(|&v| v >= -1e-12) || d.iter().all(|&v| v <= 1e-12)
+    })
+}
+
+#[test]
+fn regions_cover_exactly_the_reachable_grid_points() {
+    let axis = reach_axis(3.0, 0.25);
+    let field = ReachField::for_heading(axis, 0.0);
+    assert_eq!(field.values.len(), axis.count * axis.count);
+    let region = field.region(2.0, &ORIGIN);
+    for (ix, iy) in [(20, 12), (16, 12), (12, 16), (12, 20), (4, 12), (18, 14)] {
+        let p = (axis.value(ix), axis.value(iy));
+        let length = exact_distance(Pose {
+            x: p.0,
+            y: p.1,
+            theta_degree: 0.0,
+        });
+        assert_eq!(field.get(ix, iy), length);
+        // Keep away from the contour, where the bilinear cut is approximate.
+        if (length - 2.0).abs() > 0.1 {
+            assert_eq!(covers(&region, p), length <= 2.0, "{:?} at {}", p, length);
+        }
+    }
+    // Sideways costs far more than driving straight: the ball is not a disc.
+    assert!(covers(&region, (1.75, 0.0)));
+    assert!(!covers(&region, (0.0, 1.0)));
+    assert!(!region.outline.is_empty());
+}
+
+#[test]
+fn regions_follow_the_start_pose() {
+    let field = ReachField::for_heading(reach_axis(3.0, 0.25), 0.0);
+    let start = Pose {
+        x: 5.0,
+        y: -2.0,
+        theta_degree: 90.0,
+    };
+    let region = field.region(2.0, &start);
+    assert!(covers(&region, (5.0, -0.25)));
+    assert!(!covers(&region, (6.0, -2.0)));
+    let nested = field.regions(2.0, &start);
+    assert_eq!(nested.last().unwrap(), &region);
+    assert!(nested.windows(2).all(|w| w[0].level < w[1].level));
+}
+
+#[test]
+fn union_is_never_longer_than_a_single_heading() {
+    let axis = reach_axis(2.0, 0.25);
+    // Twelve headings every 30° include 0°.
+    let union = ReachField::union(axis, 12);
+    let straight = ReachField::for_heading(axis, 0.0);
+    assert!(union.heading_degree.is_none());
+    for (u, s) in union.values.iter().zip(&straight.values) {
+        assert!(*u <= *s + 1e-5, "{} > {}", u, s);
+    }
+    assert!(union.area_under(1.5) > straight.area_under(1.5));
+}
+
+#[test]
+fn budget_changes_reuse_the_field() {
+    let mut state = State::new();
+    let session = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+reach 1 2 heading
+";
+    for frame in parse_session(session).unwrap() {
+        state.apply_frame(&frame);
+    }
+    // The end faces +y while the start faces +x.
+    let field = state.reach_field().unwrap().clone();
+    assert_eq!(field.heading_degree, Some(90.0));
+    let small = state.reach_regions.last().unwrap().triangles.len();
+
+    for frame in parse_session("frame\nreach 1 4 heading\n").unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert_eq!(state.reach_field(), Some(&field));
+    assert!(state.reach_regions.last().unwrap().triangles.len() > small);
+
+    for frame in parse_session("frame\nreach 0 4 heading\n").unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert!(state.reach_regions.is_empty());
+}
@@ -0,0 +1,374 @@
+use reeds_shepp_lib::{Pose, get_optimal_path, path_length};
+use visualize_reeds_shepp::candidates::evaluate_family;
+use visualize_reeds_shepp::grid::SnapSettings;
+use visualize_reeds_shepp::input::{InputEvent, InputFrame, Shortcut, parse_session, write_frame};
+use visualize_reeds_shepp::lattice::LatticeSettings;
+use visualize_reeds_shepp::obstacle::Obstacle;
+use visualize_reeds_shepp::path::{parse_path_word, path_end_pose, path_word};
+use visualize_reeds_shepp::reach::ReachSettings;
+use visualize_reeds_shepp::sketch::SketchMetric;
+use visualize_reeds_shepp::stability::StabilitySettings;
+use visualize_reeds_shepp::state::{AppState, DisplayMode, State};
+
+const PLACE_BOTH_POSES: &str = "
+# start at the origin facing +x
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 1 0 0
+frame
+pointer 1 0 0 0 1 0
+# end at (4, 3) facing +y
+frame
+pointer 4 3 1 1 0 0
+frame
+pointer 4 4 0 1 0 0
+frame
+pointer 4 4 0 0 1 0
+";
+
+fn replay(text: &str) -> State {
+    let mut state = State::new();
+    for frame in parse_session(text).unwrap() {
+        state.apply_frame(&frame);
+    }
+    state
+}
+
+fn assert_pose(actual: Option<Pose>, x: f64, y: f64, theta_degree: f64) {
+    let p = actual.expect("pose should be placed");
+    assert!((p.x - x).abs() < 1e-9, "x: {} != {}", p.x, x);
+    assert!((p.y - y).abs() < 1e-9, "y: {} != {}", p.y, y);
+    assert!(
+        (p.theta_degree - theta_degree).abs() < 1e-9,
+        "theta: {} != {}",
+        p.theta_degree,
+        theta_degree
+    );
+}
+
+#[test]
+fn placing_both_poses_shows_the_shortest_path() {
+    let state = replay(PLACE_BOTH_POSES);
+    assert_eq!(state.app_state, AppState::DisplayingPaths);
+    assert_pose(state.start_pose, 0.0, 0.0, 0.0);
+    assert_pose(state.end_pose, 4.0, 3.0, 90.0);
+
+    let expected = get_optimal_path(state.start_pose.unwrap(), state.end_pose.unwrap()).unwrap();
+    let shown = state.current_raw_path.as_ref().unwrap();
+    assert!((path_length(shown) - path_length(&expected)).abs() < 1e-12);
+}
+
+#[test]
+fn dragging_the_end_body_moves_it_and_replans() {
+    let session = format!(
+        "{}
+frame
+pointer 4 3 1 1 0 0
+frame
+pointer -2 1.5 0 1 0 0
+frame
+pointer -2 1.5 0 0 1 0
+",
+        PLACE_BOTH_POSES
+    );
+    let state = replay(&session);
+    assert_pose(state.end_pose, -2.0, 1.5, 90.0);
+    assert_eq!(state.dragging_modify, None);
+
+    let expected = get_optimal_path(state.start_pose.unwrap(), state.end_pose.unwrap()).unwrap();
+    let shown = state.current_raw_path.as_ref().unwrap();
+    assert!((path_length(shown) - path_length(&expected)).abs() < 1e-12);
+}
+
+#[test]
+fn presses_over_the_ui_do_not_grab_a_car() {
+    let session = format!(
+        "{}
+frame
+pointer 4 3 1 1 0 1
+frame
+pointer 0 -3 0 1 0 0
+",
+        PLACE_BOTH_POSES
+    );
+    let state = replay(&session);
+    assert_eq!(state.dragging_modify, None);
+    assert_pose(state.end_pose, 4.0, 3.0, 90.0);
+}
+
+#[test]
+fn selecting_a_family_with_transforms_uses_that_variant() {
+    let session = format!(
+        "{}
+frame
+mode 4
+reflect 1
+timeflip 1
+",
+        PLACE_BOTH_POSES
+    );
+    let state = replay(&session);
+    assert_eq!(state.display_mode, DisplayMode::SinglePath(2));
+    let start = state.start_pose.unwrap();
+    let end = state.end_pose.unwrap();
+    let expected = evaluate_family(&start, &end, 2, true, true);
+    match &state.current_raw_path {
+        Some(shown) => assert!((path_length(shown) - path_length(&expected)).abs() < 1e-12),
+        None => assert!(expected.is_empty()),
+    }
+}
+
+#[test]
+fn reset_returns_to_placing_start() {
+    let state = replay(&format!("{}\nframe\nreset\n", PLACE_BOTH_POSES));
+    assert_eq!(state.app_state, AppState::PlacingStart);
+    assert!(state.start_pose.is_none());
+    assert!(state.current_raw_path.is_none());
+}
+
+#[test]
+fn typing_into_the_word_field_does_not_trigger_shortcuts() {
+    let typed = format!(
+        "{}
+frame
+word_focus 1
+frame
+key r
+word \"R\"
+frame
+key l
+word \"RL\"
+frame
+key g
+word \"RLG\"
+",
+        PLACE_BOTH_POSES
+    );
+    let state = replay(&typed);
+    assert_eq!(state.app_state, AppState::DisplayingPaths);
+    assert_pose(state.start_pose, 0.0, 0.0, 0.0);
+    assert_pose(state.end_pose, 4.0, 3.0, 90.0);
+    assert_eq!(state.custom_word, "RLG");
+    assert!(!state.accepts_shortcuts());
+
+    // Once the field loses focus the same key resets.
+    let state = replay(&format!("{}frame\nword_focus 0\nframe\nkey r\n", typed));
+    assert_eq!(state.app_state, AppState::PlacingStart);
+    assert!(state.start_pose.is_none());
+}
+
+#[test]
+fn recorded_frames_round_trip() {
+    let mut frames = parse_session(PLACE_BOTH_POSES).unwrap();
+    frames.push(InputFrame {
+        events: vec![
+            InputEvent::SetCc {
+                enabled: true,
+                max_steering_rate: 0.1 + 0.2,
+            },
+            InputEvent::SelectDisplayMode(1),
+            InputEvent::SetWordFocus(true),
+            InputEvent::Shortcut(Shortcut::ExportGif),
+            InputEvent::SetWordFocus(false),
+            InputEvent::SetCustomWord("L+0.8 S-1.2".to_string()),
+            // Typed text is replayed exactly, spacing and all.
+            InputEvent::SetCustomWord("  L+0.8   S-1.2\tR+1 ".to_string()),
+            InputEvent::SetCustomWord(String::new()),
+            InputEvent::SetCustomWord("\"quoted\" \\ word\n".to_string()),
+            InputEvent::SetSketch {
+                enabled: true,
+                metric: SketchMetric::Hausdorff,
+            },
+            InputEvent::AddObstacle(Obstacle::from_corners((-1.0, 0.5), (2.0, 1.25))),
+            InputEvent::ClearObstacles,
+            InputEvent::SetObstacleEditing(true),
+            InputEvent::SetCspace {
+                enabled: true,
+                heading_degree: -42.5,
+            },
+            InputEvent::SetPrm {
+                samples: 120,
+                neighbors: 5,
+            },
+            InputEvent::RebuildRoadmap,
+            InputEvent::SetLattice(LatticeSettings {
+                spacing: 0.75,
+                max_primitive_length: 2.5,
+            }),
+            InputEvent::SetHeadingSweep(true),
+            InputEvent::SetTransformExplorer(true),
+            InputEvent::SetTrace(true),
+            InputEvent::SetConstruction(true),
+            InputEvent::SetEndHeading(-42.5),
+            InputEvent::SetReach {
+                enabled: true,
+                settings: ReachSettings {
+                    budget: 2.5,
+                    union: true,
+                },
+            },
+            InputEvent::SetStability(StabilitySettings {
+                show_runner_up: true,
+                gap_threshold: 0.25,
+                hysteresis: true,
+                margin: 0.4,
+            }),
+            InputEvent::SetGrid {
+                visible: false,
+                snap: SnapSettings {
+                    to_grid: true,
+                    grid_spacing: 0.25,
+                    to_angles: true,
+                    angle_increment_degree: 22.5,
+                },
+            },
+        ],
+    });
+    let mut text = Vec::new();
+    for frame in &frames {
+        write_frame(&mut text, frame).unwrap();
+    }
+    let parsed = parse_session(std::str::from_utf8(&text).unwrap()).unwrap();
+    assert_eq!(parsed, frames);
+
+    let mut state = State::new();
+    for frame in &parsed {
+        state.apply_frame(frame);
+    }
+    assert_eq!(state.custom_word, "\"quoted\" \\ word\n");
+    let spaced = replay(&format!(
+        "{}\nframe\nword \" L+1  S+2 \"\n",
+        PLACE_BOTH_POSES
+    ));
+    assert_eq!(spaced.custom_word, " L+1  S+2 ");
+    assert!(parse_session("frame\nword L+1 S+2\n").is_err());
+}
+
+#[test]
+fn snapping_rounds_placement_and_headings() {
+    let state = replay(
+        "
+frame
+grid 1 1 0.5 1 15
+frame
+pointer 0.2 -0.3 1 1 0 0
+frame
+pointer 1.2 0.25 0 1 0 0
+frame
+pointer 1.2 0.25 0 0 1 0
+frame
+pointer 3.9 3.1 1 1 0 0
+frame
+pointer 3.9 4.1 0 1 0 0
+frame
+pointer 3.9 4.1 0 0 1 0
+",
+    );
+    // The start drag points at 32° and snaps to 30°; the end drag at 95° snaps to 90°.
+    assert_pose(state.start_pose, 0.0, -0.5, 30.0);
+    assert_pose(state.end_pose, 4.0, 3.0, 90.0);
+}
+
+#[test]
+fn clicking_a_hovered_candidate_selects_its_family() {
+    let mut state = replay(&format!("{}\nframe\nmode 1\n", PLACE_BOTH_POSES));
+    assert_eq!(state.display_mode, DisplayMode::AllPaths);
+    // Aim at the middle of the longest candidate, away from the cars.
+    let (target, _) = state
+        .all_candidates
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.length.total_cmp(&b.1.length))
+        .unwrap();
+    let samples = &state.all_paths_samples[target];
+    let p = samples[samples.len() / 2];
+
+    state.apply_frame(
+        &parse_session(&format!("frame\npointer {} {} 0 0 0 0", p.x, p.y)).unwrap()[0],
+    );
+    let hovered = state
+        .hovered_candidate
+        .expect("a candidate should be hovered");
+    let c = state.all_candidates[hovered].clone();
+
+    state.apply_frame(
+        &parse_session(&format!("frame\npointer {} {} 1 1 0 0", p.x, p.y)).unwrap()[0],
+    );
+    assert_eq!(state.display_mode, DisplayMode::SinglePath(c.family));
+    assert_eq!(state.combo_box_selected_index, c.family + 2);
+    assert_eq!(
+        (state.reflect_path, state.timeflip_path),
+        (c.reflect, c.timeflip)
+    );
+    assert_eq!(state.dragging_modify, None);
+    let shown = state.current_raw_path.as_ref().unwrap();
+    assert!((path_length(shown) - c.length).abs() < 1e-9);
+}
+
+fn pointer_frame(x: f64, y: f64, pressed: bool) -> InputFrame {
+    let p = pressed as u8;
+    parse_session(&format!("frame\npointer {} {} {} {} 0 0", x, y, p, p)).unwrap()[0].clone()
+}
+
+#[test]
+fn inspector_follows_the_pointer_until_pinned() {
+    let mut state = replay(PLACE_BOTH_POSES);
+    let samples = state.current_path_samples.clone().unwrap();
+    let mid = samples[samples.len() / 2];
+
+    state.apply_frame(&pointer_frame(mid.x, mid.y, false));
+    let inspected = state.inspected_sample.expect("pointer is on the path");
+    assert!((inspected.s - mid.s).abs() < 1e-6);
+    assert!((inspected.x - mid.x).abs() < 1e-6 && (inspected.y - mid.y).abs() < 1e-6);
+    assert!((inspected.theta - mid.theta).abs() < 1e-6);
+
+    state.apply_frame(&pointer_frame(mid.x, mid.y, true));
+    assert!(state.inspector_pinned);
+    state.apply_frame(&pointer_frame(-5.0, 5.0, false));
+    assert!((state.inspected_s.unwrap() - mid.s).abs() < 1e-6);
+
+    state.apply_frame(&pointer_frame(-5.0, 5.0, true));
+    assert!(!state.inspector_pinned);
+    assert!(state.inspected_sample.is_none());
+}
+
+#[test]
+fn custom_word_is_driven_from_the_start_and_compared_to_the_goal() {
+    let state = replay(&format!(
+        "{}\nframe\nword \"L+0.8 s-1.2 R+1.57\"\n",
+        PLACE_BOTH_POSES
+    ));
+    assert_eq!(state.custom_word_error, None);
+    let path = parse_path_word("L+0.8 S-1.2 R+1.57").unwrap();
+    let end = path_end_pose(&state.start_pose.unwrap(), &path);
+    let last = *state.custom_path_samples.as_ref().unwrap().last().unwrap();
+    assert!((last.x - end.x).abs() < 1e-9 && (last.y - end.y).abs() < 1e-9);
+
+    // The offset is the word's end seen from the goal, which faces +y at (4, 3).
+    let offset = state.custom_end_offset.unwrap();
+    assert!((offset.x - (end.y - 3.0)).abs() < 1e-9);
+    assert!((offset.y - (4.0 - end.x)).abs() < 1e-9);
+
+    let state = replay(&format!(
+        "{}\nframe\nword \"L+0.8 Q+1\"\n",
+        PLACE_BOTH_POSES
+    ));
+    assert!(state.custom_word_error.unwrap().contains("Q+1"));
+    assert!(state.custom_path_samples.is_none() && state.custom_end_offset.is_none());
+}
+
+#[test]
+fn path_words_parse_back_to_their_path() {
+    let path = parse_path_word("l-0.25 S+1 r+3.5").unwrap();
+    assert_eq!(path_word(&path), "L-0.25 S+1.00 R+3.50");
+    assert_eq!(parse_path_word(&path_word(&path)).unwrap(), path);
+    assert!(parse_path_word("L0.5").is_err());
+    assert!(parse_path_word("S+-1").is_err());
+}
+
+#[test]
+fn malformed_lines_report_their_line_number() {
+    let err = parse_session("frame\npointer 1 2 3\n").unwrap_err();
+    assert!(err.to_string().starts_with("session line 2:"), "{}", err);
+}
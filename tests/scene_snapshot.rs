@@ -0,0 +1,209 @@
+use std::fs;
+use std::path::PathBuf;
+
+use reeds_shepp_lib::{Gear, Path, PathElement, Pose, Steering};
+use visualize_reeds_shepp::path::{PATH_SAMPLE_STEP, path_end_pose, sample_path};
+use visualize_reeds_shepp::render::{Primitive, RecordingRenderer, TriangleMesh, WHITE};
+use visualize_reeds_shepp::scene::{
+    ARROW_SPACING, END_CAR_COLOR, FORWARD_PATH_COLOR, GRID_COLOR, LEFT_PATH_COLOR,
+    REVERSE_PATH_COLOR, RIGHT_PATH_COLOR, SELECTED_PATH_THICKNESS, START_CAR_COLOR,
+    STRAIGHT_PATH_COLOR, draw_grid, draw_scene, sample_color, stroke_selected_path,
+};
+use visualize_reeds_shepp::state::{AppState, PathColoring, State};
+use visualize_reeds_shepp::stroke::stroke_polyline;
+use visualize_reeds_shepp::view::View;
+
+/// Compares against `tests/snapshots/<name>.txt`. Set `UPDATE_SNAPSHOTS=1`
+/// to (re)write the file after an intended change; a missing file fails.
+fn assert_snapshot(name: &str, actual: &str) {
+    let file: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", name]
+        .iter()
+        .collect::<PathBuf>()
+        .with_extension("txt");
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, actual).unwrap();
+        return;
+    }
+    let Ok(expected) = fs::read_to_string(&file) else {
+        panic!(
+            "snapshot {} is missing; run the tests with UPDATE_SNAPSHOTS=1 to write it \
+             and commit the file",
+            file.display()
+        );
+    };
+    if expected != actual {
+        let first_diff = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(e, a)| e != a)
+            .unwrap_or(expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "snapshot {} differs at line {}:\n  expected: {:?}\n  actual:   {:?}",
+            file.display(),
+            first_diff + 1,
+            expected.lines().nth(first_diff),
+            actual.lines().nth(first_diff)
+        );
+    }
+}
+
+fn element(param: f64, steering: Steering, gear: Gear) -> PathElement {
+    PathElement {
+        param,
+        steering,
+        gear,
+    }
+}
+
+/// Left turn, reverse straight, right turn: covers arcs, a cusp and a line.
+fn displaying_state() -> State {
+    let path: Path = vec![
+        element(1.2, Steering::Left, Gear::Forward),
+        element(1.5, Steering::Straight, Gear::Backwards),
+        element(0.8, Steering::Right, Gear::Backwards),
+    ];
+    let start = Pose {
+        x: -1.0,
+        y: -0.5,
+        theta_degree: 20.0,
+    };
+    let mut state = State::new();
+    state.app_state = AppState::DisplayingPaths;
+    state.start_pose = Some(start);
+    state.end_pose = Some(path_end_pose(&start, &path));
+    state.current_path_samples = Some(sample_path(&start, &path, PATH_SAMPLE_STEP));
+    state.current_raw_path = Some(path);
+    state
+}
+
+#[test]
+fn single_path_scene_matches_snapshot() {
+    let mut renderer = RecordingRenderer::new();
+    draw_scene(&mut renderer, &View::new(400.0, 300.0), &displaying_state());
+    assert_snapshot("single_path_scene", &renderer.snapshot());
+}
+
+#[test]
+fn cars_are_drawn_last_at_their_poses() {
+    let state = displaying_state();
+    let view = View::new(400.0, 300.0);
+    let mut renderer = RecordingRenderer::new();
+    draw_scene(&mut renderer, &view, &state);
+
+    let bodies: Vec<_> = renderer
+        .primitives
+        .iter()
+        .filter_map(|p| match p {
+            Primitive::Rectangle { center, color, .. } => Some((*center, *color)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(bodies.len(), 2);
+    let start = state.start_pose.unwrap();
+    let end = state.end_pose.unwrap();
+    assert_eq!(
+        bodies[0],
+        (view.world_to_screen(start.x, start.y), START_CAR_COLOR)
+    );
+    assert_eq!(
+        bodies[1],
+        (view.world_to_screen(end.x, end.y), END_CAR_COLOR)
+    );
+    assert!(matches!(
+        renderer.primitives.last(),
+        Some(Primitive::Circle { .. })
+    ));
+}
+
+#[test]
+fn empty_state_without_grid_only_clears() {
+    let mut state = State::new();
+    state.grid_visible = false;
+    let mut renderer = RecordingRenderer::new();
+    draw_scene(&mut renderer, &View::new(400.0, 300.0), &state);
+    assert_eq!(renderer.primitives.len(), 1);
+    assert!(matches!(renderer.primitives[0], Primitive::Clear(_)));
+}
+
+#[test]
+fn grid_lines_sit_on_multiples_of_the_spacing() {
+    let view = View::new(400.0, 300.0);
+    let mut renderer = RecordingRenderer::new();
+    draw_grid(&mut renderer, &view, 0.5);
+    let vertical: Vec<f32> = renderer
+        .primitives
+        .iter()
+        .filter_map(|p| match p {
+            Primitive::Line { a, b, color, .. } if *color == GRID_COLOR && a.0 == b.0 => Some(a.0),
+            _ => None,
+        })
+        .collect();
+    // 50 px per unit: a line every 25 px across the 400 px wide canvas.
+    assert_eq!(vertical.len(), 17);
+    assert!(vertical.iter().all(|x| x % 25.0 == 0.0));
+    assert!(
+        renderer
+            .primitives
+            .iter()
+            .any(|p| matches!(p, Primitive::Text { text, .. } if text == "2 m"))
+    );
+}
+
+fn mesh_area(mesh: &TriangleMesh) -> f32 {
+    mesh.indices
+        .chunks(3)
+        .map(|t| {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| mesh.vertices[i as usize].position);
+            ((b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)).abs() / 2.0
+        })
+        .sum()
+}
+
+#[test]
+fn strokes_cover_each_piece_exactly_once() {
+    // An L-shaped polyline: a mitered corner adds no overlap, so the area is
+    // the two pieces' length times the thickness.
+    let mut mesh = TriangleMesh::new();
+    let points = [(0.0, 0.0), (50.0, 0.0), (100.0, 0.0), (100.0, 40.0)];
+    stroke_polyline(&mut mesh, &points, &[WHITE; 4], 4.0);
+    assert!((mesh_area(&mesh) - 140.0 * 4.0).abs() < 1e-3);
+
+    // Doubling back is a cusp: the strip ends and restarts without a miter.
+    let mut mesh = TriangleMesh::new();
+    let points = [(0.0, 0.0), (50.0, 0.0), (20.0, 0.0)];
+    stroke_polyline(&mut mesh, &points, &[WHITE; 3], 2.0);
+    assert!((mesh_area(&mesh) - 80.0 * 2.0).abs() < 1e-3);
+    assert_eq!(mesh.vertices.len(), 8);
+}
+
+#[test]
+fn selected_path_is_colored_by_gear_with_arrows_along_travel() {
+    let state = displaying_state();
+    let samples = state.current_path_samples.as_ref().unwrap();
+    let view = View::new(400.0, 300.0);
+    let mut mesh = TriangleMesh::new();
+    stroke_selected_path(&mut mesh, &view, samples, PathColoring::Gear);
+    let has = |c| mesh.vertices.iter().any(|v| v.color == c);
+    assert!(has(FORWARD_PATH_COLOR) && has(REVERSE_PATH_COLOR));
+
+    let mut mesh = TriangleMesh::new();
+    stroke_selected_path(&mut mesh, &view, samples, PathColoring::Steering);
+    let has = |c| mesh.vertices.iter().any(|v| v.color == c);
+    assert!(has(LEFT_PATH_COLOR) && has(STRAIGHT_PATH_COLOR) && has(RIGHT_PATH_COLOR));
+
+    // Arrowheads are the only triangles whose vertices are not shared.
+    let length = samples.last().unwrap().s;
+    let arrows = (length / ARROW_SPACING + 0.5).floor() as usize;
+    let mut plain = TriangleMesh::new();
+    let points: Vec<_> = samples
+        .iter()
+        .map(|p| view.world_to_screen(p.x, p.y))
+        .collect();
+    let colors: Vec<_> = samples
+        .iter()
+        .map(|p| sample_color(p, PathColoring::Steering))
+        .collect();
+    stroke_polyline(&mut plain, &points, &colors, SELECTED_PATH_THICKNESS);
+    assert_eq!(mesh.indices.len() - plain.indices.len(), 3 * arrows);
+}
@@ -0,0 +1,75 @@
+use reeds_shepp_lib::path_length;
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::sketch::{
+    SketchMetric, frechet_distance, hausdorff_distance, resample_polyline, sketch_poses,
+};
+use visualize_reeds_shepp::state::{AppState, DisplayMode, State};
+
+#[test]
+fn frechet_respects_the_order_of_travel_and_hausdorff_does_not() {
+    let forward = resample_polyline(&[(0.0, 0.0), (2.0, 0.0)], 0.1);
+    let backward: Vec<(f64, f64)> = forward.iter().rev().copied().collect();
+    assert!(hausdorff_distance(&forward, &backward) < 1e-9);
+    assert!((frechet_distance(&forward, &backward) - 2.0).abs() < 1e-9);
+
+    let shifted: Vec<(f64, f64)> = forward.iter().map(|&(x, y)| (x, y + 0.3)).collect();
+    assert!((frechet_distance(&forward, &shifted) - 0.3).abs() < 1e-9);
+    assert!((hausdorff_distance(&forward, &shifted) - 0.3).abs() < 1e-9);
+}
+
+#[test]
+fn resampling_spaces_points_evenly_and_keeps_both_ends() {
+    let points = resample_polyline(&[(0.0, 0.0), (1.0, 0.0), (1.0, 0.55)], 0.25);
+    let xs: Vec<(f64, f64)> = vec![
+        (0.0, 0.0),
+        (0.25, 0.0),
+        (0.5, 0.0),
+        (0.75, 0.0),
+        (1.0, 0.0),
+        (1.0, 0.25),
+        (1.0, 0.5),
+        (1.0, 0.55),
+    ];
+    assert_eq!(points.len(), xs.len());
+    for (p, q) in points.iter().zip(&xs) {
+        assert!(
+            (p.0 - q.0).abs() < 1e-9 && (p.1 - q.1).abs() < 1e-9,
+            "{:?} != {:?}",
+            p,
+            q
+        );
+    }
+}
+
+#[test]
+fn poses_follow_the_sketch_tangents() {
+    let (start, end) = sketch_poses(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (1.0, 2.0)]).unwrap();
+    assert_eq!((start.x, start.y, start.theta_degree), (0.0, 0.0, 0.0));
+    assert_eq!((end.x, end.y, end.theta_degree), (1.0, 2.0, 90.0));
+    assert!(sketch_poses(&[(0.0, 0.0), (0.1, 0.0)]).is_none());
+}
+
+#[test]
+fn a_straight_sketch_selects_the_straight_candidate() {
+    let mut session = String::from("frame\nsketch 1 frechet\nframe\npointer 0 0 1 1 0 0\n");
+    for i in 1..=30 {
+        session.push_str(&format!("frame\npointer {} 0 0 1 0 0\n", i as f64 * 0.1));
+    }
+    session.push_str("frame\npointer 3 0 0 0 1 0\n");
+    let mut state = State::new();
+    for frame in parse_session(&session).unwrap() {
+        state.apply_frame(&frame);
+    }
+
+    assert_eq!(state.app_state, AppState::DisplayingPaths);
+    let fit = state.sketch_fit.expect("the sketch should be fitted");
+    assert_eq!(fit.metric, SketchMetric::Frechet);
+    assert!(fit.distance < 1e-6, "{}", fit.distance);
+    let candidate = state.sketch_fit_candidate.clone().unwrap();
+    assert_eq!(
+        state.display_mode,
+        DisplayMode::SinglePath(candidate.family)
+    );
+    assert!((path_length(state.current_raw_path.as_ref().unwrap()) - 3.0).abs() < 1e-6);
+    assert!(state.sketch_distance.unwrap() < 1e-6);
+}
@@ -0,0 +1,98 @@
+use reeds_shepp_lib::{get_optimal_path, path_length};
+use visualize_reeds_shepp::candidates::{Candidate, reaching_candidates};
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::path::parse_path_word;
+use visualize_reeds_shepp::stability::{StabilitySettings, Variant, rank, same_maneuver};
+use visualize_reeds_shepp::state::State;
+
+fn candidate(family: usize, word: &str) -> Candidate {
+    let path = parse_path_word(word).unwrap();
+    Candidate {
+        family,
+        reflect: false,
+        timeflip: false,
+        length: path_length(&path),
+        path,
+    }
+}
+
+#[test]
+fn runner_up_skips_degenerate_copies_of_the_optimum() {
+    let a = parse_path_word("L+0 S+2 R+0").unwrap();
+    let b = parse_path_word("S+2").unwrap();
+    assert!(same_maneuver(&a, &b));
+    assert!(!same_maneuver(&b, &parse_path_word("S-2").unwrap()));
+    assert!(!same_maneuver(&b, &parse_path_word("S+2.1").unwrap()));
+
+    let candidates = [
+        candidate(0, "L+0 S+2 R+0"),
+        candidate(1, "S+2"),
+        candidate(2, "L+0.5 S+1.8"),
+    ];
+    let t = rank(&candidates, None, &StabilitySettings::default()).unwrap();
+    assert_eq!(t.optimal.family, 0);
+    assert_eq!(t.runner_up.as_ref().unwrap().family, 2);
+    assert!((t.gap().unwrap() - 0.3).abs() < 1e-9);
+    assert!(!t.is_near_switch(0.1));
+    assert!(t.is_near_switch(0.5));
+    assert!(!t.is_held());
+}
+
+#[test]
+fn hysteresis_holds_the_previous_variant_within_the_margin() {
+    let settings = StabilitySettings {
+        hysteresis: true,
+        margin: 0.2,
+        ..Default::default()
+    };
+    let previous = Some(Variant::of(&candidate(2, "S+1")));
+    let close = [candidate(1, "S+2"), candidate(2, "L+0.15 S+2")];
+    let t = rank(&close, previous, &settings).unwrap();
+    assert_eq!(t.optimal.family, 1);
+    assert_eq!(t.shown.family, 2);
+    assert!(t.is_held());
+
+    let far = [candidate(1, "S+2"), candidate(2, "L+0.25 S+2")];
+    assert_eq!(rank(&far, previous, &settings).unwrap().shown.family, 1);
+    let off = StabilitySettings {
+        hysteresis: false,
+        ..settings
+    };
+    assert_eq!(rank(&close, previous, &off).unwrap().shown.family, 1);
+}
+
+#[test]
+fn state_shows_the_runner_up_and_the_optimum_without_hysteresis() {
+    let mut state = State::new();
+    let session = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+stability 1 0.1 0 0.1
+";
+    for frame in parse_session(session).unwrap() {
+        state.apply_frame(&frame);
+    }
+    let (start, end) = (state.start_pose.unwrap(), state.end_pose.unwrap());
+    let t = state.transition.as_ref().unwrap();
+    let optimal = path_length(&get_optimal_path(start, end).unwrap());
+    assert!((t.optimal.length - optimal).abs() < 1e-9);
+    assert!(t.gap().unwrap() >= 0.0);
+    assert!(state.runner_up_samples.is_some());
+    assert_eq!(
+        reaching_candidates(&start, &end).len() > 1,
+        t.runner_up.is_some()
+    );
+    let shown = path_length(state.current_raw_path.as_ref().unwrap());
+    assert!((shown - optimal).abs() < 1e-9);
+    // The drawn path is the variant the gap describes.
+    assert_eq!(state.current_raw_path.as_ref(), Some(&t.shown.path));
+}
@@ -0,0 +1,103 @@
+use reeds_shepp_lib::{Pose, get_optimal_path, path_length};
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::state::State;
+use visualize_reeds_shepp::sweep::{
+    HEADING_SWEEP_STEPS, SWEEP_CHART_WIDTH, SweepChart, nearest_sample, sweep_end_heading,
+};
+
+const PLACE_AND_SWEEP: &str = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+heading_sweep 1
+";
+
+fn replay(text: &str) -> State {
+    let mut state = State::new();
+    for frame in parse_session(text).unwrap() {
+        state.apply_frame(&frame);
+    }
+    state
+}
+
+#[test]
+fn sweep_matches_the_optimal_length_at_every_heading() {
+    let start = Pose {
+        x: 0.0,
+        y: 0.0,
+        theta_degree: 0.0,
+    };
+    let end = Pose {
+        x: 3.0,
+        y: 2.0,
+        theta_degree: 90.0,
+    };
+    let samples = sweep_end_heading(&start, &end, 36);
+    assert_eq!(samples.len(), 37);
+    assert_eq!(samples[0].theta_degree, -180.0);
+    assert_eq!(samples[36].theta_degree, 180.0);
+    for s in &samples {
+        let goal = Pose {
+            theta_degree: s.theta_degree,
+            ..end
+        };
+        let optimal = path_length(&get_optimal_path(start, goal).unwrap());
+        assert!((s.length - optimal).abs() < 1e-9, "{:?}", s);
+    }
+    // The winner changes somewhere around the circle.
+    assert!(samples.windows(2).any(|w| !w[0].same_winner(&w[1])));
+    assert_eq!(nearest_sample(&samples, 88.0).unwrap().theta_degree, 90.0);
+}
+
+#[test]
+fn chart_positions_map_back_to_headings() {
+    let chart = SweepChart::fit(20.0, 110.0, 1000.0);
+    assert_eq!(chart.right(), 20.0 + SWEEP_CHART_WIDTH);
+    // A narrow window narrows the chart instead of running under the panel.
+    let narrow = SweepChart::fit(20.0, 110.0, 200.0);
+    assert_eq!((narrow.right(), narrow.bottom()), (220.0, chart.bottom()));
+    assert_eq!(narrow.heading_at((narrow.plot_right(), 150.0)), Some(180.0));
+    for theta in [-180.0, -45.0, 0.0, 120.0, 180.0] {
+        let p = (chart.x_for(theta), chart.plot_top() + 5.0);
+        assert_eq!(chart.heading_at(p), Some(theta));
+    }
+    // Left of the plot but inside the chart clamps to the first heading.
+    assert_eq!(
+        chart.heading_at((chart.left + 1.0, chart.top + 1.0)),
+        Some(-180.0)
+    );
+    assert_eq!(chart.heading_at((chart.left - 1.0, chart.top + 1.0)), None);
+}
+
+#[test]
+fn clicking_the_chart_turns_the_end_pose() {
+    let mut state = replay(PLACE_AND_SWEEP);
+    assert_eq!(state.heading_sweep.len(), HEADING_SWEEP_STEPS + 1);
+    let end = state.end_pose.unwrap();
+    assert!((end.theta_degree - 90.0).abs() < 1e-9);
+
+    for frame in parse_session("frame\nend_heading -135\n").unwrap() {
+        state.apply_frame(&frame);
+    }
+    let end = state.end_pose.unwrap();
+    assert!((end.theta_degree + 135.0).abs() < 1e-9);
+    assert_eq!((end.x, end.y), (3.0, 2.0));
+    let shown = path_length(state.current_raw_path.as_ref().unwrap());
+    let optimal = path_length(&get_optimal_path(state.start_pose.unwrap(), end).unwrap());
+    assert!((shown - optimal).abs() < 1e-9);
+    // Only the heading moved, so the sweep itself is unchanged.
+    assert_eq!(state.heading_sweep, replay(PLACE_AND_SWEEP).heading_sweep);
+
+    for frame in parse_session("frame\nheading_sweep 0\n").unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert!(state.heading_sweep.is_empty());
+}
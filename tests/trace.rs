@@ -0,0 +1,126 @@
+use reeds_shepp_lib::{PATH_FNS, Pose};
+use visualize_reeds_shepp::candidates::{evaluate_family, reaches_goal};
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::state::State;
+use visualize_reeds_shepp::trace::{FamilyTrace, Infeasibility};
+use visualize_reeds_shepp::transforms::TRANSFORMS;
+
+fn goals() -> impl Iterator<Item = Pose> {
+    (0..60).map(|i| {
+        let f = i as f64;
+        Pose {
+            x: (f * 0.37).sin() * 5.0,
+            y: (f * 0.73).cos() * 5.0,
+            theta_degree: (f * 37.0) % 360.0 - 180.0,
+        }
+    })
+}
+
+#[test]
+fn traces_explain_every_family_result() {
+    let start = Pose {
+        x: 0.5,
+        y: -1.0,
+        theta_degree: 30.0,
+    };
+    let mut reasons = [0; 3];
+    for end in goals() {
+        for family in 0..PATH_FNS.len() {
+            for (reflect, timeflip) in TRANSFORMS {
+                let trace = FamilyTrace::new(&start, &end, family, reflect, timeflip).unwrap();
+                let path = evaluate_family(&start, &end, family, reflect, timeflip);
+                assert_eq!(trace.path, path);
+                let (min, max) = trace.rho_range;
+                match trace.infeasibility {
+                    None => {
+                        assert!(reaches_goal(&start, &end, &path));
+                        assert!(trace.rho >= min - 1e-9 && trace.rho <= max + 1e-9);
+                        reasons[0] += 1;
+                    }
+                    Some(Infeasibility::TooClose { rho, .. }) => {
+                        assert!(path.is_empty() && rho < min);
+                        reasons[1] += 1;
+                    }
+                    Some(Infeasibility::TooFar { rho, .. }) => {
+                        assert!(path.is_empty() && rho > max);
+                        reasons[2] += 1;
+                    }
+                    Some(Infeasibility::NonFinite) => {}
+                    Some(Infeasibility::MissesGoal { position_error, .. }) => {
+                        assert!(!path.is_empty() && !reaches_goal(&start, &end, &path));
+                        assert!(position_error >= 0.0);
+                    }
+                }
+                // Raw params carry the path's magnitudes in the written order.
+                assert_eq!(trace.params.len(), path.len());
+                for (p, e) in trace.params.iter().zip(&path) {
+                    assert_eq!(p.value.abs(), e.param);
+                }
+            }
+        }
+    }
+    assert!(reasons.iter().all(|&n| n > 0), "{:?}", reasons);
+}
+
+#[test]
+fn relative_and_transformed_goals_follow_the_flags() {
+    let start = Pose {
+        x: 1.0,
+        y: 1.0,
+        theta_degree: 90.0,
+    };
+    let end = Pose {
+        x: 0.0,
+        y: 3.0,
+        theta_degree: 135.0,
+    };
+    let trace = FamilyTrace::new(&start, &end, 0, true, false).unwrap();
+    assert!((trace.relative.x - 2.0).abs() < 1e-9);
+    assert!((trace.relative.y - 1.0).abs() < 1e-9);
+    assert!((trace.relative.theta_degree - 45.0).abs() < 1e-9);
+    assert!((trace.transformed.y + 1.0).abs() < 1e-9);
+    assert!((trace.transformed.theta_degree + 45.0).abs() < 1e-9);
+    let timeflipped = FamilyTrace::new(&start, &end, 0, false, true).unwrap();
+    assert!((timeflipped.transformed.x + 2.0).abs() < 1e-9);
+    assert!(FamilyTrace::new(&start, &end, PATH_FNS.len(), false, false).is_none());
+
+    // The frame maps back onto the world: its origin is the start and its
+    // reflected y axis points to the start's right.
+    let (x, y) = trace.to_world(&start, (0.0, 1.0));
+    assert!((x - 2.0).abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn trace_is_only_kept_in_single_path_mode() {
+    let session = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+trace 1
+";
+    let mut state = State::new();
+    for frame in parse_session(session).unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert!(state.family_trace.is_none());
+    for frame in parse_session("frame\nmode 4\nreflect 1\n").unwrap() {
+        state.apply_frame(&frame);
+    }
+    let trace = state.family_trace.as_ref().unwrap();
+    assert_eq!(
+        (trace.family, trace.reflect, trace.timeflip),
+        (2, true, false)
+    );
+    for frame in parse_session("frame\ntrace 0\n").unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert!(state.family_trace.is_none());
+}
@@ -0,0 +1,66 @@
+use reeds_shepp_lib::{Gear, Pose};
+use visualize_reeds_shepp::path::{PATH_SAMPLE_STEP, parse_path_word, sample_path};
+use visualize_reeds_shepp::trailer::{TrailerModel, TrailerSample, summarize};
+
+fn run(word: &str, model: &TrailerModel, initial_hitch_angle: f64) -> Vec<TrailerSample> {
+    let start = Pose {
+        x: 1.0,
+        y: 2.0,
+        theta_degree: -30.0,
+    };
+    let path = parse_path_word(word).unwrap();
+    model.propagate_from(
+        &sample_path(&start, &path, PATH_SAMPLE_STEP),
+        initial_hitch_angle,
+    )
+}
+
+#[test]
+fn hitch_angle_decays_when_driving_straight_forward() {
+    let model = TrailerModel::default();
+    let samples = run("S+12", &model, 0.8);
+    assert!((samples[0].hitch_angle - 0.8).abs() < 1e-12);
+    for w in samples.windows(2) {
+        assert!(w[1].hitch_angle.abs() < w[0].hitch_angle.abs());
+        assert!(w[1].hitch_angle >= 0.0);
+    }
+    assert!(samples.last().unwrap().hitch_angle.abs() < 1e-3);
+    assert_eq!(summarize(&samples).jackknifed_samples, 0);
+    // The axle stays one trailer length behind the hitch.
+    for t in &samples {
+        let d = (t.hitch.0 - t.axle.0).hypot(t.hitch.1 - t.axle.1);
+        assert!((d - model.trailer_length).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn hitch_angle_converges_to_the_steady_state_on_an_arc() {
+    // With turning radius 1, `sin(b) - M cos(b) = L` in steady state.
+    let model = TrailerModel {
+        hitch_offset: 0.3,
+        trailer_length: 0.8,
+        jackknife_limit_degree: 90.0,
+    };
+    let steady =
+        model.hitch_offset.atan() + (model.trailer_length / model.hitch_offset.hypot(1.0)).asin();
+    let left = run("L+20", &model, 0.0);
+    assert!((left.last().unwrap().hitch_angle - steady).abs() < 1e-4);
+    let right = run("R+20", &model, 0.0);
+    assert!((right.last().unwrap().hitch_angle + steady).abs() < 1e-4);
+}
+
+#[test]
+fn reversing_with_a_large_hitch_angle_jackknifes() {
+    let model = TrailerModel::default();
+    let samples = run("S+1 S-4", &model, 0.6);
+    let first_flagged = samples.iter().find(|t| t.jackknifed).unwrap();
+    assert_eq!(first_flagged.gear, Gear::Backwards);
+    assert!(first_flagged.hitch_angle > model.jackknife_limit_degree.to_radians());
+    let summary = summarize(&samples);
+    assert!(summary.jackknifed_in_reverse > 0);
+    assert_eq!(summary.jackknifed_in_reverse, summary.jackknifed_samples);
+    assert!(summary.max_hitch_angle_degree > model.jackknife_limit_degree);
+
+    // Driving forward from the same start never trips the flag.
+    assert_eq!(summarize(&run("S+5", &model, 0.6)).jackknifed_samples, 0);
+}
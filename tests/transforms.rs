@@ -0,0 +1,114 @@
+use reeds_shepp_lib::{PATH_FNS, Pose, path_length};
+use visualize_reeds_shepp::candidates::{evaluate_family, reaches_goal};
+use visualize_reeds_shepp::input::parse_session;
+use visualize_reeds_shepp::state::{DisplayMode, State};
+use visualize_reeds_shepp::transforms::{
+    EXPLORER_CELL_SIZE, ExplorerLayout, Feasibility, TRANSFORMS, TransformExplorer,
+};
+
+#[test]
+fn cells_cover_every_transform_of_the_family() {
+    let start = Pose {
+        x: 0.0,
+        y: 0.0,
+        theta_degree: 0.0,
+    };
+    let end = Pose {
+        x: 3.0,
+        y: 2.0,
+        theta_degree: 90.0,
+    };
+    let mut seen = [false; 3];
+    for family in 0..PATH_FNS.len() {
+        let explorer = TransformExplorer::new(&start, &end, family);
+        assert_eq!(explorer.cells.len(), 4);
+        for (cell, &(reflect, timeflip)) in explorer.cells.iter().zip(&TRANSFORMS) {
+            assert_eq!((cell.reflect, cell.timeflip), (reflect, timeflip));
+            assert_eq!(
+                cell.path,
+                evaluate_family(&start, &end, family, reflect, timeflip)
+            );
+            let feasibility = match cell.length() {
+                None => Feasibility::Infeasible,
+                Some(_) if reaches_goal(&start, &end, &cell.path) => Feasibility::Feasible,
+                Some(_) => Feasibility::MissesGoal,
+            };
+            assert_eq!(cell.feasibility, feasibility);
+            seen[feasibility as usize] = true;
+            // Everything drawn fits the shared square.
+            for s in &cell.samples {
+                assert!((s.x - explorer.center.0).abs() <= explorer.half_extent + 1e-9);
+                assert!((s.y - explorer.center.1).abs() <= explorer.half_extent + 1e-9);
+            }
+        }
+        if let Some(i) = explorer.shortest() {
+            let best = path_length(&explorer.cells[i].path);
+            assert!(explorer.cells.iter().all(|c| {
+                c.feasibility != Feasibility::Feasible || path_length(&c.path) >= best
+            }));
+        }
+    }
+    assert_eq!(seen, [true; 3]);
+}
+
+#[test]
+fn layout_maps_screen_points_to_cells() {
+    let layout = ExplorerLayout::fit(20.0, 290.0, 500.0, 500.0);
+    assert_eq!(layout.cell_size, EXPLORER_CELL_SIZE);
+    // Short windows shrink the cells so the grid ends within the room given.
+    let short = ExplorerLayout::fit(20.0, 290.0, 500.0, 206.0);
+    assert_eq!(short.cell_size, 100.0);
+    assert!(short.contains((20.0, 496.0)) && !short.contains((20.0, 497.0)));
+    for i in 0..4 {
+        let (x, y) = layout.cell_origin(i);
+        assert_eq!(layout.cell_at((x + 1.0, y + 1.0)), Some(i));
+        assert!(layout.contains((x + 1.0, y + 1.0)));
+    }
+    // The gap between the cells belongs to none of them.
+    let (x, y) = layout.cell_origin(0);
+    assert_eq!(layout.cell_at((x + layout.cell_size + 1.0, y + 1.0)), None);
+    assert!(!layout.contains((layout.left - 1.0, layout.top)));
+}
+
+#[test]
+fn explorer_follows_the_displayed_family() {
+    let mut state = State::new();
+    let session = "
+frame
+pointer 0 0 1 1 0 0
+frame
+pointer 1 0 0 0 1 0
+frame
+pointer 3 2 1 1 0 0
+frame
+pointer 3 3 0 1 0 0
+frame
+pointer 3 3 0 0 1 0
+frame
+transforms 1
+";
+    for frame in parse_session(session).unwrap() {
+        state.apply_frame(&frame);
+    }
+    let shown = state.shown_variant.unwrap().family;
+    assert_eq!(state.transform_explorer.as_ref().unwrap().family, shown);
+
+    let labels = DisplayMode::combo_labels();
+    assert_eq!(labels.len(), 18);
+    assert_eq!(labels[DisplayMode::SinglePath(0).combo_index()], "Path 1");
+    assert_eq!(labels[DisplayMode::Gallery.combo_index()], "Gallery");
+    for index in 0..labels.len() {
+        state.set_display_mode_from_index(index);
+        assert_eq!(state.display_mode.combo_index(), index);
+    }
+    assert_eq!(DisplayMode::from_combo_index(labels.len()), None);
+    let mode = DisplayMode::SinglePath(4);
+    for frame in parse_session(&format!("frame\nmode {}\n", mode.combo_index())).unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert_eq!(state.transform_explorer.as_ref().unwrap().family, 4);
+    for frame in parse_session("frame\nmode 1\n").unwrap() {
+        state.apply_frame(&frame);
+    }
+    assert!(state.transform_explorer.is_none());
+}
@@ -0,0 +1,33 @@
+use visualize_reeds_shepp::view::{DRAW_SCALE, View};
+
+#[test]
+fn high_dpi_views_map_logical_pixels_to_the_world() {
+    // A 1600x900 logical window on a 1.5x screen.
+    let view = View::from_physical(2400.0, 1350.0, 1.5);
+    assert_eq!(
+        (view.width, view.height, view.dpi_scale),
+        (1600.0, 900.0, 1.5)
+    );
+    assert_eq!(view.scale, DRAW_SCALE);
+    assert_eq!(view.screen_to_world((800.0, 450.0)), (0.0, 0.0));
+
+    // A pointer at physical (1575, 450) is 250 logical pixels right of and
+    // 150 above the center, (5, 3) at the default scale.
+    let physical = (1575.0, 450.0);
+    let logical = (physical.0 / view.dpi_scale, physical.1 / view.dpi_scale);
+    let (x, y) = view.screen_to_world(logical);
+    assert!((x - 5.0).abs() < 1e-6 && (y - 3.0).abs() < 1e-6);
+    assert_eq!(view.world_to_screen(5.0, 3.0), logical);
+
+    for p in [(0.0, 0.0), (1599.0, 899.0), (123.5, 456.25)] {
+        let (x, y) = view.screen_to_world(p);
+        let back = view.world_to_screen(x, y);
+        assert!((back.0 - p.0).abs() < 1e-3 && (back.1 - p.1).abs() < 1e-3);
+    }
+
+    // The same logical window on a standard screen shows the same world.
+    let plain = View::new(1600.0, 900.0);
+    assert_eq!(plain.screen_to_world(logical), (x, y));
+    // Exports are in canvas pixels, whatever the screen.
+    assert_eq!(view.resized(800.0, 450.0).dpi_scale, 1.0);
+}